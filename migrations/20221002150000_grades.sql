@@ -0,0 +1,14 @@
+CREATE TABLE grade_categories (
+    id INTEGER PRIMARY KEY,
+    owner_bearer TEXT NOT NULL,
+    class_id TEXT NOT NULL,
+    category_id TEXT NOT NULL,
+    category_name TEXT NOT NULL,
+    weight REAL NOT NULL,
+    drop_lowest INTEGER NOT NULL
+);
+
+ALTER TABLE submissions ADD COLUMN category_id TEXT NOT NULL DEFAULT '';
+ALTER TABLE submissions ADD COLUMN graded INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE submissions ADD COLUMN mark REAL NOT NULL DEFAULT 0;
+ALTER TABLE submissions ADD COLUMN out_of REAL NOT NULL DEFAULT 0;
@@ -0,0 +1,11 @@
+CREATE TABLE notification_preferences (
+    id INTEGER PRIMARY KEY,
+    user_id TEXT NOT NULL,
+    email_grades INTEGER NOT NULL,
+    email_announcements INTEGER NOT NULL,
+    email_deadlines INTEGER NOT NULL
+);
+
+ALTER TABLE lessons ADD COLUMN lesson_id TEXT NOT NULL DEFAULT '';
+ALTER TABLE lessons ADD COLUMN due_date INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE lessons ADD COLUMN deadline_reminded INTEGER NOT NULL DEFAULT 0;
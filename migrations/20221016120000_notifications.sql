@@ -0,0 +1,11 @@
+CREATE TABLE notifications (
+    id INTEGER PRIMARY KEY,
+    notification_id TEXT NOT NULL,
+    user_id TEXT NOT NULL,
+    class_id TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    title TEXT NOT NULL,
+    description TEXT NOT NULL,
+    date INTEGER NOT NULL,
+    read INTEGER NOT NULL
+);
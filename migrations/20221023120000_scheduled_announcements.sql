@@ -0,0 +1,2 @@
+ALTER TABLE announcements ADD COLUMN publish_at INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE announcements ADD COLUMN published INTEGER NOT NULL DEFAULT 1;
@@ -0,0 +1,11 @@
+ALTER TABLE announcements ADD COLUMN edited_at INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE announcements ADD COLUMN pinned INTEGER NOT NULL DEFAULT 0;
+
+CREATE TABLE announcement_history (
+    id INTEGER PRIMARY KEY,
+    announcement_id TEXT NOT NULL,
+    title TEXT NOT NULL,
+    description TEXT NOT NULL,
+    attachment TEXT NOT NULL,
+    edited_at INTEGER NOT NULL
+);
@@ -0,0 +1,12 @@
+ALTER TABLE announcements ADD COLUMN locked INTEGER NOT NULL DEFAULT 0;
+
+CREATE TABLE announcement_comments (
+    id INTEGER PRIMARY KEY,
+    class_id TEXT NOT NULL,
+    announcement_id TEXT NOT NULL,
+    comment_id TEXT NOT NULL,
+    parent_id TEXT NOT NULL,
+    user_id TEXT NOT NULL,
+    content TEXT NOT NULL,
+    date INTEGER NOT NULL
+);
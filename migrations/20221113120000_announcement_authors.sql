@@ -0,0 +1,5 @@
+ALTER TABLE announcements ADD COLUMN author_id TEXT NOT NULL DEFAULT '';
+
+UPDATE announcements SET author_id=COALESCE(
+    (SELECT users.user_id FROM users WHERE users.bearer=announcements.owner_bearer), ''
+);
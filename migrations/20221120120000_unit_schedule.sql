@@ -0,0 +1,3 @@
+ALTER TABLE units ADD COLUMN unlock_at INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE units ADD COLUMN lock_at INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE units ADD COLUMN unlock_notified INTEGER NOT NULL DEFAULT 1;
@@ -0,0 +1,4 @@
+ALTER TABLE submissions ADD COLUMN lesson_id TEXT NOT NULL DEFAULT '';
+
+ALTER TABLE lessons ADD COLUMN solutions_release TEXT NOT NULL DEFAULT 'due_date';
+ALTER TABLE lessons ADD COLUMN solutions_released INTEGER NOT NULL DEFAULT 0;
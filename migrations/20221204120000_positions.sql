@@ -0,0 +1,9 @@
+ALTER TABLE units ADD COLUMN position INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE lessons ADD COLUMN position INTEGER NOT NULL DEFAULT 0;
+
+UPDATE units SET position=(
+    SELECT COUNT(*) FROM units AS u WHERE u.class_id=units.class_id AND u.id<units.id
+);
+UPDATE lessons SET position=(
+    SELECT COUNT(*) FROM lessons AS l WHERE l.unit_id=lessons.unit_id AND l.id<lessons.id
+);
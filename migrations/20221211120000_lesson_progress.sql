@@ -0,0 +1,11 @@
+CREATE TABLE lesson_progress (
+    id INTEGER PRIMARY KEY,
+    class_id TEXT NOT NULL,
+    lesson_id TEXT NOT NULL,
+    user_id TEXT NOT NULL,
+    completed INTEGER NOT NULL,
+    video_progress REAL NOT NULL,
+    updated_at INTEGER NOT NULL
+);
+
+CREATE UNIQUE INDEX lesson_progress_user_lesson ON lesson_progress (user_id, lesson_id);
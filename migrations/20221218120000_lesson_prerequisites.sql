@@ -0,0 +1,6 @@
+CREATE TABLE lesson_prerequisites (
+    id INTEGER PRIMARY KEY,
+    lesson_id TEXT NOT NULL,
+    required_lesson_id TEXT NOT NULL,
+    min_score REAL
+);
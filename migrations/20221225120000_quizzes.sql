@@ -0,0 +1 @@
+ALTER TABLE lessons ADD COLUMN work_type TEXT NOT NULL DEFAULT 'text';
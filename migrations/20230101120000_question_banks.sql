@@ -0,0 +1,20 @@
+CREATE TABLE bank_questions (
+    id INTEGER PRIMARY KEY,
+    class_id TEXT NOT NULL,
+    unit_id TEXT NOT NULL,
+    question_id TEXT NOT NULL,
+    question TEXT NOT NULL,
+    answer TEXT NOT NULL
+);
+
+CREATE TABLE quiz_draws (
+    id INTEGER PRIMARY KEY,
+    class_id TEXT NOT NULL,
+    lesson_id TEXT NOT NULL,
+    user_id TEXT NOT NULL,
+    questions TEXT NOT NULL,
+    answers TEXT NOT NULL,
+    created_at INTEGER NOT NULL
+);
+
+CREATE UNIQUE INDEX quiz_draws_lesson_user ON quiz_draws (lesson_id, user_id);
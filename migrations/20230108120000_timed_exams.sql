@@ -0,0 +1,24 @@
+ALTER TABLE lessons ADD COLUMN opens_at INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE lessons ADD COLUMN closes_at INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE lessons ADD COLUMN time_limit INTEGER NOT NULL DEFAULT 0;
+
+CREATE TABLE exam_attempts (
+    id INTEGER PRIMARY KEY,
+    class_id TEXT NOT NULL,
+    lesson_id TEXT NOT NULL,
+    user_id TEXT NOT NULL,
+    started_at INTEGER NOT NULL,
+    submitted_at INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE UNIQUE INDEX exam_attempts_lesson_user ON exam_attempts (lesson_id, user_id);
+
+CREATE TABLE lesson_extensions (
+    id INTEGER PRIMARY KEY,
+    class_id TEXT NOT NULL,
+    lesson_id TEXT NOT NULL,
+    user_id TEXT NOT NULL,
+    extra_time INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE UNIQUE INDEX lesson_extensions_lesson_user ON lesson_extensions (lesson_id, user_id);
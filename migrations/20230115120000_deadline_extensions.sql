@@ -0,0 +1,3 @@
+ALTER TABLE lesson_extensions ADD COLUMN due_date INTEGER;
+ALTER TABLE lesson_extensions ADD COLUMN closes_at INTEGER;
+ALTER TABLE lesson_extensions ADD COLUMN deadline_reminded INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1 @@
+ALTER TABLE classes ADD COLUMN archived INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1 @@
+ALTER TABLE lessons ADD COLUMN category_id TEXT NOT NULL DEFAULT '';
@@ -0,0 +1,2 @@
+UPDATE lessons SET lesson_id=lower(hex(randomblob(32))) WHERE lesson_id='';
+CREATE UNIQUE INDEX lessons_lesson_id ON lessons (lesson_id);
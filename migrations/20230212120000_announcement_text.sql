@@ -0,0 +1,7 @@
+UPDATE announcements SET title=json_extract(title, '$') WHERE json_valid(title) AND json_type(title)='text';
+UPDATE announcements SET description=json_extract(description, '$') WHERE json_valid(description) AND json_type(description)='text';
+UPDATE announcements SET attachment=json_extract(attachment, '$') WHERE json_valid(attachment) AND json_type(attachment)='text';
+UPDATE announcements SET author_name=json_extract(author_name, '$') WHERE json_valid(author_name) AND json_type(author_name)='text';
+UPDATE announcement_history SET title=json_extract(title, '$') WHERE json_valid(title) AND json_type(title)='text';
+UPDATE announcement_history SET description=json_extract(description, '$') WHERE json_valid(description) AND json_type(description)='text';
+UPDATE announcement_history SET attachment=json_extract(attachment, '$') WHERE json_valid(attachment) AND json_type(attachment)='text';
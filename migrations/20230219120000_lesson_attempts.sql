@@ -0,0 +1 @@
+ALTER TABLE lessons ADD COLUMN max_attempts INTEGER NOT NULL DEFAULT 1;
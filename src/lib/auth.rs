@@ -88,6 +88,15 @@ pub fn verify(bearer: &str, access_token: &str) -> bool {
     return false;
 }
 
+// The is_signed() function is used to check whether the
+// provided auth token was generated for the provided bearer
+// within the past 8 seconds. Unlike verify(), the token
+// isn't used up, so the endpoint can still verify it.
+pub fn is_signed(bearer: &str, access_token: &str) -> bool {
+    let time: u64 = utils::get_time().as_secs();
+    return (0..8).any(|i| access_token == sha256::digest(format!("{}:{}:{}", bearer, time - i, SUPER_SECRET_CODE)));
+}
+
 // The storage_handler() function is used to check whether
 // the provided auth token has already been used
 // within the past 8 seconds. This is function is
@@ -118,3 +127,32 @@ fn storage_handler(bearer_storage: &mut Vec<String>, access_token: &str, time: &
     // user is using an unauthorized token. Else, return true.
     return !bearer_storage.contains(&access_token.to_string());
 }
+
+// The DELETION_TOKEN_LIFETIME is how many seconds a
+// class deletion token stays valid for.
+static DELETION_TOKEN_LIFETIME: u64 = 600;
+
+// The deletion_token() function is used to generate the
+// confirmation token that the class owner must provide
+// to permanently delete their class. The token is tied
+// to the owner, the class and the current time window.
+pub fn deletion_token(bearer: &str, class_id: &str, window: u64) -> String {
+    let str_format: String = format!("{}:{}:delete:{}:{}", bearer, class_id, window, SUPER_SECRET_CODE);
+    return sha256::digest(str_format);
+}
+
+// The current_deletion_token() function is used to generate
+// the class deletion token for the current time window.
+pub fn current_deletion_token(bearer: &str, class_id: &str) -> String {
+    let window: u64 = utils::get_time().as_secs() / DELETION_TOKEN_LIFETIME;
+    return deletion_token(bearer, class_id, window);
+}
+
+// The verify_deletion_token() function is used to check
+// whether the provided class deletion token was generated
+// within the current or previous time window.
+pub fn verify_deletion_token(bearer: &str, class_id: &str, token: &str) -> bool {
+    let window: u64 = utils::get_time().as_secs() / DELETION_TOKEN_LIFETIME;
+    return token == deletion_token(bearer, class_id, window)
+        || token == deletion_token(bearer, class_id, window - 1);
+}
@@ -0,0 +1,192 @@
+// Library Usages
+use serde_json::Value;
+use std::collections::HashSet;
+
+// HOW CLASS BUNDLES WORK:
+//
+//      A bundle is a portable json copy of a single class, used for
+//      backing up a class or moving it to another server:
+//
+//      {
+//          "version": 1,
+//          "class": { "class_name": "...", "enable_whitelist": false },
+//          "grade_categories": [{ "category_id", "category_name", "weight", "drop_lowest" }],
+//          "units": [{
+//              "unit_id", "unit_name", "locked", "unlock_at", "lock_at",
+//              "lessons": [{
+//                  "lesson_id", "title", "description", "video", "work", "work_type",
+//                  "work_solutions", "due_date", "solutions_release", "solutions_released",
+//                  "opens_at", "closes_at", "time_limit", "category_id", "max_attempts",
+//                  "prerequisites": [{ "lesson_id", "min_score" }]
+//              }]
+//          }],
+//          "questions": [{ "question_id", "unit_id", "question", "answer" }],
+//          "announcements": [{
+//              "announcement_id", "author_id", "author_name", "title", "description",
+//              "attachment", "date", "publish_at", "published", "pinned", "locked"
+//          }],
+//          "submissions": [{                   (optional)
+//              "submission_id", "lesson_id", "user_id", "submission_date", "data",
+//              "category_id", "graded", "mark", "out_of"
+//          }]
+//      }
+//
+//      Units and lessons are listed in their set order. Attachments are
+//      stored within their announcement. Every id is only used to link the
+//      bundle's data together, and is replaced with a new id on import.
+//      Announcements are credited to the user importing the bundle.
+//      Submissions are linked to their student by user id, so only the
+//      students that exist on the importing server keep their work.
+
+// The BUNDLE_VERSION is the version of the bundle format
+// that's exported. Bundles with a different version can't
+// be imported.
+pub static BUNDLE_VERSION: i64 = 1;
+
+// The MAX_BUNDLE_SIZE is the largest bundle (in bytes)
+// that can be imported.
+pub static MAX_BUNDLE_SIZE: usize = 32 * 1024 * 1024;
+
+// The is_valid() function is used to check whether the provided
+// bundle can be imported. The bundle must be the current version,
+// every grade category needs an id and the same settings a created
+// category would, and every unit and lesson needs an id and a name. As they're linked together using the same ids,
+// no two grade categories, units or lessons can share an id.
+pub fn is_valid(bundle: &Value) -> bool {
+    if bundle["version"].as_i64() != Some(BUNDLE_VERSION) || bundle["class"]["class_name"].as_str().is_none() {
+        return false;
+    }
+
+    // Make sure the grade categories, units and lessons
+    // can be linked together
+    let mut ids: HashSet<&str> = HashSet::new();
+    if !array(bundle, "grade_categories").iter().all(|c| c["category_id"].as_str().is_some_and(|id| ids.insert(id)) && is_valid_category(c)) {
+        return false;
+    }
+    let units: &Vec<Value> = match bundle["units"].as_array() {
+        Some(units) => units,
+        None => return false,
+    };
+    return units.iter().all(|u| {
+        u["unit_id"].as_str().is_some_and(|id| ids.insert(id))
+            && u["unit_name"].as_str().is_some()
+            && u["lessons"].as_array().is_some_and(|lessons| lessons.iter().all(|l| {
+                l["lesson_id"].as_str().is_some_and(|id| ids.insert(id)) && l["title"].as_str().is_some()
+            }))
+    });
+}
+
+// The is_valid_category() function is used to check whether the
+// provided grade category has a name, and a weight and drop count
+// that aren't negative. Categories without a drop count don't
+// drop any work.
+fn is_valid_category(category: &Value) -> bool {
+    let drop_lowest: Option<i64> = match &category["drop_lowest"] {
+        Value::Null => Some(0),
+        drop_lowest => drop_lowest.as_i64()
+    };
+    return category["category_name"].as_str().is_some()
+        && category["weight"].as_f64().is_some_and(|weight| weight >= 0.0)
+        && drop_lowest.is_some_and(|drop_lowest| drop_lowest >= 0);
+}
+
+// The array() function is used to get the array under the
+// provided key of the bundle. Sections that are missing
+// from the bundle are treated as empty.
+pub fn array<'a>(bundle: &'a Value, key: &str) -> &'a [Value] {
+    return bundle[key].as_array().map_or(&[], |a| a.as_slice());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn valid_bundle() -> Value {
+        return json!({
+            "version": BUNDLE_VERSION,
+            "class": { "class_name": "Math" },
+            "grade_categories": [{ "category_id": "c1", "category_name": "Tests", "weight": 1.0 }],
+            "units": [{
+                "unit_id": "u1",
+                "unit_name": "Unit 1",
+                "lessons": [{ "lesson_id": "l1", "title": "Lesson 1" }, { "lesson_id": "l2", "title": "Lesson 2" }]
+            }]
+        });
+    }
+
+    #[test]
+    fn accepts_valid_bundles() {
+        assert!(is_valid(&valid_bundle()));
+
+        let mut bundle: Value = valid_bundle();
+        bundle.as_object_mut().unwrap().remove("grade_categories");
+        assert!(is_valid(&bundle));
+    }
+
+    #[test]
+    fn rejects_other_versions() {
+        let mut bundle: Value = valid_bundle();
+        bundle["version"] = json!(BUNDLE_VERSION + 1);
+        assert!(!is_valid(&bundle));
+        bundle.as_object_mut().unwrap().remove("version");
+        assert!(!is_valid(&bundle));
+    }
+
+    #[test]
+    fn rejects_missing_names_and_ids() {
+        let mut bundle: Value = valid_bundle();
+        bundle["class"] = json!({});
+        assert!(!is_valid(&bundle));
+
+        let mut bundle: Value = valid_bundle();
+        bundle["units"][0]["lessons"][1]["title"] = Value::Null;
+        assert!(!is_valid(&bundle));
+
+        let mut bundle: Value = valid_bundle();
+        bundle["grade_categories"][0]["category_id"] = json!(1);
+        assert!(!is_valid(&bundle));
+
+        let mut bundle: Value = valid_bundle();
+        bundle["units"] = Value::Null;
+        assert!(!is_valid(&bundle));
+    }
+
+    #[test]
+    fn rejects_invalid_categories() {
+        let mut bundle: Value = valid_bundle();
+        bundle["grade_categories"][0]["weight"] = json!(-1.0);
+        assert!(!is_valid(&bundle));
+
+        let mut bundle: Value = valid_bundle();
+        bundle["grade_categories"][0]["weight"] = Value::Null;
+        assert!(!is_valid(&bundle));
+
+        let mut bundle: Value = valid_bundle();
+        bundle["grade_categories"][0]["drop_lowest"] = json!(-2);
+        assert!(!is_valid(&bundle));
+
+        let mut bundle: Value = valid_bundle();
+        bundle["grade_categories"][0]["category_name"] = Value::Null;
+        assert!(!is_valid(&bundle));
+
+        let mut bundle: Value = valid_bundle();
+        bundle["grade_categories"][0]["drop_lowest"] = json!(2);
+        assert!(is_valid(&bundle));
+    }
+
+    #[test]
+    fn rejects_shared_ids() {
+        let mut bundle: Value = valid_bundle();
+        bundle["units"][0]["lessons"][1]["lesson_id"] = json!("l1");
+        assert!(!is_valid(&bundle));
+
+        let mut bundle: Value = valid_bundle();
+        bundle["units"][0]["lessons"][1]["lesson_id"] = json!("u1");
+        assert!(!is_valid(&bundle));
+
+        let mut bundle: Value = valid_bundle();
+        bundle["grade_categories"][0]["category_id"] = json!("l2");
+        assert!(!is_valid(&bundle));
+    }
+}
@@ -1,5 +1,5 @@
 use crate::lib::{
-    self, utils
+    self, utils, structs::{ScheduledAnnouncement, AnnouncementEdit}
 };
 
 // Database Implementation
@@ -7,7 +7,10 @@ impl lib::handlers::Database {
     // The insert_class_announcement() function is used to create a new announcement 
     // for the provided class_id. A unique announcement identifier is created before hand
     // so that if the announcement author wants to delete their announcement, they can. 
-    // Along with this, a post date is also inserted into the database.
+    // Along with this, a post date is also inserted into the database. If a future
+    // publish_at is provided, the announcement is hidden from students until then.
+    // Only the class owner can post announcements, and the author is always the
+    // signed in user rather than a name provided within the request body.
     pub async fn insert_class_announcement(
         &self, 
         bearer: &str, 
@@ -20,33 +23,46 @@ impl lib::handlers::Database {
             return false;
         }
 
-        // Get the request body variables
-        let author_name: String = match data.get("author_name") {
-            Some(name) => name.to_string(),
+        // Only the class owner can post announcements
+        if !self.is_class_owner(bearer, class_id).await {
+            return false;
+        }
+
+        // Get the author's user id and current name
+        let author_id: String = match self.get_user_id_by_bearer(bearer).await {
+            Some(r) => r,
             None => return false
         };
-        let title = match data.get("title") {
-            Some(title) => title.to_string(),
+        let author_name: String = match self.get_user_name_by_id(&author_id).await {
+            Some(r) => r,
             None => return false
         };
-        let description = match data.get("description") {
-            Some(description) => description.to_string(),
+
+        // Get the request body variables
+        let title: &str = match data["title"].as_str() {
+            Some(title) => title,
             None => return false
         };
-        let attachment = match data.get("attachment") {
-            Some(attachment) => attachment.to_string(),
+        let description: &str = match data["description"].as_str() {
+            Some(description) => description,
+            None => return false
+        };
+        let attachment: &str = match data["attachment"].as_str() {
+            Some(attachment) => attachment,
             None => return false
         };
 
-
-        // Get the current date of the announcement post
-        let date: i64 = utils::get_time().as_secs() as i64;
+        // Get the current date of the announcement post. If the
+        // announcement is scheduled, it's posted at the publish date.
+        let now: i64 = utils::get_time().as_secs() as i64;
+        let publish_at: i64 = data["publish_at"].as_i64().unwrap_or(now).max(now);
+        let published: i64 = (publish_at <= now) as i64;
 
         // Query the database, inserting the new announcement
         // along with all of it's data.
         let query = sqlx::query!(
-            "INSERT INTO announcements (owner_bearer, class_id, announcement_id, author_name, title, description, attachment, date) VALUES (?, ?, ?, ?, ?, ?, ?, ?)", 
-            bearer, class_id, announcement_id, author_name, title, description, attachment, date
+            "INSERT INTO announcements (owner_bearer, class_id, announcement_id, author_id, author_name, title, description, attachment, date, publish_at, published) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)", 
+            bearer, class_id, announcement_id, author_id, author_name, title, description, attachment, publish_at, publish_at, published
         ).execute(&self.conn).await;
 
         // Return query result
@@ -67,7 +83,141 @@ impl lib::handlers::Database {
         ).fetch_one(&self.conn).await;
 
         // Return whether valid query data has been obtained
-        return !query.is_err();
+        return query.is_ok();
+    }
+
+    // The update_class_announcement() function is used to edit the
+    // title, description, attachment or pinned status of the provided
+    // announcement. Before the announcement is changed, it's current
+    // version is stored within the announcement history. Any value
+    // not provided is left as is.
+    pub async fn update_class_announcement(
+        &self,
+        bearer: &str,
+        class_id: &str,
+        announcement_id: &str,
+        data: &serde_json::Value,
+    ) -> bool {
+        // Get the editor's user id
+        let author_id: String = match self.get_user_id_by_bearer(bearer).await {
+            Some(r) => r,
+            None => return false
+        };
+
+        // Get the current version of the announcement. Only
+        // the announcement's author can edit it.
+        let current = sqlx::query!(
+            "SELECT title, description, attachment, pinned FROM announcements WHERE announcement_id=? AND author_id=? AND class_id=?",
+            announcement_id, author_id, class_id
+        ).fetch_one(&self.conn).await;
+        let current = match current {
+            Ok(r) => r,
+            Err(_) => return false
+        };
+
+        // Get the request body variables
+        let title: &str = data["title"].as_str().unwrap_or(&current.title);
+        let description: &str = data["description"].as_str().unwrap_or(&current.description);
+        let attachment: &str = data["attachment"].as_str().unwrap_or(&current.attachment);
+        let pinned: i64 = data["pinned"].as_bool().map_or(current.pinned, |p| p as i64);
+        let edited_at: i64 = utils::get_time().as_secs() as i64;
+
+        // Only changing the content counts as an edit. Pinning
+        // an announcement doesn't add to it's history.
+        let edited: bool = title != current.title
+            || description != current.description
+            || attachment != current.attachment;
+
+        // Store the current version and update the announcement
+        // within a single transaction
+        let mut tx = match self.conn.begin().await {
+            Ok(tx) => tx,
+            Err(_) => return false
+        };
+        if edited {
+            let query = sqlx::query!(
+                "INSERT INTO announcement_history (announcement_id, title, description, attachment, edited_at) VALUES (?, ?, ?, ?, ?)",
+                announcement_id, current.title, current.description, current.attachment, edited_at
+            ).execute(&mut tx).await;
+            if query.is_err() {
+                return false;
+            }
+            let query = sqlx::query!(
+                "UPDATE announcements SET title=?, description=?, attachment=?, edited_at=? WHERE announcement_id=?",
+                title, description, attachment, edited_at, announcement_id
+            ).execute(&mut tx).await;
+            if query.is_err() {
+                return false;
+            }
+        }
+        let query = sqlx::query!(
+            "UPDATE announcements SET pinned=? WHERE announcement_id=?",
+            pinned, announcement_id
+        ).execute(&mut tx).await;
+
+        // Return query result
+        return match query {
+            Ok(_) => tx.commit().await.is_ok(),
+            Err(_) => false
+        };
+    }
+
+    // The get_announcement_history() function is used to get
+    // every previous version of the provided announcement,
+    // with the most recent edit first. Students can't see the
+    // history of an announcement that hasn't been published yet.
+    pub async fn get_announcement_history(
+        &self,
+        class_id: &str,
+        announcement_id: &str,
+        staff: bool
+    ) -> Option<Vec<serde_json::Value>> {
+        // Make sure the announcement belongs to the class
+        // and that the user is allowed to see it
+        let published: bool = match self.is_announcement_published(class_id, announcement_id).await {
+            Some(r) => r,
+            None => return None
+        };
+        if !staff && !published {
+            return None;
+        }
+
+        // Query the database
+        let query = sqlx::query_as!(AnnouncementEdit,
+            "SELECT title, description, attachment, edited_at FROM announcement_history WHERE announcement_id=? ORDER BY edited_at DESC, id DESC",
+            announcement_id
+        ).fetch_all(&self.conn).await;
+
+        // Return query result
+        return match query {
+            Err(_) => None,
+            Ok(r) => Some(r.iter().map(|e| {
+                serde_json::json!({
+                    "title": e.title,
+                    "description": e.description,
+                    "attachment": e.attachment,
+                    "edited_at": e.edited_at
+                })
+            }).collect())
+        };
+    }
+
+    // The is_announcement_published() function is used to
+    // check whether the provided announcement has been published
+    // to students. None is returned if the announcement isn't
+    // within the class.
+    pub async fn is_announcement_published(&self, class_id: &str, announcement_id: &str) -> Option<bool> {
+        // Query the database
+        let query = sqlx::query!(
+            "SELECT published FROM announcements WHERE announcement_id=? AND class_id=?",
+            announcement_id, class_id
+        ).fetch_one(&self.conn).await;
+
+        // Return query result
+        return match query {
+            Ok(r) => Some(r.published == 1),
+            Err(_) => None
+        };
     }
 
     // The delete_class_announcement() function is used
@@ -79,17 +229,67 @@ impl lib::handlers::Database {
         class_id: &str, 
         announcement_id: &str,
     ) -> bool {
+        // Get the user id of the announcement's author
+        let author_id: String = match self.get_user_id_by_bearer(bearer).await {
+            Some(r) => r,
+            None => return false
+        };
+
         // Query the database, deleting the announcement with
         // the incoming requests data.announcement_id
         let query = sqlx::query!(
-            "DELETE FROM announcements WHERE announcement_id=? AND owner_bearer=? AND class_id=?",
-            announcement_id, bearer, class_id
+            "DELETE FROM announcements WHERE announcement_id=? AND author_id=? AND class_id=?",
+            announcement_id, author_id, class_id
         ).execute(&self.conn).await;
 
-        // Return query result
+        // If the announcement was deleted, delete it's history and comments too
         return match query {
-            Ok(r) => r.rows_affected() > 0,
-            Err(_) => false
+            Ok(r) if r.rows_affected() > 0 => {
+                let _ = sqlx::query!(
+                    "DELETE FROM announcement_history WHERE announcement_id=?", announcement_id
+                ).execute(&self.conn).await;
+                let _ = sqlx::query!(
+                    "DELETE FROM announcement_comments WHERE announcement_id=?", announcement_id
+                ).execute(&self.conn).await;
+                true
+            },
+            _ => false
+        };
+    }
+
+    // The publish_due_announcements() function is used to make
+    // every scheduled announcement whose publish date has passed
+    // visible to students. Announcements within archived classes
+    // stay scheduled until the class is restored. The newly published
+    // announcements are returned so that the class's students can
+    // be notified.
+    pub async fn publish_due_announcements(&self) -> Vec<ScheduledAnnouncement> {
+        let now: i64 = utils::get_time().as_secs() as i64;
+
+        // Query the database for the announcements that have come due
+        let query = sqlx::query_as!(ScheduledAnnouncement,
+            "SELECT class_id, announcement_id, title, description, attachment FROM announcements WHERE published=0 AND publish_at<=? AND class_id NOT IN (SELECT class_id FROM classes WHERE archived=1)",
+            now
+        ).fetch_all(&self.conn).await;
+        let announcements: Vec<ScheduledAnnouncement> = match query {
+            Ok(r) => r,
+            Err(_) => return Vec::new()
         };
+
+        // Publish each announcement, keeping only the ones that
+        // haven't already been published by someone else
+        let mut published: Vec<ScheduledAnnouncement> = Vec::new();
+        for a in announcements {
+            let query = sqlx::query!(
+                "UPDATE announcements SET published=1 WHERE announcement_id=? AND published=0",
+                a.announcement_id
+            ).execute(&self.conn).await;
+            if let Ok(r) = query {
+                if r.rows_affected() > 0 {
+                    published.push(a);
+                }
+            }
+        }
+        return published;
     }
 }
@@ -0,0 +1,365 @@
+use crate::lib::{
+    self, utils, bundle, database::{cloning, lessons, questions}, structs::{GradeCategory, Lesson, Prerequisite, Unit}
+};
+use std::collections::{HashMap, HashSet};
+
+// Database Implementation
+impl lib::handlers::Database {
+    // The export_class_bundle() function is used by the class owner
+    // to export their class as a portable bundle. The bundle contains
+    // the class's settings, grade categories, units, lessons, question
+    // bank and announcements, along with the class's submissions if
+    // they're asked for. The class's whitelist is never exported.
+    pub async fn export_class_bundle(
+        &self,
+        bearer: &str,
+        class_id: &str,
+        include_submissions: bool
+    ) -> Option<serde_json::Value> {
+        // Only the class owner can export the class
+        if !self.is_class_owner(bearer, class_id).await {
+            return None;
+        }
+
+        // Get the class settings and grade categories
+        let class = sqlx::query!(
+            "SELECT class_name, enable_whitelist FROM classes WHERE class_id=?", class_id
+        ).fetch_one(&self.conn).await.ok()?;
+        let categories: Vec<GradeCategory> = sqlx::query_as!(GradeCategory,
+            "SELECT category_id, category_name, weight, drop_lowest FROM grade_categories WHERE class_id=? ORDER BY id",
+            class_id
+        ).fetch_all(&self.conn).await.ok()?;
+
+        // Get the units and their lessons in their set order
+        let units: Vec<Unit> = sqlx::query_as!(Unit,
+            "SELECT unit_id, unit_name, locked, unlock_at, lock_at FROM units WHERE class_id=? ORDER BY position, id",
+            class_id
+        ).fetch_all(&self.conn).await.ok()?;
+        let prerequisites: HashMap<String, Vec<Prerequisite>> = self.get_class_prerequisites(class_id).await;
+        let mut unit_json: Vec<serde_json::Value> = Vec::new();
+        for u in &units {
+            let lessons: Vec<Lesson> = self.get_unit_lessons(&u.unit_id).await;
+            unit_json.push(serde_json::json!({
+                "unit_id": u.unit_id,
+                "unit_name": u.unit_name,
+                "locked": u.locked == 1,
+                "unlock_at": u.unlock_at,
+                "lock_at": u.lock_at,
+                "lessons": lessons.iter().map(|l| lesson_json(l, prerequisites.get(&l.lesson_id))).collect::<Vec<serde_json::Value>>()
+            }));
+        }
+
+        // Get the announcements, including the scheduled ones
+        let announcements = sqlx::query!(
+            "SELECT announcement_id, author_id, author_name, title, description, attachment, date, publish_at, published, pinned, locked
+            FROM announcements WHERE class_id=? ORDER BY id",
+            class_id
+        ).fetch_all(&self.conn).await.ok()?;
+
+        // Create the bundle
+        let mut bundle: serde_json::Value = serde_json::json!({
+            "version": bundle::BUNDLE_VERSION,
+            "class": {
+                "class_name": class.class_name,
+                "enable_whitelist": class.enable_whitelist == 1
+            },
+            "grade_categories": categories.iter().map(|c| serde_json::json!({
+                "category_id": c.category_id,
+                "category_name": c.category_name,
+                "weight": c.weight,
+                "drop_lowest": c.drop_lowest
+            })).collect::<Vec<serde_json::Value>>(),
+            "units": unit_json,
+            "questions": self.get_question_bank(class_id).await.iter().map(|q| serde_json::json!({
+                "question_id": q.question_id,
+                "unit_id": q.unit_id,
+                "question": serde_json::from_str::<serde_json::Value>(&q.question).unwrap_or_default(),
+                "answer": serde_json::from_str::<serde_json::Value>(&q.answer).unwrap_or_default()
+            })).collect::<Vec<serde_json::Value>>(),
+            "announcements": announcements.iter().map(|a| serde_json::json!({
+                "announcement_id": a.announcement_id,
+                "author_id": a.author_id,
+                "author_name": a.author_name,
+                "title": a.title,
+                "description": a.description,
+                "attachment": a.attachment,
+                "date": a.date,
+                "publish_at": a.publish_at,
+                "published": a.published == 1,
+                "pinned": a.pinned == 1,
+                "locked": a.locked == 1
+            })).collect::<Vec<serde_json::Value>>()
+        });
+
+        // Add the submissions if they were asked for. Submissions are
+        // linked to their student by user id instead of their bearer.
+        if include_submissions {
+            let submissions = sqlx::query!(
+                "SELECT submissions.submission_id as \"submission_id!\", submissions.lesson_id as \"lesson_id!\", users.user_id as \"user_id!\",
+                submissions.submission_date as \"submission_date!\", submissions.data as \"data!\", submissions.category_id as \"category_id!\",
+                submissions.graded as \"graded!\", submissions.mark as \"mark!\", submissions.out_of as \"out_of!\"
+                FROM submissions
+                JOIN users ON users.bearer=submissions.submitter_bearer
+                WHERE submissions.class_id=? ORDER BY submissions.id",
+                class_id
+            ).fetch_all(&self.conn).await.ok()?;
+            bundle["submissions"] = submissions.iter().map(|s| serde_json::json!({
+                "submission_id": s.submission_id,
+                "lesson_id": s.lesson_id,
+                "user_id": s.user_id,
+                "submission_date": s.submission_date,
+                "data": s.data,
+                "category_id": s.category_id,
+                "graded": s.graded == 1,
+                "mark": s.mark,
+                "out_of": s.out_of
+            })).collect::<Vec<serde_json::Value>>().into();
+        }
+        return Some(bundle);
+    }
+
+    // The import_class_bundle() function is used to create a new
+    // class owned by the bearer from the provided bundle. Everything
+    // within the bundle is given a new id, and the links between them
+    // are remapped to the new ids. Submissions are only imported for
+    // students that exist on this server. Everything is imported
+    // within a single transaction, so either the whole bundle is
+    // imported or none of it is.
+    pub async fn import_class_bundle(&self, bearer: &str, class_id: &str, bundle: &serde_json::Value) -> bool {
+        // Make sure the bundle can be imported
+        if !bundle::is_valid(bundle) || self.class_exists(class_id).await {
+            return false;
+        }
+
+        // Get the bearer owner id and name
+        let owner_id: String = match self.get_user_id_by_bearer(bearer).await {
+            Some(r) => r,
+            None => return false
+        };
+        let author_name: String = self.get_user_name_by_id(&owner_id).await.unwrap_or_default();
+        let now: i64 = utils::get_time().as_secs() as i64;
+
+        // Generate the new id of every grade category, unit and lesson
+        let units: &[serde_json::Value] = bundle::array(bundle, "units");
+        let mut ids: HashMap<String, String> = HashMap::new();
+        let old_ids = bundle::array(bundle, "grade_categories").iter().map(|c| &c["category_id"])
+            .chain(units.iter().map(|u| &u["unit_id"]))
+            .chain(units.iter().flat_map(|u| bundle::array(u, "lessons")).map(|l| &l["lesson_id"]));
+        for id in old_ids.filter_map(|id| id.as_str()) {
+            ids.insert(id.to_string(), utils::generate_new_id(&format!("{}:{}", class_id, id)));
+        }
+        let unit_ids: HashSet<String> = units.iter()
+            .filter_map(|u| u["unit_id"].as_str().and_then(|id| ids.get(id)).cloned())
+            .collect();
+
+        // Begin a new transaction
+        let mut tx = match self.conn.begin().await {
+            Ok(tx) => tx,
+            Err(_) => return false
+        };
+
+        // Insert the class and it's settings
+        let class_name: &str = bundle["class"]["class_name"].as_str().unwrap_or("");
+        let enable_whitelist: i64 = bundle["class"]["enable_whitelist"].as_bool().unwrap_or(false) as i64;
+        let query = sqlx::query!(
+            "INSERT INTO classes (owner_bearer, owner_id, class_id, class_name, enable_whitelist) VALUES (?, ?, ?, ?, ?)",
+            bearer, owner_id, class_id, class_name, enable_whitelist
+        ).execute(&mut tx).await;
+        if query.is_err() {
+            return false;
+        }
+
+        // Insert the grade categories
+        for category in bundle::array(bundle, "grade_categories") {
+            let (category_id, category_name, weight) = match (
+                category["category_id"].as_str().and_then(|id| ids.get(id)),
+                category["category_name"].as_str(),
+                category["weight"].as_f64()
+            ) {
+                (Some(id), Some(name), Some(weight)) => (id, name, weight),
+                _ => return false
+            };
+            let drop_lowest: i64 = category["drop_lowest"].as_i64().unwrap_or(0);
+            let query = sqlx::query!(
+                "INSERT INTO grade_categories (owner_bearer, class_id, category_id, category_name, weight, drop_lowest) VALUES (?, ?, ?, ?, ?, ?)",
+                bearer, class_id, category_id, category_name, weight, drop_lowest
+            ).execute(&mut tx).await;
+            if query.is_err() {
+                return false;
+            }
+        }
+
+        // Insert the units and their lessons in their set order
+        for (position, unit) in units.iter().enumerate() {
+            let position: i64 = position as i64;
+            let unit_id: &String = &ids[unit["unit_id"].as_str().unwrap_or("")];
+            let unit_name: &str = unit["unit_name"].as_str().unwrap_or("");
+            let locked: i64 = unit["locked"].as_bool().unwrap_or(false) as i64;
+            let unlock_at: i64 = unit["unlock_at"].as_i64().unwrap_or(0);
+            let lock_at: i64 = unit["lock_at"].as_i64().unwrap_or(0);
+            let unlock_notified: i64 = (unlock_at <= now) as i64;
+            let query = sqlx::query!(
+                "INSERT INTO units (owner_bearer, class_id, unit_id, unit_name, locked, unlock_at, lock_at, unlock_notified, position) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                bearer, class_id, unit_id, unit_name, locked, unlock_at, lock_at, unlock_notified, position
+            ).execute(&mut tx).await;
+            if query.is_err() {
+                return false;
+            }
+
+            for (position, lesson) in bundle::array(unit, "lessons").iter().enumerate() {
+                let position: i64 = position as i64;
+                let lesson_id: &String = &ids[lesson["lesson_id"].as_str().unwrap_or("")];
+                let title: &str = lesson["title"].as_str().unwrap_or("");
+                let description: &str = lesson["description"].as_str().unwrap_or("");
+                let video: &str = lesson["video"].as_str().unwrap_or("");
+                let work_type: &str = lesson["work_type"].as_str().unwrap_or("text");
+                let work_solutions: &str = lesson["work_solutions"].as_str().unwrap_or("");
+                let due_date: i64 = lesson["due_date"].as_i64().unwrap_or(0);
+                let deadline_reminded: i64 = (due_date <= now) as i64;
+                let solutions_release: &str = lesson["solutions_release"].as_str().unwrap_or("due_date");
+                let solutions_released: i64 = lesson["solutions_released"].as_bool().unwrap_or(false) as i64;
+                let opens_at: i64 = lesson["opens_at"].as_i64().unwrap_or(0);
+                let closes_at: i64 = lesson["closes_at"].as_i64().unwrap_or(0);
+                let time_limit: i64 = lesson["time_limit"].as_i64().unwrap_or(0);
+                let max_attempts: i64 = lesson["max_attempts"].as_i64().unwrap_or(1);
+                let category_id: &str = lesson["category_id"].as_str().and_then(|id| ids.get(id)).map_or("", |id| id);
+
+                // Bank quizzes draw from the imported units, and
+                // can't draw from units outside of the bundle
+                let work: String = match work_type {
+                    "bank" => cloning::remap_draws(lesson["work"].as_str().unwrap_or(""), &ids),
+                    _ => lesson["work"].as_str().unwrap_or("").to_string()
+                };
+
+                // Make sure the lesson is valid
+                if !lessons::is_valid_work(work_type, &work, work_solutions)
+                    || !lessons::SOLUTION_RELEASES.contains(&solutions_release)
+                    || !lessons::is_valid_window(opens_at, closes_at, time_limit)
+                    || !lessons::is_valid_attempts(work_type, time_limit, max_attempts)
+                    || (work_type == "bank" && !lessons::is_valid_draws(&work, &unit_ids))
+                {
+                    return false;
+                }
+                let query = sqlx::query!(
+                    "INSERT INTO lessons (owner_bearer, unit_id, lesson_id, title, description, video, work, work_type, work_solutions, due_date, deadline_reminded, solutions_release, solutions_released, opens_at, closes_at, time_limit, category_id, max_attempts, position) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                    bearer, unit_id, lesson_id, title, description, video, work, work_type, work_solutions, due_date, deadline_reminded, solutions_release, solutions_released, opens_at, closes_at, time_limit, category_id, max_attempts, position
+                ).execute(&mut tx).await;
+                if query.is_err() {
+                    return false;
+                }
+
+                // Insert the lesson's prerequisites. Prerequisites on
+                // lessons that aren't within the bundle are skipped.
+                for prerequisite in bundle::array(lesson, "prerequisites") {
+                    let required_lesson_id: &String = match prerequisite["lesson_id"].as_str().and_then(|id| ids.get(id)) {
+                        Some(id) => id,
+                        None => continue
+                    };
+                    let min_score: Option<f64> = prerequisite["min_score"].as_f64();
+                    let query = sqlx::query!(
+                        "INSERT INTO lesson_prerequisites (lesson_id, required_lesson_id, min_score) VALUES (?, ?, ?)",
+                        lesson_id, required_lesson_id, min_score
+                    ).execute(&mut tx).await;
+                    if query.is_err() {
+                        return false;
+                    }
+                }
+            }
+        }
+
+        // Insert the question bank. Every question is given a new id.
+        for q in bundle::array(bundle, "questions") {
+            let (question_id, unit_id) = match (
+                q["question_id"].as_str(),
+                q["unit_id"].as_str().and_then(|id| ids.get(id))
+            ) {
+                (Some(question_id), Some(unit_id)) => (question_id, unit_id),
+                _ => return false
+            };
+            if !q["question"].is_object() || !questions::is_valid_question(question_id, &q["question"], &q["answer"]) {
+                return false;
+            }
+            let (question, answer): (String, String) = (q["question"].to_string(), q["answer"].to_string());
+            let question_id: String = utils::generate_new_id(&format!("{}:{}", class_id, question_id));
+            let query = sqlx::query!(
+                "INSERT INTO bank_questions (class_id, unit_id, question_id, question, answer) VALUES (?, ?, ?, ?, ?)",
+                class_id, unit_id, question_id, question, answer
+            ).execute(&mut tx).await;
+            if query.is_err() {
+                return false;
+            }
+        }
+
+        // Insert the announcements. As the bundle's authors
+        // might not exist on this server, the announcements
+        // are credited to the bearer.
+        for a in bundle::array(bundle, "announcements") {
+            let announcement_id: String = utils::generate_new_id(&format!("{}:{}", class_id, a["announcement_id"]));
+            let title: &str = a["title"].as_str().unwrap_or("");
+            let description: &str = a["description"].as_str().unwrap_or("");
+            let attachment: &str = a["attachment"].as_str().unwrap_or("");
+            let date: i64 = a["date"].as_i64().unwrap_or(now);
+            let publish_at: i64 = a["publish_at"].as_i64().unwrap_or(date);
+            let published: i64 = a["published"].as_bool().unwrap_or(publish_at <= now) as i64;
+            let pinned: i64 = a["pinned"].as_bool().unwrap_or(false) as i64;
+            let locked: i64 = a["locked"].as_bool().unwrap_or(false) as i64;
+            let query = sqlx::query!(
+                "INSERT INTO announcements (owner_bearer, class_id, announcement_id, author_id, author_name, title, description, attachment, date, publish_at, published, pinned, locked) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                bearer, class_id, announcement_id, owner_id, author_name, title, description, attachment, date, publish_at, published, pinned, locked
+            ).execute(&mut tx).await;
+            if query.is_err() {
+                return false;
+            }
+        }
+
+        // Insert the submissions of the students that exist on this server
+        for s in bundle::array(bundle, "submissions") {
+            let submission_id: String = utils::generate_new_id(&format!("{}:{}", class_id, s["submission_id"]));
+            let user_id: &str = s["user_id"].as_str().unwrap_or("");
+            let lesson_id: &str = s["lesson_id"].as_str().and_then(|id| ids.get(id)).map_or("", |id| id);
+            let category_id: &str = s["category_id"].as_str().and_then(|id| ids.get(id)).map_or("", |id| id);
+            let submission_date: i64 = s["submission_date"].as_i64().unwrap_or(now);
+            let data: &str = s["data"].as_str().unwrap_or("");
+            let graded: i64 = s["graded"].as_bool().unwrap_or(false) as i64;
+            let mark: f64 = s["mark"].as_f64().unwrap_or(0.0);
+            let out_of: f64 = s["out_of"].as_f64().unwrap_or(0.0);
+            let query = sqlx::query!(
+                "INSERT INTO submissions (class_id, submission_id, submitter_bearer, submission_date, data, category_id, graded, mark, out_of, lesson_id)
+                SELECT ?, ?, bearer, ?, ?, ?, ?, ?, ?, ? FROM users WHERE user_id=?",
+                class_id, submission_id, submission_date, data, category_id, graded, mark, out_of, lesson_id, user_id
+            ).execute(&mut tx).await;
+            if query.is_err() {
+                return false;
+            }
+        }
+
+        // Commit the transaction once everything has been imported
+        return tx.commit().await.is_ok();
+    }
+}
+
+// The lesson_json() function is used to convert the provided
+// lesson and it's prerequisites into the bundle's lesson format.
+fn lesson_json(lesson: &Lesson, prerequisites: Option<&Vec<Prerequisite>>) -> serde_json::Value {
+    return serde_json::json!({
+        "lesson_id": lesson.lesson_id,
+        "title": lesson.title,
+        "description": lesson.description,
+        "video": lesson.video,
+        "work": lesson.work,
+        "work_type": lesson.work_type,
+        "work_solutions": lesson.work_solutions,
+        "due_date": lesson.due_date,
+        "solutions_release": lesson.solutions_release,
+        "solutions_released": lesson.solutions_released == 1,
+        "opens_at": lesson.opens_at,
+        "closes_at": lesson.closes_at,
+        "time_limit": lesson.time_limit,
+        "category_id": lesson.category_id,
+        "max_attempts": lesson.max_attempts,
+        "prerequisites": prerequisites.map(|p| p.iter().map(|p| serde_json::json!({
+            "lesson_id": p.required_lesson_id,
+            "min_score": p.min_score
+        })).collect::<Vec<serde_json::Value>>()).unwrap_or_default()
+    });
+}
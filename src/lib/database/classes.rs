@@ -1,27 +1,75 @@
 use crate::lib::{
-    self, structs::{Class, Announcement, Whitelist, Unit, Lesson}
+    self, utils, database::{units, lessons, exams, extensions}, structs::{Class, Announcement, Whitelist, Unit, Lesson, LessonProgress, StudentProgress, ExamAttempt, Extension}
 };
 
 // Database Implementation
 impl lib::handlers::Database {
     // The class_exists() function is used to check whether
     // the provided class hash already exists. This function
-    // is called in the insert_class_data() function.
-    async fn class_exists(&self, class_id: &str) -> bool {
+    // is called in the insert_class_data() and
+    // clone_class_data() functions.
+    pub async fn class_exists(&self, class_id: &str) -> bool {
         // Query the database
         let query = sqlx::query!(
             "SELECT * FROM classes WHERE class_id=?", class_id
         ).fetch_one(&self.conn).await;
         
         // Return whether valid query data has been obtained
-        return !query.is_err();
+        return query.is_ok();
+    }
+
+    // The is_class_owner() function is used to check whether
+    // the provided bearer owns the provided class. This function
+    // is called before any teacher only data is returned.
+    pub async fn is_class_owner(&self, bearer: &str, class_id: &str) -> bool {
+        // Query the database
+        let query = sqlx::query!(
+            "SELECT id FROM classes WHERE class_id=? AND owner_bearer=?", class_id, bearer
+        ).fetch_one(&self.conn).await;
+
+        // Return whether valid query data has been obtained
+        return query.is_ok();
+    }
+
+    // The can_view_class() function is used to check whether
+    // the provided bearer is allowed to see the provided class.
+    // The class owner can always see the class, and if the class
+    // whitelist is enabled, only whitelisted users can see it.
+    pub async fn can_view_class(&self, bearer: &str, class_id: &str) -> bool {
+        // Get the class's general data
+        let class: Class = match self.get_class_general_data(class_id).await {
+            Some(r) => r,
+            None => return false
+        };
+
+        // Get the bearer's user id
+        let user_id: String = match self.get_user_id_by_bearer(bearer).await {
+            Some(r) => r,
+            None => return false
+        };
+
+        // The owner can always see the class, and if the
+        // whitelist is disabled, anyone can see the class
+        if class.owner_id == user_id || class.enable_whitelist == 0 {
+            return true;
+        }
+
+        // Query the database for the user within the whitelist
+        let query = sqlx::query!(
+            "SELECT id FROM whitelists WHERE class_id=? AND whitelisted_user_id=?",
+            class_id, user_id
+        ).fetch_one(&self.conn).await;
+
+        // Return whether valid query data has been obtained
+        return query.is_ok();
     }
 
     // The insert_class_data() function is used to insert
-    // a new class into the database. A maximum of
-    // 5 classes is allowed per user. To generate the unique
-    // class identifier, format the bearer with the current
-    // time in nanoseconds.
+    // a new class into the database. The number of classes
+    // a user can own is limited by the classes_per_user quota,
+    // which is checked before this function is called. To
+    // generate the unique class identifier, format the bearer
+    // with the current time in nanoseconds.
     pub async fn insert_class_data(
         &self, 
         bearer: &str, 
@@ -68,10 +116,7 @@ impl lib::handlers::Database {
             None => ()
         };
         // Get the class name from the request body
-        match data["class_name"].as_str() {
-            Some(name) => query.push_str(&format!("class_name='{}',", name)),
-            None => ()
-        };
+        if let Some(name) = data["class_name"].as_str() { query.push_str(&format!("class_name='{}',", name)) };
 
         // Remove the trailing comma at the end of the query
         return query[..query.len() - 1].to_string();
@@ -105,11 +150,114 @@ impl lib::handlers::Database {
     }
 
 
+    // The is_class_archived() function is used to check whether
+    // the provided class has been archived. Archived classes
+    // are read-only until they're restored.
+    pub async fn is_class_archived(&self, class_id: &str) -> bool {
+        // Query the database
+        let query = sqlx::query!(
+            "SELECT id FROM classes WHERE class_id=? AND archived=1", class_id
+        ).fetch_one(&self.conn).await;
+
+        // Return whether valid query data has been obtained
+        return query.is_ok();
+    }
+
+    // The set_class_archived() function is used by the class
+    // owner to archive or restore the provided class.
+    pub async fn set_class_archived(&self, bearer: &str, class_id: &str, archived: bool) -> bool {
+        let archived: i64 = archived as i64;
+
+        // Query the database
+        let query = sqlx::query!(
+            "UPDATE classes SET archived=? WHERE class_id=? AND owner_bearer=? AND archived!=?",
+            archived, class_id, bearer, archived
+        ).execute(&self.conn).await;
+
+        // Return query result
+        return match query {
+            Ok(r) => r.rows_affected() > 0,
+            Err(_) => false
+        };
+    }
+
+    // The delete_class_data() function is used to permanently
+    // delete the provided class along with everything within it.
+    // Everything is deleted within a single transaction, so either
+    // the whole class is deleted or none of it is.
+    pub async fn delete_class_data(&self, bearer: &str, class_id: &str) -> bool {
+        // Only the class owner can delete the class
+        if !self.is_class_owner(bearer, class_id).await {
+            return false;
+        }
+
+        // Begin a new transaction
+        let mut tx = match self.conn.begin().await {
+            Ok(tx) => tx,
+            Err(_) => return false
+        };
+
+        // Delete everything within the class. The lessons' data is
+        // deleted before the lessons and units it's found through.
+        let statements: [&str; 15] = [
+            "DELETE FROM lesson_prerequisites WHERE lesson_id IN (SELECT lessons.lesson_id FROM lessons JOIN units ON units.unit_id=lessons.unit_id WHERE units.class_id=?)",
+            "DELETE FROM lessons WHERE unit_id IN (SELECT unit_id FROM units WHERE class_id=?)",
+            "DELETE FROM lesson_progress WHERE class_id=?",
+            "DELETE FROM lesson_extensions WHERE class_id=?",
+            "DELETE FROM exam_attempts WHERE class_id=?",
+            "DELETE FROM quiz_draws WHERE class_id=?",
+            "DELETE FROM bank_questions WHERE class_id=?",
+            "DELETE FROM units WHERE class_id=?",
+            "DELETE FROM announcement_history WHERE announcement_id IN (SELECT announcement_id FROM announcements WHERE class_id=?)",
+            "DELETE FROM announcement_comments WHERE class_id=?",
+            "DELETE FROM announcements WHERE class_id=?",
+            "DELETE FROM whitelists WHERE class_id=?",
+            "DELETE FROM submissions WHERE class_id=?",
+            "DELETE FROM grade_categories WHERE class_id=?",
+            "DELETE FROM notifications WHERE class_id=?",
+        ];
+        for statement in statements {
+            if sqlx::query(statement).bind(class_id).execute(&mut tx).await.is_err() {
+                return false;
+            }
+        }
+
+        // Delete the class itself
+        let query = sqlx::query!(
+            "DELETE FROM classes WHERE class_id=? AND owner_bearer=?", class_id, bearer
+        ).execute(&mut tx).await;
+
+        // Commit the transaction if everything was deleted
+        return match query {
+            Ok(r) if r.rows_affected() > 0 => tx.commit().await.is_ok(),
+            _ => false
+        };
+    }
+
+    // The get_user_classes() function is used to get the id of
+    // every class the provided user owns. Archived classes are
+    // only returned when asked for.
+    pub async fn get_user_classes(&self, user_id: &str, archived: bool) -> Vec<String> {
+        let archived: i64 = archived as i64;
+
+        // Query the database
+        let query = sqlx::query!(
+            "SELECT class_id FROM classes WHERE owner_id=? AND archived=? ORDER BY id",
+            user_id, archived
+        ).fetch_all(&self.conn).await;
+
+        // Return query result
+        return match query {
+            Ok(r) => r.into_iter().map(|c| c.class_id).collect(),
+            Err(_) => Vec::new()
+        };
+    }
+
     // The get_class_data() function is used to get all data
     // revolving around the provided class_id. This includes
     // the class's primary data (shown below) and the class's
-    // units and lessons.
-    pub async fn get_class_data(&self, class_id: &str) -> Option<serde_json::Value> {
+    // units and lessons. Staff can see the class's unpublished data.
+    pub async fn get_class_data(&self, bearer: &str, class_id: &str, staff: bool) -> Option<serde_json::Value> {
         // Get the class's general data
         let class: Class = match self.get_class_general_data(class_id).await {
             Some(r) => r,
@@ -117,9 +265,9 @@ impl lib::handlers::Database {
         };
 
         // If the class does exist, get all of it's data
-        let units = self.get_class_units(class_id).await;
+        let units = self.get_class_units(bearer, class_id, staff).await;
         let whitelist = self.get_class_whitelist(class_id).await;
-        let announcements = self.get_class_announcements(class_id).await;
+        let announcements = self.get_class_announcements(class_id, staff).await;
 
         // Return a formatted string of all the class data
         return Some(serde_json::json!({
@@ -127,6 +275,7 @@ impl lib::handlers::Database {
             "owner_id": class.owner_id,
             "class_name": class.class_name,
             "enable_whitelist": class.enable_whitelist == 1,
+            "archived": class.archived == 1,
             "units": units,
             "whitelist": whitelist,
             "announcements": announcements
@@ -136,11 +285,11 @@ impl lib::handlers::Database {
     // The get_class_general_data() function is used to get
     // all the primary class data. All the data names
     // are shown within the below comment.
-    async fn get_class_general_data(&self, class_id: &str) -> Option<Class> {
+    pub async fn get_class_general_data(&self, class_id: &str) -> Option<Class> {
         // Get the class's general data. This includes the class:
         // class_name, whitelist[bool], rls[bool], and class_id
         let query = sqlx::query_as!(Class,
-            "SELECT class_name, owner_id, enable_whitelist FROM classes WHERE class_id=?",
+            "SELECT class_name, owner_id, enable_whitelist, archived FROM classes WHERE class_id=?",
             class_id
         ).fetch_one(&self.conn).await;
 
@@ -153,11 +302,21 @@ impl lib::handlers::Database {
 
     // The get_class_units() function is used to
     // easily get all the units corresponding with
-    // the provided class_id.
-    pub async fn get_class_units(&self, class_id: &str) -> Vec<serde_json::Value> {
+    // the provided class_id. The locked state returned
+    // is the unit's effective state, taking it's unlock
+    // and lock dates into account. Students only see the
+    // name of a locked unit, while staff see everything.
+    // The units and lessons are returned in their set order,
+    // along with how much of each unit the user has completed.
+    pub async fn get_class_units(&self, bearer: &str, class_id: &str, staff: bool) -> Vec<serde_json::Value> {
+        let now: i64 = utils::get_time().as_secs() as i64;
+
+        // Get the user's progress through the class
+        let student: StudentProgress = self.get_student_progress(bearer, class_id).await;
+
         // Query the database
         let query = sqlx::query_as!(Unit,
-            "SELECT unit_id, unit_name, locked FROM units WHERE class_id=?",
+            "SELECT unit_id, unit_name, locked, unlock_at, lock_at FROM units WHERE class_id=? ORDER BY position, id",
             class_id
         ).fetch_all(&self.conn).await;
 
@@ -166,65 +325,164 @@ impl lib::handlers::Database {
         return match query {
             Err(_) => Vec::new(),
             Ok(r) => futures::future::join_all(r.iter().map(|u| async {
+                let locked: bool = units::is_unit_locked(u, now);
+                let lessons: Vec<Lesson> = self.get_unit_lessons(&u.unit_id).await;
+
+                // Get the percentage of the unit's lessons the user has completed
+                let completed: usize = lessons.iter()
+                    .filter(|l| student.lessons.get(&l.lesson_id).is_some_and(|p| p.completed == 1))
+                    .count();
+                let percent: f64 = match lessons.len() {
+                    0 => 0.0,
+                    n => completed as f64 * 100.0 / n as f64
+                };
+
+                // Withhold the lessons of locked units from students
+                let lessons: Vec<serde_json::Value> = match staff || !locked {
+                    true => self.get_lesson_json(&lessons, staff, &student),
+                    false => Vec::new()
+                };
                 serde_json::json!({
+                    "unit_id": u.unit_id,
                     "unit_name": u.unit_name,
-                    "locked": u.locked == 1,
-                    "lessons": self.get_unit_lessons(&u.unit_id).await
+                    "locked": locked,
+                    "unlock_at": u.unlock_at,
+                    "lock_at": u.lock_at,
+                    "progress": percent,
+                    "lessons": lessons
                 })
             })).await
         }
     }
     
     // The get_unit_lessons() function is used to get all
-    // the lesson data that comes with the provided unit hash.
-    // The function then converts the query data into a readable 
-    // json map that will eventually be returned with the 
-    // outgoing response body
-    async fn get_unit_lessons(&self, unit_id: &str) -> Vec<serde_json::Value> {
+    // the lesson data that comes with the provided unit hash,
+    // in the unit's set order.
+    pub async fn get_unit_lessons(&self, unit_id: &str) -> Vec<Lesson> {
         // Query the database
         let query = sqlx::query_as!(Lesson,
-            "SELECT title, description, video, work, work_solutions FROM lessons WHERE unit_id=?",
+            "SELECT lesson_id, title, description, video, work, work_type, work_solutions, due_date, solutions_release, solutions_released, opens_at, closes_at, time_limit, category_id, max_attempts FROM lessons WHERE unit_id=? ORDER BY position, id",
             unit_id
         ).fetch_all(&self.conn).await;
 
         // Return query result
         return match query {
-            Err(_) => Vec::new(),
-            Ok(r) => r.iter().map(|f| {
-                serde_json::json!({
-                    "title": f.title,
-                    "description": f.description,
-                    "video": f.video,
-                    "work": f.work,
-                    "work_solutions": f.work_solutions
-                })
-            }).collect()
+            Ok(r) => r,
+            Err(_) => Vec::new()
         };
     }
 
+    // The get_lesson_json() function is used to convert the
+    // provided lessons into a readable json map that will
+    // eventually be returned with the outgoing response body.
+    // Students only get a lesson's solutions once it's release
+    // policy has been met, and only see the title of a lesson
+    // whose prerequisites they haven't met. A timed lesson's work
+    // is hidden until the lesson opens and the student starts it.
+    // Students see their own due date and closing time if they've
+    // been given an extension.
+    fn get_lesson_json(&self, lessons: &[Lesson], staff: bool, student: &StudentProgress) -> Vec<serde_json::Value> {
+        let now: i64 = utils::get_time().as_secs() as i64;
+        return lessons.iter().map(|f| {
+            let locked: bool = !staff && !lessons::prerequisites_met(&f.lesson_id, student);
+            let prerequisites: Vec<serde_json::Value> = student.prerequisites
+                .get(&f.lesson_id)
+                .map(|p| p.iter().map(|p| serde_json::json!({
+                    "lesson_id": p.required_lesson_id,
+                    "min_score": p.min_score
+                })).collect())
+                .unwrap_or_default();
+
+            // Withhold the lesson's content until it's prerequisites are met
+            if locked {
+                return serde_json::json!({
+                    "lesson_id": f.lesson_id,
+                    "title": f.title,
+                    "locked": true,
+                    "prerequisites": prerequisites
+                });
+            }
+            let extension: Option<&Extension> = student.extensions.get(&f.lesson_id);
+            let released: bool = lessons::solutions_released(f, extension, student.submitted.get(&f.lesson_id).copied().unwrap_or(0), now);
+            let progress: Option<&LessonProgress> = student.lessons.get(&f.lesson_id);
+            let attempt: Option<&ExamAttempt> = student.attempts.get(&f.lesson_id);
+            serde_json::json!({
+                "lesson_id": f.lesson_id,
+                "title": f.title,
+                "description": f.description,
+                "video": f.video,
+                "work": if staff || exams::work_visible(f, student, now) { Some(&f.work) } else { None },
+                "work_type": f.work_type,
+                "work_solutions": if staff || released { Some(&f.work_solutions) } else { None },
+                "due_date": extensions::due_date(f, extension),
+                "solutions_release": f.solutions_release,
+                "solutions_released": released,
+                "category_id": f.category_id,
+                "locked": false,
+                "prerequisites": prerequisites,
+                "completed": progress.is_some_and(|p| p.completed == 1),
+                "video_progress": progress.map_or(0.0, |p| p.video_progress),
+                "opens_at": f.opens_at,
+                "closes_at": extensions::closes_at(f, extension),
+                "time_limit": f.time_limit,
+                "max_attempts": f.max_attempts,
+                "attempts": student.submitted.get(&f.lesson_id).copied().unwrap_or(0),
+                "extra_time": extension.map_or(0, |e| e.extra_time),
+                "extended": extension.is_some(),
+                "started_at": attempt.map_or(0, |a| a.started_at),
+                "ends_at": exams::ends_at(f, attempt.map_or(0, |a| a.started_at), extension),
+                "submitted": attempt.is_some_and(|a| a.submitted_at > 0)
+            })
+        }).collect();
+    }
+
 
     // The get_class_announcements() function is used
     // to get all the announcements a teacher has
-    // made within provided class_id.
-    pub async fn get_class_announcements(&self, class_id: &str) -> Vec<serde_json::Value> {
+    // made within provided class_id. Scheduled announcements
+    // are hidden from students until the scheduler publishes
+    // them, while staff see them marked as scheduled. Pinned
+    // announcements are returned first, along with the
+    // amount of comments under each announcement. The
+    // author's current name is shown, so renaming a user
+    // updates all of their announcements.
+    pub async fn get_class_announcements(&self, class_id: &str, staff: bool) -> Vec<serde_json::Value> {
         // Fetch all the announcements that the
         // class owner has created.
         let query = sqlx::query_as!(Announcement, 
-            "SELECT author_name, title, description, attachment FROM announcements WHERE class_id=?", 
+            "SELECT announcements.announcement_id as \"announcement_id!\", announcements.author_id as \"author_id!\",
+            COALESCE(users.user_name, announcements.author_name) as \"author_name!: String\",
+            announcements.title as \"title!\", announcements.description as \"description!\", announcements.attachment as \"attachment!\",
+            announcements.publish_at as \"publish_at!\", announcements.published as \"published!\", announcements.edited_at as \"edited_at!\", announcements.pinned as \"pinned!\", announcements.locked as \"locked!\",
+            (SELECT COUNT(*) FROM announcement_comments WHERE announcement_comments.announcement_id=announcements.announcement_id) as \"comment_count!: i64\"
+            FROM announcements
+            LEFT JOIN users ON users.user_id=announcements.author_id
+            WHERE announcements.class_id=? ORDER BY announcements.pinned DESC, announcements.date DESC", 
             class_id
         ).fetch_all(&self.conn).await;
 
         // Return query result
         return match query {
             Err(_) => Vec::new(),
-            Ok(r) => r.iter().map(|f| {
-                serde_json::json!({
-                    "author_name": f.author_name,
-                    "title": f.title,
-                    "description": f.description,
-                    "attachment": f.attachment
-                })
-            }).collect()
+            Ok(r) => r.iter()
+                .filter(|f| staff || f.published == 1)
+                .map(|f| {
+                    serde_json::json!({
+                        "announcement_id": f.announcement_id,
+                        "author_id": f.author_id,
+                        "author_name": f.author_name,
+                        "title": f.title,
+                        "description": f.description,
+                        "attachment": f.attachment,
+                        "publish_at": f.publish_at,
+                        "scheduled": f.published == 0,
+                        "edited": f.edited_at > 0,
+                        "edited_at": f.edited_at,
+                        "pinned": f.pinned == 1,
+                        "locked": f.locked == 1,
+                        "comment_count": f.comment_count
+                    })
+                }).collect()
         };
     }
 
@@ -0,0 +1,208 @@
+use crate::lib::{self, utils};
+use std::collections::HashMap;
+
+// Database Implementation
+impl lib::handlers::Database {
+    // The clone_class_data() function is used by the class owner to
+    // copy their class into a new class, such as for a new term. The
+    // class's settings, grade categories, units, lessons, lesson
+    // prerequisites and question bank are copied in their set order,
+    // and the announcements are copied if asked for. Every copy is
+    // given a new id. The class's whitelist and student data are never
+    // copied, and the copied lessons' solutions aren't released. Units
+    // and deadlines that have already passed aren't announced again.
+    // Everything is copied within a single transaction.
+    pub async fn clone_class_data(
+        &self,
+        bearer: &str,
+        class_id: &str,
+        new_class_id: &str,
+        class_name: Option<&str>,
+        include_announcements: bool
+    ) -> bool {
+        // Only the class owner can clone the class
+        if !self.is_class_owner(bearer, class_id).await || self.class_exists(new_class_id).await {
+            return false;
+        }
+
+        // Get the bearer owner id
+        let owner_id: String = match self.get_user_id_by_bearer(bearer).await {
+            Some(r) => r,
+            None => return false
+        };
+
+        // Get the ids of everything being copied
+        let unit_ids: Vec<String> = match sqlx::query!(
+            "SELECT unit_id FROM units WHERE class_id=? ORDER BY position, id", class_id
+        ).fetch_all(&self.conn).await {
+            Ok(r) => r.into_iter().map(|u| u.unit_id).collect(),
+            Err(_) => return false
+        };
+        let lessons = match sqlx::query!(
+            "SELECT lessons.lesson_id as \"lesson_id!\", lessons.unit_id as \"unit_id!\", lessons.work_type as \"work_type!\", lessons.work as \"work!\",
+            lessons.category_id as \"category_id!\"
+            FROM lessons
+            JOIN units ON units.unit_id=lessons.unit_id
+            WHERE units.class_id=?",
+            class_id
+        ).fetch_all(&self.conn).await {
+            Ok(r) => r,
+            Err(_) => return false
+        };
+        let questions = match sqlx::query!(
+            "SELECT question_id, unit_id FROM bank_questions WHERE class_id=? ORDER BY id", class_id
+        ).fetch_all(&self.conn).await {
+            Ok(r) => r,
+            Err(_) => return false
+        };
+        let category_ids: Vec<String> = match sqlx::query!(
+            "SELECT category_id FROM grade_categories WHERE class_id=?", class_id
+        ).fetch_all(&self.conn).await {
+            Ok(r) => r.into_iter().map(|c| c.category_id).collect(),
+            Err(_) => return false
+        };
+        let announcement_ids: Vec<String> = match include_announcements {
+            false => Vec::new(),
+            true => match sqlx::query!(
+                "SELECT announcement_id FROM announcements WHERE class_id=? ORDER BY id", class_id
+            ).fetch_all(&self.conn).await {
+                Ok(r) => r.into_iter().map(|a| a.announcement_id).collect(),
+                Err(_) => return false
+            }
+        };
+
+        // Generate the new id of every grade category, unit and lesson
+        let mut ids: HashMap<String, String> = HashMap::new();
+        for id in category_ids.iter().chain(&unit_ids).chain(lessons.iter().map(|l| &l.lesson_id)) {
+            ids.insert(id.clone(), utils::generate_new_id(&format!("{}:{}", new_class_id, id)));
+        }
+        let now: i64 = utils::get_time().as_secs() as i64;
+
+        // Begin a new transaction
+        let mut tx = match self.conn.begin().await {
+            Ok(tx) => tx,
+            Err(_) => return false
+        };
+
+        // Copy the class and it's settings
+        let query = sqlx::query!(
+            "INSERT INTO classes (owner_bearer, owner_id, class_id, class_name, enable_whitelist)
+            SELECT ?, ?, ?, COALESCE(?, class_name), enable_whitelist FROM classes WHERE class_id=?",
+            bearer, owner_id, new_class_id, class_name, class_id
+        ).execute(&mut tx).await;
+        if query.is_err() {
+            return false;
+        }
+
+        // Copy the grade categories
+        for category_id in &category_ids {
+            let new_category_id: &String = &ids[category_id];
+            let query = sqlx::query!(
+                "INSERT INTO grade_categories (owner_bearer, class_id, category_id, category_name, weight, drop_lowest)
+                SELECT ?, ?, ?, category_name, weight, drop_lowest FROM grade_categories WHERE category_id=? AND class_id=?",
+                bearer, new_class_id, new_category_id, category_id, class_id
+            ).execute(&mut tx).await;
+            if query.is_err() {
+                return false;
+            }
+        }
+
+        // Copy the units
+        for unit_id in &unit_ids {
+            let new_unit_id: &String = &ids[unit_id];
+            let query = sqlx::query!(
+                "INSERT INTO units (owner_bearer, class_id, unit_id, unit_name, locked, unlock_at, lock_at, unlock_notified, position)
+                SELECT ?, ?, ?, unit_name, locked, unlock_at, lock_at, unlock_at <= ?, position FROM units WHERE unit_id=?",
+                bearer, new_class_id, new_unit_id, now, unit_id
+            ).execute(&mut tx).await;
+            if query.is_err() {
+                return false;
+            }
+        }
+
+        // Copy the question bank into the copied units. Every
+        // question is given a new id.
+        for question in &questions {
+            let new_unit_id: &String = match ids.get(&question.unit_id) {
+                Some(id) => id,
+                None => continue
+            };
+            let new_question_id: String = utils::generate_new_id(&format!("{}:{}", new_class_id, question.question_id));
+            let query = sqlx::query!(
+                "INSERT INTO bank_questions (class_id, unit_id, question_id, question, answer)
+                SELECT ?, ?, ?, question, answer FROM bank_questions WHERE question_id=? AND class_id=?",
+                new_class_id, new_unit_id, new_question_id, question.question_id, class_id
+            ).execute(&mut tx).await;
+            if query.is_err() {
+                return false;
+            }
+        }
+
+        // Copy the lessons. Bank quizzes draw from the copied units,
+        // and the lessons count towards the copied grade categories.
+        for lesson in &lessons {
+            let work: String = match lesson.work_type.as_str() {
+                "bank" => remap_draws(&lesson.work, &ids),
+                _ => lesson.work.clone()
+            };
+            let category_id: &str = ids.get(&lesson.category_id).map_or("", |id| id);
+            let query = sqlx::query!(
+                "INSERT INTO lessons (owner_bearer, unit_id, lesson_id, title, description, video, work, work_type, work_solutions, due_date, deadline_reminded, solutions_release, solutions_released, opens_at, closes_at, time_limit, category_id, max_attempts, position)
+                SELECT ?, ?, ?, title, description, video, ?, work_type, work_solutions, due_date, due_date <= ?, solutions_release, 0, opens_at, closes_at, time_limit, ?, max_attempts, position FROM lessons WHERE lesson_id=?",
+                bearer, ids[&lesson.unit_id], ids[&lesson.lesson_id], work, now, category_id, lesson.lesson_id
+            ).execute(&mut tx).await;
+            if query.is_err() {
+                return false;
+            }
+        }
+
+        // Copy the lesson prerequisites
+        for prerequisite in self.get_class_prerequisites(class_id).await.values().flatten() {
+            let (lesson_id, required_lesson_id) = match (ids.get(&prerequisite.lesson_id), ids.get(&prerequisite.required_lesson_id)) {
+                (Some(l), Some(r)) => (l, r),
+                _ => continue
+            };
+            let query = sqlx::query!(
+                "INSERT INTO lesson_prerequisites (lesson_id, required_lesson_id, min_score) VALUES (?, ?, ?)",
+                lesson_id, required_lesson_id, prerequisite.min_score
+            ).execute(&mut tx).await;
+            if query.is_err() {
+                return false;
+            }
+        }
+
+        // Copy the announcements, without their comments or edit history
+        for announcement_id in &announcement_ids {
+            let new_announcement_id: String = utils::generate_new_id(&format!("{}:{}", new_class_id, announcement_id));
+            let query = sqlx::query!(
+                "INSERT INTO announcements (owner_bearer, class_id, announcement_id, author_id, author_name, title, description, attachment, date, publish_at, published, pinned, locked)
+                SELECT ?, ?, ?, author_id, author_name, title, description, attachment, date, publish_at, published, pinned, locked FROM announcements WHERE announcement_id=? AND class_id=?",
+                bearer, new_class_id, new_announcement_id, announcement_id, class_id
+            ).execute(&mut tx).await;
+            if query.is_err() {
+                return false;
+            }
+        }
+
+        // Commit the transaction once everything has been copied
+        return tx.commit().await.is_ok();
+    }
+}
+
+// The remap_draws() function is used to point a copied bank
+// quiz's draws at the copied units. Draws from units that
+// weren't copied are left as they are.
+pub fn remap_draws(work: &str, ids: &HashMap<String, String>) -> String {
+    let mut work: serde_json::Value = match serde_json::from_str(work) {
+        Ok(w) => w,
+        Err(_) => return work.to_string()
+    };
+    if let Some(draws) = work["draws"].as_array_mut() {
+        for draw in draws {
+            if let Some(new_unit_id) = draw["unit_id"].as_str().and_then(|id| ids.get(id)) {
+                draw["unit_id"] = serde_json::Value::from(new_unit_id.as_str());
+            }
+        }
+    }
+    return work.to_string();
+}
@@ -0,0 +1,339 @@
+use crate::lib::{self, utils, structs::Comment};
+use std::collections::HashMap;
+
+// The MAX_REPLY_DEPTH is how deeply replies
+// can be nested under a top level comment
+static MAX_REPLY_DEPTH: i64 = 8;
+
+// Database Implementation
+impl lib::handlers::Database {
+    // The get_announcement_thread() function is used to get
+    // whether the provided announcement is published and locked of the provided announcement.
+    // None is returned if the announcement isn't within the class.
+    async fn get_announcement_thread(&self, class_id: &str, announcement_id: &str) -> Option<(bool, bool)> {
+        // Query the database
+        let query = sqlx::query!(
+            "SELECT published, locked FROM announcements WHERE announcement_id=? AND class_id=?",
+            announcement_id, class_id
+        ).fetch_one(&self.conn).await;
+
+        // Return query result
+        return match query {
+            Ok(r) => Some((r.published == 1, r.locked == 1)),
+            Err(_) => None
+        };
+    }
+
+    // The get_comment_depth() function is used to get how deeply
+    // the provided comment is nested under the provided announcement,
+    // with top level comments at a depth of zero. This function is
+    // used to verify the comment being replied to. None is returned
+    // if the comment doesn't exist.
+    async fn get_comment_depth(&self, announcement_id: &str, comment_id: &str) -> Option<i64> {
+        // Query the database, walking up through the comment's
+        // parents until the top level comment is reached
+        let max_depth: i64 = MAX_REPLY_DEPTH;
+        let query = sqlx::query!(
+            "WITH RECURSIVE parents(parent_id, depth) AS (
+                SELECT parent_id, 0 FROM announcement_comments WHERE announcement_id=? AND comment_id=?
+                UNION ALL SELECT announcement_comments.parent_id, parents.depth + 1 FROM announcement_comments
+                JOIN parents ON announcement_comments.comment_id=parents.parent_id
+                WHERE announcement_comments.announcement_id=? AND parents.depth < ?
+            )
+            SELECT MAX(depth) as \"depth: i64\" FROM parents",
+            announcement_id, comment_id, announcement_id, max_depth
+        ).fetch_one(&self.conn).await;
+
+        // Return query result
+        return match query {
+            Ok(r) => r.depth,
+            Err(_) => None
+        };
+    }
+
+    // The insert_announcement_comment() function is used to add a
+    // new comment under the provided announcement. If a parent_id is
+    // provided, the comment is a reply to that comment. Replies can be
+    // nested up to MAX_REPLY_DEPTH deep. Students can't comment on
+    // scheduled announcements or on locked threads.
+    pub async fn insert_announcement_comment(
+        &self,
+        bearer: &str,
+        class_id: &str,
+        announcement_id: &str,
+        comment_id: &str,
+        staff: bool,
+        data: &serde_json::Value
+    ) -> bool {
+        // Get the comment author's user id
+        let user_id: String = match self.get_user_id_by_bearer(bearer).await {
+            Some(r) => r,
+            None => return false
+        };
+
+        // Make sure the user is allowed to comment on the announcement
+        let (published, locked): (bool, bool) = match self.get_announcement_thread(class_id, announcement_id).await {
+            Some(r) => r,
+            None => return false
+        };
+        if !staff && (locked || !published) {
+            return false;
+        }
+
+        // Get the request body variables
+        let content: &str = match data["content"].as_str() {
+            Some(content) if !content.trim().is_empty() => content,
+            _ => return false
+        };
+        let parent_id: &str = data["parent_id"].as_str().unwrap_or("");
+
+        // Replies must be to a comment under the same announcement,
+        // and can only be nested so deep
+        if !parent_id.is_empty() {
+            match self.get_comment_depth(announcement_id, parent_id).await {
+                Some(depth) if depth < MAX_REPLY_DEPTH => (),
+                _ => return false
+            };
+        }
+
+        // Insert the comment into the database
+        let date: i64 = utils::get_time().as_secs() as i64;
+        let query = sqlx::query!(
+            "INSERT INTO announcement_comments (class_id, announcement_id, comment_id, parent_id, user_id, content, date) VALUES (?, ?, ?, ?, ?, ?, ?)",
+            class_id, announcement_id, comment_id, parent_id, user_id, content, date
+        ).execute(&self.conn).await;
+
+        // Return query result
+        return match query {
+            Ok(r) => r.rows_affected() > 0,
+            Err(_) => false
+        };
+    }
+
+    // The get_announcement_comments() function is used to get every
+    // comment under the provided announcement. Replies are nested
+    // under the comment they're replying to, with the oldest
+    // comments first. Students can't see the comments under
+    // an announcement that hasn't been published yet.
+    pub async fn get_announcement_comments(
+        &self,
+        class_id: &str,
+        announcement_id: &str,
+        staff: bool
+    ) -> Option<serde_json::Value> {
+        // Make sure the user is allowed to see the announcement
+        let (published, locked): (bool, bool) = match self.get_announcement_thread(class_id, announcement_id).await {
+            Some(r) => r,
+            None => return None
+        };
+        if !staff && !published {
+            return None;
+        }
+
+        // Query the database. The author's current name is used
+        // so that renamed users are shown correctly.
+        let query = sqlx::query_as!(Comment,
+            "SELECT announcement_comments.comment_id as \"comment_id!\", announcement_comments.parent_id as \"parent_id!\", announcement_comments.user_id as \"user_id!\", users.user_name as \"user_name!\", announcement_comments.content as \"content!\", announcement_comments.date as \"date!\"
+            FROM announcement_comments
+            JOIN users ON users.user_id=announcement_comments.user_id
+            WHERE announcement_comments.announcement_id=?
+            ORDER BY announcement_comments.date ASC, announcement_comments.id ASC",
+            announcement_id
+        ).fetch_all(&self.conn).await;
+        let comments: Vec<Comment> = match query {
+            Ok(r) => r,
+            Err(_) => return None
+        };
+
+        // Return the comment thread
+        return Some(serde_json::json!({
+            "locked": locked,
+            "comment_count": comments.len(),
+            "comments": comment_thread(&comments)
+        }));
+    }
+
+    // The delete_announcement_comment() function is used to delete the
+    // provided comment along with all of it's replies. Users can delete
+    // their own comments, while staff can delete any comment in the class.
+    pub async fn delete_announcement_comment(
+        &self,
+        bearer: &str,
+        class_id: &str,
+        announcement_id: &str,
+        comment_id: &str,
+        staff: bool
+    ) -> bool {
+        // Get the user's id
+        let user_id: String = match self.get_user_id_by_bearer(bearer).await {
+            Some(r) => r,
+            None => return false
+        };
+
+        // Query the database, making sure the user is allowed
+        // to delete the comment before anything is removed
+        let query = sqlx::query!(
+            "SELECT user_id FROM announcement_comments WHERE comment_id=? AND announcement_id=? AND class_id=?",
+            comment_id, announcement_id, class_id
+        ).fetch_one(&self.conn).await;
+        match query {
+            Ok(r) if staff || r.user_id == user_id => (),
+            _ => return false
+        };
+
+        // Delete the comment and every reply beneath it
+        let query = sqlx::query!(
+            "WITH RECURSIVE thread(comment_id) AS (
+                SELECT ? UNION SELECT announcement_comments.comment_id FROM announcement_comments
+                JOIN thread ON announcement_comments.parent_id=thread.comment_id
+                WHERE announcement_comments.announcement_id=?
+            )
+            DELETE FROM announcement_comments WHERE announcement_id=? AND comment_id IN (SELECT comment_id FROM thread)",
+            comment_id, announcement_id, announcement_id
+        ).execute(&self.conn).await;
+
+        // Return query result
+        return match query {
+            Ok(r) => r.rows_affected() > 0,
+            Err(_) => false
+        };
+    }
+
+    // The lock_announcement_comments() function is used to lock or
+    // unlock the comment thread under the provided announcement.
+    // Only the class owner can lock an announcement's comments.
+    pub async fn lock_announcement_comments(
+        &self,
+        bearer: &str,
+        class_id: &str,
+        announcement_id: &str,
+        data: &serde_json::Value
+    ) -> bool {
+        // Only the class owner can lock comment threads
+        if !self.is_class_owner(bearer, class_id).await {
+            return false;
+        }
+
+        // Get the request body variables
+        let locked: i64 = match data["locked"].as_bool() {
+            Some(locked) => locked as i64,
+            None => return false
+        };
+
+        // Query the database
+        let query = sqlx::query!(
+            "UPDATE announcements SET locked=? WHERE announcement_id=? AND class_id=?",
+            locked, announcement_id, class_id
+        ).execute(&self.conn).await;
+
+        // Return query result
+        return match query {
+            Ok(r) => r.rows_affected() > 0,
+            Err(_) => false
+        };
+    }
+}
+
+// The comment_thread() function is used to nest the provided
+// comments under the comment they're replying to, returning
+// every top level comment with their replies. The thread is
+// built from the deepest replies up, rather than recursively,
+// so that a long chain of replies can't overflow the stack.
+fn comment_thread(comments: &[Comment]) -> Vec<serde_json::Value> {
+    // Group the comments by the comment they're replying to
+    let mut replies: HashMap<&str, Vec<&Comment>> = HashMap::new();
+    for c in comments {
+        replies.entry(c.parent_id.as_str()).or_default().push(c);
+    }
+
+    // Order the comments so that every comment
+    // comes before the replies beneath it
+    let mut order: Vec<&Comment> = Vec::new();
+    let mut stack: Vec<&Comment> = replies.get("").cloned().unwrap_or_default();
+    while let Some(c) = stack.pop() {
+        order.push(c);
+        if let Some(r) = replies.get(c.comment_id.as_str()) {
+            stack.extend(r);
+        }
+    }
+
+    // Build each comment after all of it's replies
+    let mut built: HashMap<&str, serde_json::Value> = HashMap::new();
+    for c in order.iter().rev() {
+        let mut comment: serde_json::Value = serde_json::json!({
+            "comment_id": c.comment_id,
+            "user_id": c.user_id,
+            "user_name": c.user_name,
+            "content": c.content,
+            "date": c.date
+        });
+        comment["replies"] = serde_json::Value::Array(take_replies(&replies, &mut built, &c.comment_id));
+        built.insert(&c.comment_id, comment);
+    }
+    return take_replies(&replies, &mut built, "");
+}
+
+// The take_replies() function is used to take the already
+// built replies to the provided comment, in the order
+// they were posted.
+fn take_replies(
+    replies: &HashMap<&str, Vec<&Comment>>,
+    built: &mut HashMap<&str, serde_json::Value>,
+    parent_id: &str
+) -> Vec<serde_json::Value> {
+    return match replies.get(parent_id) {
+        None => Vec::new(),
+        Some(comments) => comments.iter().filter_map(|c| built.remove(c.comment_id.as_str())).collect()
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn comment(comment_id: &str, parent_id: &str) -> Comment {
+        return Comment {
+            comment_id: comment_id.to_string(),
+            parent_id: parent_id.to_string(),
+            user_id: String::from("user"),
+            user_name: String::from("User"),
+            content: String::from("Hello"),
+            date: 0
+        };
+    }
+
+    fn ids(comments: &serde_json::Value) -> Vec<&str> {
+        return comments.as_array().unwrap().iter().map(|c| c["comment_id"].as_str().unwrap()).collect();
+    }
+
+    #[test]
+    fn nests_replies_in_order() {
+        let thread: serde_json::Value = serde_json::Value::Array(comment_thread(&[
+            comment("a", ""),
+            comment("b", ""),
+            comment("c", "a"),
+            comment("d", "c"),
+            comment("e", "a"),
+            comment("f", "missing")
+        ]));
+        assert_eq!(ids(&thread), ["a", "b"]);
+        assert_eq!(ids(&thread[0]["replies"]), ["c", "e"]);
+        assert_eq!(ids(&thread[0]["replies"][0]["replies"]), ["d"]);
+        assert_eq!(ids(&thread[1]["replies"]), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn builds_long_reply_chains() {
+        let comments: Vec<Comment> = (0..1000).map(|i| {
+            comment(&i.to_string(), &if i == 0 { String::new() } else { (i - 1).to_string() })
+        }).collect();
+        let thread: Vec<serde_json::Value> = comment_thread(&comments);
+        let mut depth: usize = 0;
+        let mut replies: &serde_json::Value = &thread[0];
+        while let Some(reply) = replies["replies"].get(0) {
+            replies = reply;
+            depth += 1;
+        }
+        assert_eq!(depth, 999);
+    }
+}
@@ -0,0 +1,230 @@
+use crate::lib::{
+    self, utils, database::{units, lessons, extensions}, structs::{ExamAttempt, Extension, Lesson, StudentProgress}
+};
+use std::collections::HashMap;
+
+// Database Implementation
+impl lib::handlers::Database {
+    // The get_exam_attempts() function is used to get the bearer's
+    // attempts at the timed lessons within the provided class,
+    // mapped by the lesson's id.
+    pub async fn get_exam_attempts(&self, bearer: &str, class_id: &str) -> HashMap<String, ExamAttempt> {
+        // Query the database
+        let query = sqlx::query_as!(ExamAttempt,
+            "SELECT exam_attempts.lesson_id as \"lesson_id!\", exam_attempts.started_at as \"started_at!\", exam_attempts.submitted_at as \"submitted_at!\"
+            FROM exam_attempts
+            JOIN users ON users.user_id=exam_attempts.user_id
+            WHERE exam_attempts.class_id=? AND users.bearer=?",
+            class_id, bearer
+        ).fetch_all(&self.conn).await;
+
+        // Return query result
+        return match query {
+            Ok(r) => r.into_iter().map(|a| (a.lesson_id.clone(), a)).collect(),
+            Err(_) => HashMap::new()
+        };
+    }
+
+    // The start_exam() function is used to start the bearer's
+    // timer on the provided timed lesson. The start time is stored
+    // so the bearer can't restart the timer, and starting a lesson
+    // that has already been started returns the existing attempt.
+    // Students can only start a lesson while it's open.
+    pub async fn start_exam(
+        &self,
+        bearer: &str,
+        class_id: &str,
+        unit_id: &str,
+        lesson_id: &str,
+        staff: bool
+    ) -> Option<serde_json::Value> {
+        // Get the user's id
+        let user_id: String = self.get_user_id_by_bearer(bearer).await?;
+
+        // Make sure the student can see the lesson
+        let now: i64 = utils::get_time().as_secs() as i64;
+        match self.get_class_unit(class_id, unit_id).await {
+            Some(unit) if staff || !units::is_unit_locked(&unit, now) => (),
+            _ => return None
+        };
+        let student: StudentProgress = self.get_student_progress(bearer, class_id).await;
+        if !staff && !lessons::prerequisites_met(lesson_id, &student) {
+            return None;
+        }
+
+        // Only timed lessons can be started
+        let lesson: Lesson = self.get_lesson(unit_id, lesson_id).await?;
+        if lesson.time_limit == 0 {
+            return None;
+        }
+        let extension: Option<&Extension> = student.extensions.get(lesson_id);
+
+        // If the lesson has already been started, return the attempt
+        if let Some(attempt) = student.attempts.get(lesson_id) {
+            return Some(serde_json::json!({
+                "started_at": attempt.started_at,
+                "ends_at": ends_at(&lesson, attempt.started_at, extension),
+                "submitted": attempt.submitted_at > 0
+            }));
+        }
+
+        // Make sure the lesson is open
+        let closes_at: i64 = extensions::closes_at(&lesson, extension);
+        if now < lesson.opens_at || (closes_at > 0 && now >= closes_at) {
+            return None;
+        }
+
+        // Insert the attempt into the database
+        let query = sqlx::query!(
+            "INSERT INTO exam_attempts (class_id, lesson_id, user_id, started_at) VALUES (?, ?, ?, ?) ON CONFLICT (lesson_id, user_id) DO NOTHING",
+            class_id, lesson_id, user_id, now
+        ).execute(&self.conn).await;
+
+        // Return the attempt
+        return match query {
+            Ok(r) if r.rows_affected() > 0 => Some(serde_json::json!({
+                "started_at": now,
+                "ends_at": ends_at(&lesson, now, extension),
+                "submitted": false
+            })),
+            _ => None
+        };
+    }
+
+    // The can_submit_lesson() function is used to check whether the
+    // bearer can still submit work for the provided lesson. Work can't
+    // be submitted before the lesson opens or after it closes. Timed
+    // lessons must have been started, can only be submitted once, and
+    // can't be submitted once the bearer's time is up. The bearer's
+    // extension is used for the closing time and time limit.
+    pub async fn can_submit_lesson(&self, bearer: &str, lesson: &Lesson) -> bool {
+        // Get the user's id
+        let user_id: String = match self.get_user_id_by_bearer(bearer).await {
+            Some(r) => r,
+            None => return false
+        };
+        let now: i64 = utils::get_time().as_secs() as i64;
+        if now < lesson.opens_at {
+            return false;
+        }
+        let extension: Option<Extension> = self.get_lesson_extension(&lesson.lesson_id, &user_id).await;
+
+        // Lessons without a time limit only need to be open
+        if lesson.time_limit == 0 {
+            let closes_at: i64 = extensions::closes_at(lesson, extension.as_ref());
+            return closes_at == 0 || now <= closes_at;
+        }
+
+        // Query the database for the bearer's attempt
+        let query = sqlx::query!(
+            "SELECT started_at, submitted_at FROM exam_attempts WHERE lesson_id=? AND user_id=?",
+            lesson.lesson_id, user_id
+        ).fetch_one(&self.conn).await;
+
+        // Make sure the attempt is still running
+        return match query {
+            Ok(r) => r.submitted_at == 0 && now <= ends_at(lesson, r.started_at, extension.as_ref()),
+            Err(_) => false
+        };
+    }
+
+    // The close_exam_attempt() function is used to close the
+    // bearer's attempt at the provided timed lesson once they've
+    // submitted it, so that it can't be submitted again.
+    pub async fn close_exam_attempt(&self, bearer: &str, lesson_id: &str) -> bool {
+        let now: i64 = utils::get_time().as_secs() as i64;
+
+        // Query the database
+        let query = sqlx::query!(
+            "UPDATE exam_attempts SET submitted_at=? WHERE lesson_id=? AND submitted_at=0 AND user_id=(SELECT user_id FROM users WHERE bearer=?)",
+            now, lesson_id, bearer
+        ).execute(&self.conn).await;
+
+        // Return query result
+        return match query {
+            Ok(r) => r.rows_affected() > 0,
+            Err(_) => false
+        };
+    }
+}
+
+// The ends_at() function is used to get when the student's time
+// on the provided lesson is up. A timed lesson ends once the time
+// limit has passed since the student started it, or when the lesson
+// closes for the student, whichever is first. The student's extra
+// time is added to the time limit. If the lesson hasn't been started,
+// when it closes is returned. 0 is returned if the lesson never ends.
+pub fn ends_at(lesson: &Lesson, started_at: i64, extension: Option<&Extension>) -> i64 {
+    let closes_at: i64 = extensions::closes_at(lesson, extension);
+    if lesson.time_limit == 0 || started_at == 0 {
+        return closes_at;
+    }
+    let time_up: i64 = started_at + lesson.time_limit + extension.map_or(0, |e| e.extra_time);
+    return if closes_at > 0 { time_up.min(closes_at) } else { time_up };
+}
+
+// The work_visible() function is used to check whether a student
+// can see the provided lesson's work. The work is hidden until the
+// lesson opens, and a timed lesson's work is hidden until the
+// student starts it.
+pub fn work_visible(lesson: &Lesson, student: &StudentProgress, now: i64) -> bool {
+    return now >= lesson.opens_at && (lesson.time_limit == 0 || student.attempts.contains_key(&lesson.lesson_id));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lesson(closes_at: i64, time_limit: i64) -> Lesson {
+        return Lesson {
+            lesson_id: String::from("lesson"),
+            title: String::from("Lesson"),
+            description: String::new(),
+            video: String::new(),
+            work: String::new(),
+            work_type: String::from("quiz"),
+            work_solutions: String::new(),
+            due_date: 0,
+            solutions_release: String::from("manual"),
+            solutions_released: 0,
+            opens_at: 0,
+            closes_at,
+            time_limit,
+            category_id: String::new(),
+            max_attempts: 1
+        };
+    }
+
+    fn extension(extra_time: i64, closes_at: Option<i64>) -> Extension {
+        return Extension {
+            lesson_id: String::from("lesson"),
+            user_id: String::from("student"),
+            extra_time,
+            due_date: None,
+            closes_at
+        };
+    }
+
+    #[test]
+    fn extra_time_only_extends_the_time_limit() {
+        let extended: Extension = extension(30, None);
+        assert_eq!(extensions::closes_at(&lesson(1000, 60), Some(&extended)), 1000);
+        assert_eq!(ends_at(&lesson(1000, 60), 100, Some(&extended)), 190);
+        assert_eq!(ends_at(&lesson(1000, 60), 950, Some(&extended)), 1000);
+        assert_eq!(ends_at(&lesson(0, 60), 100, Some(&extended)), 190);
+    }
+
+    #[test]
+    fn own_closing_time_replaces_the_lessons() {
+        let extended: Extension = extension(0, Some(2000));
+        assert_eq!(extensions::closes_at(&lesson(1000, 0), Some(&extended)), 2000);
+        assert_eq!(ends_at(&lesson(1000, 60), 1500, Some(&extended)), 1560);
+        assert_eq!(ends_at(&lesson(1000, 0), 0, Some(&extended)), 2000);
+    }
+
+    #[test]
+    fn lessons_without_an_end() {
+        assert_eq!(ends_at(&lesson(0, 0), 100, None), 0);
+        assert_eq!(ends_at(&lesson(0, 60), 0, None), 0);
+    }
+}
@@ -0,0 +1,239 @@
+use crate::lib::{self, utils, structs::{Extension, DueExtension, Lesson}};
+use std::collections::{HashMap, HashSet};
+
+// Database Implementation
+impl lib::handlers::Database {
+    // The get_user_extensions() function is used to get the
+    // extensions the bearer has been given on each lesson
+    // within the provided class, mapped by the lesson's id.
+    pub async fn get_user_extensions(&self, bearer: &str, class_id: &str) -> HashMap<String, Extension> {
+        // Query the database
+        let query = sqlx::query_as!(Extension,
+            "SELECT lesson_extensions.lesson_id as \"lesson_id!\", lesson_extensions.user_id as \"user_id!\", lesson_extensions.extra_time as \"extra_time!\",
+            lesson_extensions.due_date, lesson_extensions.closes_at
+            FROM lesson_extensions
+            JOIN users ON users.user_id=lesson_extensions.user_id
+            WHERE lesson_extensions.class_id=? AND users.bearer=?",
+            class_id, bearer
+        ).fetch_all(&self.conn).await;
+
+        // Return query result
+        return match query {
+            Ok(r) => r.into_iter().map(|e| (e.lesson_id.clone(), e)).collect(),
+            Err(_) => HashMap::new()
+        };
+    }
+
+    // The get_lesson_extension() function is used to get the
+    // extension the provided user has been given on the
+    // provided lesson, if they've been given one.
+    pub async fn get_lesson_extension(&self, lesson_id: &str, user_id: &str) -> Option<Extension> {
+        // Query the database
+        let query = sqlx::query_as!(Extension,
+            "SELECT lesson_id, user_id, extra_time, due_date, closes_at FROM lesson_extensions WHERE lesson_id=? AND user_id=?",
+            lesson_id, user_id
+        ).fetch_one(&self.conn).await;
+
+        // Return query result
+        return match query {
+            Ok(r) => Some(r),
+            Err(_) => None
+        };
+    }
+
+    // The get_lesson_extensions() function is used by the unit
+    // owner to get every extension that has been given on the
+    // provided lesson.
+    pub async fn get_lesson_extensions(
+        &self,
+        bearer: &str,
+        class_id: &str,
+        unit_id: &str,
+        lesson_id: &str
+    ) -> Option<Vec<serde_json::Value>> {
+        // Only the unit owner can see the lesson's extensions
+        if !self.is_unit_owner(bearer, class_id, unit_id).await {
+            return None;
+        }
+
+        // Query the database
+        let query = sqlx::query_as!(Extension,
+            "SELECT lesson_id, user_id, extra_time, due_date, closes_at FROM lesson_extensions WHERE lesson_id=? AND class_id=?",
+            lesson_id, class_id
+        ).fetch_all(&self.conn).await;
+
+        // Convert the extensions into json
+        return match query {
+            Ok(r) => Some(r.iter().map(|e| serde_json::json!({
+                "user_id": e.user_id,
+                "extra_time": e.extra_time,
+                "due_date": e.due_date,
+                "closes_at": e.closes_at
+            })).collect()),
+            Err(_) => None
+        };
+    }
+
+    // The set_lesson_extension() function is used by the unit owner
+    // to give a student extra time on the provided lesson, or their own
+    // due date and closing time. Extensions can only be given to the
+    // students within the class's whitelist. If the student already
+    // has an extension, it's replaced. Changing the student's due date allows
+    // them to be reminded of their new deadline.
+    pub async fn set_lesson_extension(
+        &self,
+        bearer: &str,
+        class_id: &str,
+        unit_id: &str,
+        lesson_id: &str,
+        user_id: &str,
+        data: &serde_json::Value
+    ) -> bool {
+        // Only the unit owner can give extensions
+        if !self.is_unit_owner(bearer, class_id, unit_id).await {
+            return false;
+        }
+
+        // Make sure the lesson is within the unit, and
+        // that the user is a student of the class
+        if self.get_lesson(unit_id, lesson_id).await.is_none() || !self.is_class_student(class_id, user_id).await {
+            return false;
+        }
+
+        // Get the request body variables
+        let extra_time: i64 = data["extra_time"].as_i64().unwrap_or(0);
+        let due_date: Option<i64> = data["due_date"].as_i64();
+        let closes_at: Option<i64> = data["closes_at"].as_i64();
+
+        // Make sure the extension actually extends something
+        if extra_time < 0 || due_date.is_some_and(|d| d <= 0) || closes_at.is_some_and(|c| c <= 0) {
+            return false;
+        }
+        if extra_time == 0 && due_date.is_none() && closes_at.is_none() {
+            return false;
+        }
+
+        // Insert the extension into the database, replacing
+        // the student's existing extension if there is one
+        let query = sqlx::query!(
+            "INSERT INTO lesson_extensions (class_id, lesson_id, user_id, extra_time, due_date, closes_at) VALUES (?, ?, ?, ?, ?, ?)
+            ON CONFLICT (lesson_id, user_id) DO UPDATE SET
+                extra_time=excluded.extra_time,
+                deadline_reminded=(deadline_reminded AND due_date IS excluded.due_date),
+                due_date=excluded.due_date,
+                closes_at=excluded.closes_at",
+            class_id, lesson_id, user_id, extra_time, due_date, closes_at
+        ).execute(&self.conn).await;
+
+        // Return query result
+        return match query {
+            Ok(r) => r.rows_affected() > 0,
+            Err(_) => false
+        };
+    }
+
+    // The delete_lesson_extension() function is used by the
+    // unit owner to revoke a student's extension on the
+    // provided lesson.
+    pub async fn delete_lesson_extension(
+        &self,
+        bearer: &str,
+        class_id: &str,
+        unit_id: &str,
+        lesson_id: &str,
+        user_id: &str
+    ) -> bool {
+        // Only the unit owner can revoke extensions
+        if !self.is_unit_owner(bearer, class_id, unit_id).await {
+            return false;
+        }
+
+        // Query the database
+        let query = sqlx::query!(
+            "DELETE FROM lesson_extensions WHERE lesson_id=? AND user_id=? AND class_id=?",
+            lesson_id, user_id, class_id
+        ).execute(&self.conn).await;
+
+        // Return query result
+        return match query {
+            Ok(r) => r.rows_affected() > 0,
+            Err(_) => false
+        };
+    }
+
+    // The get_extended_students() function is used to get the
+    // students who have their own due date for the provided lesson.
+    // These students aren't reminded of the lesson's due date.
+    pub async fn get_extended_students(&self, lesson_id: &str) -> HashSet<String> {
+        // Query the database
+        let query = sqlx::query!(
+            "SELECT user_id FROM lesson_extensions WHERE lesson_id=? AND due_date IS NOT NULL",
+            lesson_id
+        ).fetch_all(&self.conn).await;
+
+        // Return query result
+        return match query {
+            Ok(r) => r.into_iter().map(|e| e.user_id).collect(),
+            Err(_) => HashSet::new()
+        };
+    }
+
+    // The get_due_extensions() function is used to get all the
+    // students whose own due date is before the provided time
+    // and who haven't been reminded yet.
+    pub async fn get_due_extensions(&self, before: i64) -> Vec<DueExtension> {
+        // Get the current time
+        let now: i64 = utils::get_time().as_secs() as i64;
+
+        // Query the database
+        let query = sqlx::query_as!(DueExtension,
+            "SELECT lessons.lesson_id as \"lesson_id!\", lessons.title as \"title!\", lesson_extensions.due_date as \"due_date!: i64\", classes.class_name as \"class_name!\",
+            users.user_id as \"user_id!\", users.user_name as \"user_name!\", users.email as \"email!\"
+            FROM lesson_extensions
+            JOIN lessons ON lessons.lesson_id=lesson_extensions.lesson_id
+            JOIN classes ON classes.class_id=lesson_extensions.class_id
+            JOIN users ON users.user_id=lesson_extensions.user_id
+            WHERE lesson_extensions.deadline_reminded=0 AND lesson_extensions.due_date>? AND lesson_extensions.due_date<=?",
+            now, before
+        ).fetch_all(&self.conn).await;
+
+        // Return query result
+        return match query {
+            Ok(r) => r,
+            Err(_) => Vec::new()
+        };
+    }
+
+    // The set_extension_reminded() function is used to mark that
+    // the provided student has been reminded of their own due
+    // date, so they aren't reminded twice.
+    pub async fn set_extension_reminded(&self, lesson_id: &str, user_id: &str) -> bool {
+        // Query the database
+        let query = sqlx::query!(
+            "UPDATE lesson_extensions SET deadline_reminded=1 WHERE lesson_id=? AND user_id=?",
+            lesson_id, user_id
+        ).execute(&self.conn).await;
+
+        // Return query result
+        return match query {
+            Ok(r) => r.rows_affected() > 0,
+            Err(_) => false
+        };
+    }
+}
+
+// The due_date() function is used to get when the provided
+// lesson's work is due for a student. A student's own due
+// date replaces the lesson's due date.
+pub fn due_date(lesson: &Lesson, extension: Option<&Extension>) -> i64 {
+    return extension.and_then(|e| e.due_date).unwrap_or(lesson.due_date);
+}
+
+// The closes_at() function is used to get when the provided
+// lesson stops accepting a student's work. A student's own
+// closing time replaces the lesson's closing time. A student's
+// extra time only adds to the time limit, not the closing time.
+// 0 is returned if the lesson never closes.
+pub fn closes_at(lesson: &Lesson, extension: Option<&Extension>) -> i64 {
+    return extension.and_then(|e| e.closes_at).unwrap_or(lesson.closes_at);
+}
@@ -0,0 +1,443 @@
+use crate::lib::{self, structs::{GradeCategory, Grade}};
+use std::collections::HashMap;
+
+// Database Implementation
+impl lib::handlers::Database {
+    // The grade_category_exists() function is used to check whether
+    // the provided category hash already exists. This function
+    // is called in the insert_grade_category() function.
+    async fn grade_category_exists(&self, category_id: &str) -> bool {
+        // Query the database
+        let query = sqlx::query!(
+            "SELECT id FROM grade_categories WHERE category_id=?", category_id
+        ).fetch_one(&self.conn).await;
+
+        // Return whether valid query data has been obtained
+        return query.is_ok();
+    }
+
+    // The insert_grade_category() function is used to create a
+    // new weighted grade category (ex: homework 30%) for the
+    // provided class. Only the class owner can create categories.
+    pub async fn insert_grade_category(
+        &self,
+        bearer: &str,
+        class_id: &str,
+        category_id: &str,
+        data: &serde_json::Value
+    ) -> bool {
+        // If the category already exists, return the function
+        if self.grade_category_exists(category_id).await {
+            return false;
+        }
+
+        // Get the request body variables
+        let category_name: &str = match data["category_name"].as_str() {
+            Some(name) => name,
+            None => return false
+        };
+        let weight: f64 = match data["weight"].as_f64() {
+            Some(weight) => weight,
+            None => return false
+        };
+        let drop_lowest: i64 = data["drop_lowest"].as_i64().unwrap_or(0);
+
+        // Negative weights or drop counts make no sense
+        if weight < 0.0 || drop_lowest < 0 {
+            return false;
+        }
+
+        // Only the class owner is allowed to create categories
+        if !self.is_class_owner(bearer, class_id).await {
+            return false;
+        }
+
+        // Insert the category into the database
+        let query = sqlx::query!(
+            "INSERT INTO grade_categories (owner_bearer, class_id, category_id, category_name, weight, drop_lowest) VALUES (?, ?, ?, ?, ?, ?)",
+            bearer, class_id, category_id, category_name, weight, drop_lowest
+        ).execute(&self.conn).await;
+
+        // Return query result
+        return match query {
+            Ok(r) => r.rows_affected() > 0,
+            Err(_) => false
+        };
+    }
+
+    // The update_grade_category() function is used to change
+    // the name, weight or drop_lowest rule of an existing
+    // grade category. Any value not provided is left as is.
+    pub async fn update_grade_category(
+        &self,
+        bearer: &str,
+        class_id: &str,
+        category_id: &str,
+        data: &serde_json::Value
+    ) -> bool {
+        // Get the current category data so that any
+        // missing values aren't overwritten
+        let category: GradeCategory = match self.get_grade_category(class_id, category_id).await {
+            Some(r) => r,
+            None => return false
+        };
+
+        // Get the request body variables
+        let category_name: &str = data["category_name"].as_str().unwrap_or(&category.category_name);
+        let weight: f64 = data["weight"].as_f64().unwrap_or(category.weight);
+        let drop_lowest: i64 = data["drop_lowest"].as_i64().unwrap_or(category.drop_lowest);
+
+        // Negative weights or drop counts make no sense
+        if weight < 0.0 || drop_lowest < 0 {
+            return false;
+        }
+
+        // Query the database
+        let query = sqlx::query!(
+            "UPDATE grade_categories SET category_name=?, weight=?, drop_lowest=? WHERE category_id=? AND class_id=? AND owner_bearer=?",
+            category_name, weight, drop_lowest, category_id, class_id, bearer
+        ).execute(&self.conn).await;
+
+        // Return query result
+        return match query {
+            Ok(r) => r.rows_affected() > 0,
+            Err(_) => false
+        };
+    }
+
+    // The delete_grade_category() function is used to delete
+    // a grade category from the provided class. Any submissions
+    // within the category will no longer count towards the
+    // student's running average, and the class's lessons are
+    // taken out of the category.
+    pub async fn delete_grade_category(
+        &self,
+        bearer: &str,
+        class_id: &str,
+        category_id: &str
+    ) -> bool {
+        // Query the database
+        let query = sqlx::query!(
+            "DELETE FROM grade_categories WHERE category_id=? AND class_id=? AND owner_bearer=?",
+            category_id, class_id, bearer
+        ).execute(&self.conn).await;
+
+        // If the category was deleted, take it off of the class's lessons
+        return match query {
+            Ok(r) if r.rows_affected() > 0 => {
+                let _ = sqlx::query!(
+                    "UPDATE lessons SET category_id='' WHERE category_id=? AND unit_id IN (SELECT unit_id FROM units WHERE class_id=?)",
+                    category_id, class_id
+                ).execute(&self.conn).await;
+                true
+            },
+            _ => false
+        };
+    }
+
+    // The get_grade_category() function is used to get
+    // a single grade category within the provided class.
+    async fn get_grade_category(&self, class_id: &str, category_id: &str) -> Option<GradeCategory> {
+        // Query the database
+        let query = sqlx::query_as!(GradeCategory,
+            "SELECT category_id, category_name, weight, drop_lowest FROM grade_categories WHERE class_id=? AND category_id=?",
+            class_id, category_id
+        ).fetch_one(&self.conn).await;
+
+        // Return query result
+        return match query {
+            Ok(r) => Some(r),
+            Err(_) => None
+        };
+    }
+
+    // The is_valid_category() function is used to check whether
+    // work can count towards the provided grade category. The
+    // category must be within the provided class, and if the class
+    // uses categories, required work must be given one of them.
+    pub async fn is_valid_category(&self, class_id: &str, category_id: &str, required: bool) -> bool {
+        if !category_id.is_empty() {
+            return self.get_grade_category(class_id, category_id).await.is_some();
+        }
+        return !required || self.get_grade_categories(class_id).await.is_empty();
+    }
+
+    // The get_grade_categories() function is used to get
+    // all the grade categories within the provided class.
+    async fn get_grade_categories(&self, class_id: &str) -> Vec<GradeCategory> {
+        // Query the database
+        let query = sqlx::query_as!(GradeCategory,
+            "SELECT category_id, category_name, weight, drop_lowest FROM grade_categories WHERE class_id=?",
+            class_id
+        ).fetch_all(&self.conn).await;
+
+        // Return query result
+        return match query {
+            Ok(r) => r,
+            Err(_) => Vec::new()
+        };
+    }
+
+    // The get_class_grades() function is used to compute the
+    // running average of every student within the provided class
+    // along with the class average. This function is used in the
+    // teacher dashboard, thus only the class owner can call it.
+    pub async fn get_class_grades(&self, bearer: &str, class_id: &str) -> Option<serde_json::Value> {
+        // Only the class owner can see every student's grades
+        if !self.is_class_owner(bearer, class_id).await {
+            return None;
+        }
+
+        // Query the database for all the marked submissions
+        let query = sqlx::query_as!(Grade,
+            "SELECT submitter_bearer, lesson_id, category_id, mark, out_of FROM submissions WHERE class_id=? AND graded=1",
+            class_id
+        ).fetch_all(&self.conn).await;
+        let grades: Vec<Grade> = match query {
+            Ok(r) => r,
+            Err(_) => return None
+        };
+        let categories: Vec<GradeCategory> = self.get_grade_categories(class_id).await;
+
+        // Group the marked submissions by the student who submitted them
+        let mut students: HashMap<&str, Vec<&Grade>> = HashMap::new();
+        for grade in grades.iter() {
+            students.entry(&grade.submitter_bearer).or_default().push(grade);
+        }
+
+        // Compute each student's running average
+        let students: Vec<serde_json::Value> = futures::future::join_all(
+            students.into_iter().map(|(submitter_bearer, grades)| {
+                let categories = &categories;
+                async move {
+                    let mut result: serde_json::Value = compute_grades(categories, &grades);
+                    let user_id: String = self.get_user_id_by_bearer(submitter_bearer).await.unwrap_or_default();
+                    result["user_name"] = serde_json::json!(self.get_user_name_by_id(&user_id).await);
+                    result["user_id"] = serde_json::json!(user_id);
+                    result
+                }
+            })
+        ).await;
+
+        // The class average is the mean of each student's average
+        let averages: Vec<f64> = students.iter().filter_map(|s| s["average"].as_f64()).collect();
+        return Some(serde_json::json!({
+            "class_average": mean(&averages),
+            "students": students
+        }));
+    }
+
+    // The get_user_grades() function is used to compute the
+    // running average of the provided bearer within the provided
+    // class. This function is used for students to see their grades.
+    pub async fn get_user_grades(&self, class_id: &str, bearer: &str) -> Option<serde_json::Value> {
+        // Query the database for the student's marked submissions
+        let query = sqlx::query_as!(Grade,
+            "SELECT submitter_bearer, lesson_id, category_id, mark, out_of FROM submissions WHERE class_id=? AND submitter_bearer=? AND graded=1",
+            class_id, bearer
+        ).fetch_all(&self.conn).await;
+
+        // Return the computed grades
+        return match query {
+            Ok(r) => Some(compute_grades(
+                &self.get_grade_categories(class_id).await,
+                &r.iter().collect::<Vec<&Grade>>()
+            )),
+            Err(_) => None
+        };
+    }
+}
+
+// The mean() function is used to get the average
+// of the provided values. None is returned if there
+// are no values to average.
+fn mean(values: &[f64]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    return Some(values.iter().sum::<f64>() / values.len() as f64);
+}
+
+// The percent() function is used to convert
+// the provided grade into a percentage.
+fn percent(grade: &&Grade) -> f64 {
+    return grade.mark / grade.out_of * 100.0;
+}
+
+// The best_attempts() function is used to keep only the best
+// marked attempt at each lesson's work, so that retrying a
+// quiz doesn't count more than once. Submissions that aren't
+// for a lesson are all kept.
+fn best_attempts<'a>(grades: &[&'a Grade]) -> Vec<&'a Grade> {
+    let mut best: HashMap<&str, &Grade> = HashMap::new();
+    let mut result: Vec<&Grade> = Vec::new();
+    for grade in grades {
+        if grade.lesson_id.is_empty() {
+            result.push(grade);
+            continue;
+        }
+        let entry: &mut &Grade = best.entry(&grade.lesson_id).or_insert(grade);
+        if percent(grade) > percent(entry) {
+            *entry = grade;
+        }
+    }
+    result.extend(best.into_values());
+    return result;
+}
+
+// The compute_grades() function is used to compute a student's
+// average within each grade category, and their weighted running
+// average. Only the best attempt at each lesson counts. Each
+// category drops the student's lowest marks (always keeping at
+// least one) and only categories with marked work count towards
+// the running average. If the class has no categories, the
+// running average is the mean of every marked submission.
+fn compute_grades(categories: &[GradeCategory], grades: &[&Grade]) -> serde_json::Value {
+    let grades: Vec<&Grade> = best_attempts(grades);
+
+    // If the class doesn't use categories, average everything
+    if categories.is_empty() {
+        let percents: Vec<f64> = grades.iter().map(percent).collect();
+        return serde_json::json!({
+            "average": mean(&percents),
+            "categories": []
+        });
+    }
+
+    // Compute the average for each category
+    let mut weighted_sum: f64 = 0.0;
+    let mut weight_total: f64 = 0.0;
+    let categories: Vec<serde_json::Value> = categories.iter().map(|c| {
+        let mut percents: Vec<f64> = grades.iter()
+            .filter(|g| g.category_id == c.category_id)
+            .map(percent)
+            .collect();
+        percents.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+
+        // Drop the lowest marks while keeping atleast one
+        let dropped: usize = (c.drop_lowest as usize).min(percents.len().saturating_sub(1));
+        let average: Option<f64> = mean(&percents[dropped..]);
+
+        // Add the category average to the running average
+        if let Some(average) = average {
+            weighted_sum += average * c.weight;
+            weight_total += c.weight;
+        }
+        serde_json::json!({
+            "category_id": c.category_id,
+            "category_name": c.category_name,
+            "weight": c.weight,
+            "drop_lowest": c.drop_lowest,
+            "graded": percents.len(),
+            "dropped": dropped,
+            "average": average
+        })
+    }).collect();
+
+    // Return the weighted running average
+    return serde_json::json!({
+        "average": if weight_total > 0.0 { Some(weighted_sum / weight_total) } else { None },
+        "categories": categories
+    });
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grade(lesson_id: &str, category_id: &str, mark: f64, out_of: f64) -> Grade {
+        return Grade {
+            submitter_bearer: String::from("bearer"),
+            lesson_id: lesson_id.to_string(),
+            category_id: category_id.to_string(),
+            mark,
+            out_of
+        };
+    }
+
+    fn category(category_id: &str, weight: f64, drop_lowest: i64) -> GradeCategory {
+        return GradeCategory {
+            category_id: category_id.to_string(),
+            category_name: category_id.to_string(),
+            weight,
+            drop_lowest
+        };
+    }
+
+    #[test]
+    fn averages_everything_without_categories() {
+        let grades: Vec<Grade> = vec![grade("", "", 5.0, 10.0), grade("", "", 10.0, 10.0)];
+        let result = compute_grades(&[], &grades.iter().collect::<Vec<&Grade>>());
+        assert_eq!(result["average"], 75.0);
+        assert_eq!(result["categories"], serde_json::json!([]));
+    }
+
+    #[test]
+    fn no_grades_has_no_average() {
+        assert!(compute_grades(&[], &[])["average"].is_null());
+        assert!(compute_grades(&[category("hw", 1.0, 0)], &[])["average"].is_null());
+    }
+
+    #[test]
+    fn weights_category_averages() {
+        let categories: Vec<GradeCategory> = vec![category("hw", 30.0, 0), category("test", 70.0, 0)];
+        let grades: Vec<Grade> = vec![
+            grade("", "hw", 10.0, 10.0),
+            grade("", "hw", 5.0, 10.0),
+            grade("", "test", 40.0, 100.0)
+        ];
+        let result = compute_grades(&categories, &grades.iter().collect::<Vec<&Grade>>());
+        assert_eq!(result["categories"][0]["average"], 75.0);
+        assert_eq!(result["categories"][1]["average"], 40.0);
+        assert!((result["average"].as_f64().unwrap() - 50.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn skips_categories_without_grades() {
+        let categories: Vec<GradeCategory> = vec![category("hw", 30.0, 0), category("test", 70.0, 0)];
+        let grades: Vec<Grade> = vec![grade("", "hw", 8.0, 10.0), grade("", "other", 0.0, 10.0)];
+        let result = compute_grades(&categories, &grades.iter().collect::<Vec<&Grade>>());
+        assert_eq!(result["average"], 80.0);
+        assert!(result["categories"][1]["average"].is_null());
+        assert_eq!(result["categories"][1]["graded"], 0);
+    }
+
+    #[test]
+    fn drops_lowest_marks() {
+        let categories: Vec<GradeCategory> = vec![category("hw", 1.0, 2)];
+        let grades: Vec<Grade> = vec![
+            grade("", "hw", 2.0, 10.0),
+            grade("", "hw", 9.0, 10.0),
+            grade("", "hw", 4.0, 10.0),
+            grade("", "hw", 7.0, 10.0)
+        ];
+        let result = compute_grades(&categories, &grades.iter().collect::<Vec<&Grade>>());
+        assert_eq!(result["categories"][0]["graded"], 4);
+        assert_eq!(result["categories"][0]["dropped"], 2);
+        assert_eq!(result["average"], 80.0);
+    }
+
+    #[test]
+    fn drop_lowest_keeps_one_mark() {
+        let categories: Vec<GradeCategory> = vec![category("hw", 1.0, 5)];
+        let grades: Vec<Grade> = vec![grade("", "hw", 3.0, 10.0), grade("", "hw", 6.0, 10.0)];
+        let result = compute_grades(&categories, &grades.iter().collect::<Vec<&Grade>>());
+        assert_eq!(result["categories"][0]["dropped"], 1);
+        assert_eq!(result["average"], 60.0);
+    }
+
+    #[test]
+    fn counts_best_attempt_per_lesson() {
+        let categories: Vec<GradeCategory> = vec![category("quiz", 1.0, 0)];
+        let grades: Vec<Grade> = vec![
+            grade("lesson", "quiz", 2.0, 10.0),
+            grade("lesson", "quiz", 8.0, 10.0),
+            grade("lesson", "quiz", 5.0, 10.0),
+            grade("other", "quiz", 4.0, 10.0)
+        ];
+        let result = compute_grades(&categories, &grades.iter().collect::<Vec<&Grade>>());
+        assert_eq!(result["categories"][0]["graded"], 2);
+        assert_eq!(result["average"], 60.0);
+    }
+}
\ No newline at end of file
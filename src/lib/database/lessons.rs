@@ -0,0 +1,790 @@
+use crate::lib::{
+    self, utils, quiz, database::extensions, structs::{Lesson, DueLesson, Prerequisite, StudentProgress, Extension}
+};
+use std::collections::{HashMap, HashSet};
+
+// The SOLUTION_RELEASES are the policies for when a lesson's
+// work solutions are shown to students. Solutions can be
+// shown after the due date, after the student submits their
+// work, or only once the teacher releases them.
+pub static SOLUTION_RELEASES: [&str; 3] = ["due_date", "submitted", "manual"];
+
+// Database Implementation
+impl lib::handlers::Database {
+    // The lesson_exists() function is used to check whether
+    // the provided lesson hash already exists. This function
+    // is called in the insert_unit_lesson() function.
+    async fn lesson_exists(&self, lesson_id: &str) -> bool {
+        // Query the database
+        let query = sqlx::query!(
+            "SELECT id FROM lessons WHERE lesson_id=?", lesson_id
+        ).fetch_one(&self.conn).await;
+
+        // Return whether valid query data has been obtained
+        return query.is_ok();
+    }
+
+    // The get_lesson() function is used to get all the
+    // lesson data for the provided lesson within the
+    // provided unit.
+    pub async fn get_lesson(&self, unit_id: &str, lesson_id: &str) -> Option<Lesson> {
+        // Query the database
+        let query = sqlx::query_as!(Lesson,
+            "SELECT lesson_id, title, description, video, work, work_type, work_solutions, due_date, solutions_release, solutions_released, opens_at, closes_at, time_limit, category_id, max_attempts FROM lessons WHERE unit_id=? AND lesson_id=?",
+            unit_id, lesson_id
+        ).fetch_one(&self.conn).await;
+
+        // Return query result
+        return match query {
+            Ok(r) => Some(r),
+            Err(_) => None
+        };
+    }
+
+    // The is_class_lesson() function is used to check whether
+    // the provided lesson is within one of the provided class's units.
+    pub async fn is_class_lesson(&self, class_id: &str, lesson_id: &str) -> bool {
+        // Query the database
+        let query = sqlx::query!(
+            "SELECT lessons.id FROM lessons JOIN units ON units.unit_id=lessons.unit_id WHERE lessons.lesson_id=? AND units.class_id=?",
+            lesson_id, class_id
+        ).fetch_one(&self.conn).await;
+
+        // Return whether valid query data has been obtained
+        return query.is_ok();
+    }
+
+    // The get_class_lesson() function is used to get all the
+    // lesson data for the provided lesson, as long as it's within
+    // one of the provided class's units.
+    pub async fn get_class_lesson(&self, class_id: &str, lesson_id: &str) -> Option<Lesson> {
+        // Query the database
+        let query = sqlx::query_as!(Lesson,
+            "SELECT lessons.lesson_id as \"lesson_id!\", lessons.title as \"title!\", lessons.description as \"description!\", lessons.video as \"video!\",
+            lessons.work as \"work!\", lessons.work_type as \"work_type!\", lessons.work_solutions as \"work_solutions!\", lessons.due_date as \"due_date!\",
+            lessons.solutions_release as \"solutions_release!\", lessons.solutions_released as \"solutions_released!\",
+            lessons.opens_at as \"opens_at!\", lessons.closes_at as \"closes_at!\", lessons.time_limit as \"time_limit!\", lessons.category_id as \"category_id!\",
+            lessons.max_attempts as \"max_attempts!\"
+            FROM lessons JOIN units ON units.unit_id=lessons.unit_id WHERE lessons.lesson_id=? AND units.class_id=?",
+            lesson_id, class_id
+        ).fetch_one(&self.conn).await;
+
+        // Return query result
+        return match query {
+            Ok(r) => Some(r),
+            Err(_) => None
+        };
+    }
+
+    // The insert_unit_lesson() function is used to insert a new
+    // lesson into the provided unit. Only the owner of the unit
+    // can add lessons to it. A due date of 0 means the lesson's
+    // work has no due date. By default, the lesson's solutions
+    // are shown to students once the due date has passed. New
+    // lessons are placed after the unit's existing lessons.
+    pub async fn insert_unit_lesson(
+        &self,
+        bearer: &str,
+        class_id: &str,
+        unit_id: &str,
+        lesson_id: &str,
+        data: &serde_json::Value
+    ) -> bool {
+        // If the lesson already exists, return false
+        if self.lesson_exists(lesson_id).await {
+            return false;
+        }
+
+        // Only the unit owner can add lessons
+        if !self.is_unit_owner(bearer, class_id, unit_id).await {
+            return false;
+        }
+
+        // Get the request body variables
+        let title: &str = match data["title"].as_str() {
+            Some(title) => title,
+            None => return false
+        };
+        let description: &str = data["description"].as_str().unwrap_or("");
+        let video: &str = data["video"].as_str().unwrap_or("");
+        let work: &str = data["work"].as_str().unwrap_or("");
+        let work_solutions: &str = data["work_solutions"].as_str().unwrap_or("");
+        let due_date: i64 = data["due_date"].as_i64().unwrap_or(0);
+        let work_type: &str = data["work_type"].as_str().unwrap_or("text");
+        let solutions_release: &str = data["solutions_release"].as_str().unwrap_or("due_date");
+        let solutions_released: i64 = data["solutions_released"].as_bool().unwrap_or(false) as i64;
+        let opens_at: i64 = data["opens_at"].as_i64().unwrap_or(0);
+        let closes_at: i64 = data["closes_at"].as_i64().unwrap_or(0);
+        let time_limit: i64 = data["time_limit"].as_i64().unwrap_or(0);
+        let category_id: &str = data["category_id"].as_str().unwrap_or("");
+        let max_attempts: i64 = data["max_attempts"].as_i64().unwrap_or(1);
+
+        // Make sure the homework, solution release policy, availability
+        // window, number of attempts and grade category are valid. Bank
+        // quizzes can only draw from the class's own units.
+        if !is_valid_work(work_type, work, work_solutions)
+            || (work_type == "bank" && !is_valid_draws(work, &self.get_class_unit_ids(class_id).await))
+            || !SOLUTION_RELEASES.contains(&solutions_release)
+            || !is_valid_window(opens_at, closes_at, time_limit)
+            || !is_valid_attempts(work_type, time_limit, max_attempts)
+            || !self.is_valid_category(class_id, category_id, quiz::is_quiz(work_type)).await
+        {
+            return false;
+        }
+
+        // Get the lesson's prerequisites
+        let prerequisites: Vec<(&str, Option<f64>)> = match self.get_prerequisites_body(class_id, lesson_id, data).await {
+            Some(r) => r.unwrap_or_default(),
+            None => return false
+        };
+
+        // Insert the lesson and it's prerequisites
+        // within a single transaction
+        let mut tx = match self.conn.begin().await {
+            Ok(tx) => tx,
+            Err(_) => return false
+        };
+        let query = sqlx::query!(
+            "INSERT INTO lessons (owner_bearer, unit_id, lesson_id, title, description, video, work, work_type, work_solutions, due_date, solutions_release, solutions_released, opens_at, closes_at, time_limit, category_id, max_attempts, position) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, (SELECT COALESCE(MAX(position) + 1, 0) FROM lessons WHERE unit_id=?))",
+            bearer, unit_id, lesson_id, title, description, video, work, work_type, work_solutions, due_date, solutions_release, solutions_released, opens_at, closes_at, time_limit, category_id, max_attempts, unit_id
+        ).execute(&mut tx).await;
+        if !matches!(query, Ok(r) if r.rows_affected() > 0) || !set_lesson_prerequisites(&mut tx, lesson_id, &prerequisites).await {
+            return false;
+        }
+        return tx.commit().await.is_ok();
+    }
+
+    // The update_unit_lesson() function is used to modify any
+    // of the lesson's data. Any value that isn't provided is
+    // left as is. Changing the due date allows the students
+    // to be reminded of the new deadline.
+    pub async fn update_unit_lesson(
+        &self,
+        bearer: &str,
+        class_id: &str,
+        unit_id: &str,
+        lesson_id: &str,
+        data: &serde_json::Value
+    ) -> bool {
+        // Only the unit owner can modify lessons
+        if !self.is_unit_owner(bearer, class_id, unit_id).await {
+            return false;
+        }
+
+        // Get the current lesson data so that any
+        // missing values aren't overwritten
+        let lesson: Lesson = match self.get_lesson(unit_id, lesson_id).await {
+            Some(r) => r,
+            None => return false
+        };
+
+        // Get the request body variables
+        let title: &str = data["title"].as_str().unwrap_or(&lesson.title);
+        let description: &str = data["description"].as_str().unwrap_or(&lesson.description);
+        let video: &str = data["video"].as_str().unwrap_or(&lesson.video);
+        let work: &str = data["work"].as_str().unwrap_or(&lesson.work);
+        let work_solutions: &str = data["work_solutions"].as_str().unwrap_or(&lesson.work_solutions);
+        let due_date: i64 = data["due_date"].as_i64().unwrap_or(lesson.due_date);
+        let work_type: &str = data["work_type"].as_str().unwrap_or(&lesson.work_type);
+        let solutions_release: &str = data["solutions_release"].as_str().unwrap_or(&lesson.solutions_release);
+        let solutions_released: i64 = data["solutions_released"].as_bool().map_or(lesson.solutions_released, |r| r as i64);
+        let opens_at: i64 = data["opens_at"].as_i64().unwrap_or(lesson.opens_at);
+        let closes_at: i64 = data["closes_at"].as_i64().unwrap_or(lesson.closes_at);
+        let time_limit: i64 = data["time_limit"].as_i64().unwrap_or(lesson.time_limit);
+        let category_id: &str = data["category_id"].as_str().unwrap_or(&lesson.category_id);
+        let max_attempts: i64 = data["max_attempts"].as_i64().unwrap_or(lesson.max_attempts);
+
+        // Make sure the homework, solution release policy, availability
+        // window, number of attempts and grade category are valid. Bank
+        // quizzes can only draw from the class's own units.
+        if !is_valid_work(work_type, work, work_solutions)
+            || (work_type == "bank" && !is_valid_draws(work, &self.get_class_unit_ids(class_id).await))
+            || !SOLUTION_RELEASES.contains(&solutions_release)
+            || !is_valid_window(opens_at, closes_at, time_limit)
+            || !is_valid_attempts(work_type, time_limit, max_attempts)
+            || !self.is_valid_category(class_id, category_id, quiz::is_quiz(work_type)).await
+        {
+            return false;
+        }
+
+        // Get the lesson's new prerequisites, if any were provided
+        let prerequisites: Option<Vec<(&str, Option<f64>)>> = match self.get_prerequisites_body(class_id, lesson_id, data).await {
+            Some(r) => r,
+            None => return false
+        };
+
+        // Update the lesson and replace it's prerequisites, if
+        // new ones were provided, within a single transaction
+        let mut tx = match self.conn.begin().await {
+            Ok(tx) => tx,
+            Err(_) => return false
+        };
+        let query = sqlx::query!(
+            "UPDATE lessons SET title=?, description=?, video=?, work=?, work_type=?, work_solutions=?, due_date=?, deadline_reminded=(deadline_reminded AND due_date=?), solutions_release=?, solutions_released=?, opens_at=?, closes_at=?, time_limit=?, category_id=?, max_attempts=? WHERE lesson_id=? AND unit_id=?",
+            title, description, video, work, work_type, work_solutions, due_date, due_date, solutions_release, solutions_released, opens_at, closes_at, time_limit, category_id, max_attempts, lesson_id, unit_id
+        ).execute(&mut tx).await;
+        if !matches!(query, Ok(r) if r.rows_affected() > 0) {
+            return false;
+        }
+        if let Some(prerequisites) = prerequisites {
+            if !set_lesson_prerequisites(&mut tx, lesson_id, &prerequisites).await {
+                return false;
+            }
+        }
+        return tx.commit().await.is_ok();
+    }
+
+    // The delete_unit_lesson() function is used to delete
+    // the provided lesson from the provided unit, along with
+    // the progress every student has made through it and any
+    // prerequisites that refer to it.
+    pub async fn delete_unit_lesson(
+        &self,
+        bearer: &str,
+        class_id: &str,
+        unit_id: &str,
+        lesson_id: &str
+    ) -> bool {
+        // Only the unit owner can delete lessons
+        if !self.is_unit_owner(bearer, class_id, unit_id).await {
+            return false;
+        }
+
+        // Query the database
+        let query = sqlx::query!(
+            "DELETE FROM lessons WHERE lesson_id=? AND unit_id=?",
+            lesson_id, unit_id
+        ).execute(&self.conn).await;
+
+        // If the lesson was deleted, delete the students' progress too
+        return match query {
+            Ok(r) if r.rows_affected() > 0 => {
+                let _ = sqlx::query!(
+                    "DELETE FROM lesson_progress WHERE lesson_id=?", lesson_id
+                ).execute(&self.conn).await;
+                let _ = sqlx::query!(
+                    "DELETE FROM lesson_prerequisites WHERE lesson_id=? OR required_lesson_id=?", lesson_id, lesson_id
+                ).execute(&self.conn).await;
+                let _ = sqlx::query!(
+                    "DELETE FROM quiz_draws WHERE lesson_id=?", lesson_id
+                ).execute(&self.conn).await;
+                let _ = sqlx::query!(
+                    "DELETE FROM exam_attempts WHERE lesson_id=?", lesson_id
+                ).execute(&self.conn).await;
+                let _ = sqlx::query!(
+                    "DELETE FROM lesson_extensions WHERE lesson_id=?", lesson_id
+                ).execute(&self.conn).await;
+                true
+            },
+            _ => false
+        };
+    }
+
+    // The get_prerequisites_body() function is used to get the
+    // prerequisites within the request body. Each prerequisite must
+    // be another lesson within the same class, and the lesson can't
+    // end up requiring itself. None is returned if
+    // the prerequisites are invalid, and Some(None) if they weren't
+    // provided at all.
+    async fn get_prerequisites_body<'a>(
+        &self,
+        class_id: &str,
+        lesson_id: &str,
+        data: &'a serde_json::Value
+    ) -> Option<Option<Vec<(&'a str, Option<f64>)>>> {
+        let body: &Vec<serde_json::Value> = match data.get("prerequisites") {
+            Some(p) => p.as_array()?,
+            None => return Some(None)
+        };
+
+        // Make sure each of the prerequisites is valid
+        let mut prerequisites: Vec<(&str, Option<f64>)> = Vec::new();
+        for p in body {
+            let required_lesson_id: &str = p["lesson_id"].as_str()?;
+            if !self.is_class_lesson(class_id, required_lesson_id).await {
+                return None;
+            }
+            prerequisites.push((required_lesson_id, p["min_score"].as_f64()));
+        }
+
+        // Make sure the lesson doesn't end up requiring itself
+        let required: Vec<&str> = prerequisites.iter().map(|(id, _)| *id).collect();
+        if creates_cycle(lesson_id, &required, &self.get_class_prerequisites(class_id).await) {
+            return None;
+        }
+        return Some(Some(prerequisites));
+    }
+
+    // The get_class_prerequisites() function is used to get the
+    // prerequisites of every lesson within the provided class,
+    // mapped by the lesson's id.
+    pub async fn get_class_prerequisites(&self, class_id: &str) -> HashMap<String, Vec<Prerequisite>> {
+        // Query the database
+        let query = sqlx::query_as!(Prerequisite,
+            "SELECT lesson_prerequisites.lesson_id as \"lesson_id!\", lesson_prerequisites.required_lesson_id as \"required_lesson_id!\", lesson_prerequisites.min_score
+            FROM lesson_prerequisites
+            JOIN lessons ON lessons.lesson_id=lesson_prerequisites.lesson_id
+            JOIN units ON units.unit_id=lessons.unit_id
+            WHERE units.class_id=?",
+            class_id
+        ).fetch_all(&self.conn).await;
+
+        // Group the prerequisites by their lesson
+        let mut prerequisites: HashMap<String, Vec<Prerequisite>> = HashMap::new();
+        for p in query.unwrap_or_default() {
+            prerequisites.entry(p.lesson_id.clone()).or_default().push(p);
+        }
+        return prerequisites;
+    }
+
+    // The reorder_unit_lessons() function is used to change the
+    // order of the provided unit's lessons. The request body must
+    // contain every lesson within the unit, in their new order. All
+    // the lessons are moved within a single transaction.
+    pub async fn reorder_unit_lessons(
+        &self,
+        bearer: &str,
+        class_id: &str,
+        unit_id: &str,
+        data: &serde_json::Value
+    ) -> bool {
+        // Only the unit owner can reorder lessons
+        if !self.is_unit_owner(bearer, class_id, unit_id).await {
+            return false;
+        }
+
+        // Get the new order of the lessons
+        let lesson_ids: Vec<&str> = match utils::get_ids(data, "lesson_ids") {
+            Some(ids) => ids,
+            None => return false
+        };
+
+        // Make sure every lesson within the unit was provided
+        let query = sqlx::query!(
+            "SELECT lesson_id FROM lessons WHERE unit_id=?", unit_id
+        ).fetch_all(&self.conn).await;
+        let current: Vec<String> = match query {
+            Ok(r) => r.into_iter().map(|l| l.lesson_id).collect(),
+            Err(_) => return false
+        };
+        if !utils::same_ids(&current, &lesson_ids) {
+            return false;
+        }
+
+        // Update each lesson's position within a single transaction
+        let mut tx = match self.conn.begin().await {
+            Ok(tx) => tx,
+            Err(_) => return false
+        };
+        for (position, lesson_id) in lesson_ids.iter().enumerate() {
+            let position: i64 = position as i64;
+            let query = sqlx::query!(
+                "UPDATE lessons SET position=? WHERE lesson_id=? AND unit_id=?",
+                position, lesson_id, unit_id
+            ).execute(&mut tx).await;
+            if query.is_err() {
+                return false;
+            }
+        }
+        return tx.commit().await.is_ok();
+    }
+
+    // The move_unit_lesson() function is used to move the provided
+    // lesson into another unit within the same class. The lesson is
+    // placed at the provided position, or after the unit's existing
+    // lessons if no position is provided. The unit owner must own
+    // both of the units.
+    pub async fn move_unit_lesson(
+        &self,
+        bearer: &str,
+        class_id: &str,
+        unit_id: &str,
+        lesson_id: &str,
+        data: &serde_json::Value
+    ) -> bool {
+        // Get the unit the lesson is being moved to
+        let target_id: &str = match data["unit_id"].as_str() {
+            Some(id) => id,
+            None => return false
+        };
+
+        // Only the owner of both units can move the lesson
+        if !self.is_unit_owner(bearer, class_id, unit_id).await
+            || !self.is_unit_owner(bearer, class_id, target_id).await
+        {
+            return false;
+        }
+
+        // Get the lesson's current position
+        let query = sqlx::query!(
+            "SELECT position FROM lessons WHERE lesson_id=? AND unit_id=?",
+            lesson_id, unit_id
+        ).fetch_one(&self.conn).await;
+        let current: i64 = match query {
+            Ok(r) => r.position,
+            Err(_) => return false
+        };
+
+        // Move the lesson within a single transaction
+        let mut tx = match self.conn.begin().await {
+            Ok(tx) => tx,
+            Err(_) => return false
+        };
+
+        // Close the gap the lesson leaves behind
+        let query = sqlx::query!(
+            "UPDATE lessons SET position=position - 1 WHERE unit_id=? AND position>?",
+            unit_id, current
+        ).execute(&mut tx).await;
+        if query.is_err() {
+            return false;
+        }
+
+        // Get the position the lesson is being moved to
+        let query = sqlx::query!(
+            "SELECT COUNT(*) as count FROM lessons WHERE unit_id=? AND lesson_id!=?",
+            target_id, lesson_id
+        ).fetch_one(&mut tx).await;
+        let count: i64 = match query {
+            Ok(r) => r.count as i64,
+            Err(_) => return false
+        };
+        let position: i64 = data["position"].as_i64().unwrap_or(count).clamp(0, count);
+
+        // Make room for the lesson within the unit
+        let query = sqlx::query!(
+            "UPDATE lessons SET position=position + 1 WHERE unit_id=? AND position>=? AND lesson_id!=?",
+            target_id, position, lesson_id
+        ).execute(&mut tx).await;
+        if query.is_err() {
+            return false;
+        }
+
+        // Move the lesson into the unit
+        let query = sqlx::query!(
+            "UPDATE lessons SET unit_id=?, position=? WHERE lesson_id=?",
+            target_id, position, lesson_id
+        ).execute(&mut tx).await;
+
+        // Return query result
+        return match query {
+            Ok(_) => tx.commit().await.is_ok(),
+            Err(_) => false
+        };
+    }
+
+    // The get_due_lessons() function is used to get all the
+    // lessons that are due before the provided time and
+    // whose students haven't been reminded yet.
+    pub async fn get_due_lessons(&self, before: i64) -> Vec<DueLesson> {
+        // Get the current time
+        let now: i64 = lib::utils::get_time().as_secs() as i64;
+
+        // Query the database
+        let query = sqlx::query_as!(DueLesson,
+            "SELECT lessons.lesson_id as \"lesson_id!\", lessons.title as \"title!\", lessons.due_date as \"due_date!\", units.class_id as \"class_id!\", classes.class_name as \"class_name!\"
+            FROM lessons
+            JOIN units ON units.unit_id=lessons.unit_id
+            JOIN classes ON classes.class_id=units.class_id
+            WHERE lessons.deadline_reminded=0 AND lessons.due_date>? AND lessons.due_date<=?",
+            now, before
+        ).fetch_all(&self.conn).await;
+
+        // Return query result
+        return match query {
+            Ok(r) => r,
+            Err(_) => Vec::new()
+        };
+    }
+
+    // The set_deadline_reminded() function is used to mark
+    // that the provided lesson's students have been reminded
+    // of its due date, so they aren't reminded twice.
+    pub async fn set_deadline_reminded(&self, lesson_id: &str) -> bool {
+        // Query the database
+        let query = sqlx::query!(
+            "UPDATE lessons SET deadline_reminded=1 WHERE lesson_id=?", lesson_id
+        ).execute(&self.conn).await;
+
+        // Return query result
+        return match query {
+            Ok(r) => r.rows_affected() > 0,
+            Err(_) => false
+        };
+    }
+}
+
+// The set_lesson_prerequisites() function is used to replace
+// the provided lesson's prerequisites within the provided
+// transaction, so that they're stored along with the lesson.
+async fn set_lesson_prerequisites(
+    tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+    lesson_id: &str,
+    prerequisites: &[(&str, Option<f64>)]
+) -> bool {
+    // Delete the lesson's current prerequisites
+    let query = sqlx::query!(
+        "DELETE FROM lesson_prerequisites WHERE lesson_id=?", lesson_id
+    ).execute(&mut *tx).await;
+    if query.is_err() {
+        return false;
+    }
+
+    // Insert the new prerequisites
+    for (required_lesson_id, min_score) in prerequisites {
+        let query = sqlx::query!(
+            "INSERT INTO lesson_prerequisites (lesson_id, required_lesson_id, min_score) VALUES (?, ?, ?)",
+            lesson_id, required_lesson_id, min_score
+        ).execute(&mut *tx).await;
+        if query.is_err() {
+            return false;
+        }
+    }
+    return true;
+}
+
+// The creates_cycle() function is used to check whether
+// requiring the provided lessons before the provided lesson
+// would create a cycle, where a lesson ends up requiring
+// itself through the class's other prerequisites.
+fn creates_cycle(lesson_id: &str, required: &[&str], prerequisites: &HashMap<String, Vec<Prerequisite>>) -> bool {
+    let mut visited: HashSet<&str> = HashSet::new();
+    let mut stack: Vec<&str> = required.to_vec();
+    while let Some(id) = stack.pop() {
+        if id == lesson_id {
+            return true;
+        }
+        if !visited.insert(id) {
+            continue;
+        }
+        if let Some(p) = prerequisites.get(id) {
+            stack.extend(p.iter().map(|p| p.required_lesson_id.as_str()));
+        }
+    }
+    return false;
+}
+
+// The solutions_released() function is used to get whether the
+// provided lesson's solutions can be shown to a student. Solutions
+// the teacher has released are always shown, otherwise the lesson's
+// release policy is used. A student with their own due date doesn't
+// get the solutions until their due date has passed. Under the
+// submitted policy, the solutions are shown once the student has used
+// all of their attempts. A quiz attempt only counts once it's marked.
+pub fn solutions_released(lesson: &Lesson, extension: Option<&Extension>, submitted: i64, now: i64) -> bool {
+    if lesson.solutions_released == 1 {
+        return true;
+    }
+    let due_date: i64 = extensions::due_date(lesson, extension);
+    return match lesson.solutions_release.as_str() {
+        "due_date" => due_date > 0 && now >= due_date,
+        "submitted" => submitted >= lesson.max_attempts,
+        _ => false
+    };
+}
+
+// The can_unsubmit() function is used to check whether a student
+// can delete their submission to the provided lesson. Marked work
+// and quiz attempts are kept, as deleting them would give the
+// student their attempt back. Once the solutions have been shown
+// to the student, their submission is kept so they stay released.
+pub fn can_unsubmit(lesson: &Lesson, extension: Option<&Extension>, submitted: i64, graded: i64, now: i64) -> bool {
+    return graded == 0
+        && !quiz::is_quiz(&lesson.work_type)
+        && !solutions_released(lesson, extension, submitted, now);
+}
+
+// The is_valid_work() function is used to check whether the
+// provided homework is valid for it's type. Text work is marked
+// by the teacher, while a quiz must contain questions that can
+// all be graded automatically using the work solutions. A bank
+// quiz only says how many questions to draw from each unit.
+pub fn is_valid_work(work_type: &str, work: &str, work_solutions: &str) -> bool {
+    return match work_type {
+        "text" => true,
+        "bank" => quiz::get_draws(work).is_some(),
+        "quiz" => match (quiz::get_questions(work), serde_json::from_str(work_solutions)) {
+            (Some(questions), Ok(key)) => quiz::is_valid(&questions, &key),
+            _ => false
+        },
+        _ => false
+    };
+}
+
+// The is_valid_draws() function is used to check whether
+// the provided bank quiz only draws questions from the
+// provided units.
+pub fn is_valid_draws(work: &str, unit_ids: &HashSet<String>) -> bool {
+    return quiz::get_draws(work).is_some_and(|draws| draws.iter().all(|(unit_id, _)| unit_ids.contains(unit_id)));
+}
+
+// The is_valid_attempts() function is used to check whether
+// the provided number of attempts is valid. Only untimed quizzes
+// can be attempted more than once, as text work is submitted once
+// and a timed lesson's timer can't be restarted.
+pub fn is_valid_attempts(work_type: &str, time_limit: i64, max_attempts: i64) -> bool {
+    return max_attempts == 1 || (max_attempts > 1 && quiz::is_quiz(work_type) && time_limit == 0);
+}
+
+// The is_valid_window() function is used to check whether the
+// provided availability window and time limit are valid. A value
+// of 0 means the lesson has no opening time, closing time or time
+// limit, otherwise the lesson must close after it opens.
+pub fn is_valid_window(opens_at: i64, closes_at: i64, time_limit: i64) -> bool {
+    return opens_at >= 0 && time_limit >= 0 && (closes_at == 0 || closes_at > opens_at);
+}
+
+// The prerequisites_met() function is used to get whether the
+// student has met every prerequisite of the provided lesson. A
+// prerequisite with a minimum score needs the student's best mark
+// on the required lesson to reach it, otherwise the required lesson
+// must be completed.
+pub fn prerequisites_met(lesson_id: &str, student: &StudentProgress) -> bool {
+    let prerequisites: &Vec<Prerequisite> = match student.prerequisites.get(lesson_id) {
+        Some(p) => p,
+        None => return true
+    };
+    return prerequisites.iter().all(|p| match p.min_score {
+        Some(min_score) => student.scores.get(&p.required_lesson_id).is_some_and(|s| *s >= min_score),
+        None => student.lessons.get(&p.required_lesson_id).is_some_and(|l| l.completed == 1)
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lesson(solutions_release: &str, solutions_released: i64, due_date: i64) -> Lesson {
+        return Lesson {
+            lesson_id: String::from("lesson"),
+            title: String::from("Lesson"),
+            description: String::new(),
+            video: String::new(),
+            work: String::new(),
+            work_type: String::from("quiz"),
+            work_solutions: String::new(),
+            due_date,
+            solutions_release: solutions_release.to_string(),
+            solutions_released,
+            opens_at: 0,
+            closes_at: 0,
+            time_limit: 0,
+            category_id: String::new(),
+            max_attempts: 2
+        };
+    }
+
+    fn extension(due_date: Option<i64>) -> Extension {
+        return Extension {
+            lesson_id: String::from("lesson"),
+            user_id: String::from("student"),
+            extra_time: 0,
+            due_date,
+            closes_at: None
+        };
+    }
+
+    #[test]
+    fn released_solutions_are_always_shown() {
+        for policy in SOLUTION_RELEASES {
+            assert!(solutions_released(&lesson(policy, 1, 0), None, 0, 0));
+        }
+    }
+
+    #[test]
+    fn due_date_policy() {
+        assert!(!solutions_released(&lesson("due_date", 0, 0), None, 2, 100));
+        assert!(!solutions_released(&lesson("due_date", 0, 200), None, 2, 199));
+        assert!(solutions_released(&lesson("due_date", 0, 200), None, 0, 200));
+    }
+
+    #[test]
+    fn due_date_policy_uses_extension() {
+        let extended: Extension = extension(Some(300));
+        assert!(!solutions_released(&lesson("due_date", 0, 200), Some(&extended), 0, 250));
+        assert!(solutions_released(&lesson("due_date", 0, 200), Some(&extended), 0, 300));
+        assert!(solutions_released(&lesson("due_date", 0, 200), Some(&extension(None)), 0, 250));
+    }
+
+    #[test]
+    fn submitted_policy() {
+        assert!(!solutions_released(&lesson("submitted", 0, 100), None, 0, 200));
+        assert!(!solutions_released(&lesson("submitted", 0, 0), None, 1, 0));
+        assert!(solutions_released(&lesson("submitted", 0, 0), None, 2, 0));
+    }
+
+    #[test]
+    fn quiz_attempts_cant_be_unsubmitted() {
+        let mut text: Lesson = lesson("manual", 0, 0);
+        text.work_type = String::from("text");
+        assert!(can_unsubmit(&text, None, 1, 0, 0));
+        assert!(!can_unsubmit(&text, None, 1, 1, 0));
+
+        // Deleting a marked attempt would give the student it back
+        for work_type in ["quiz", "bank"] {
+            let mut quiz: Lesson = lesson("manual", 0, 0);
+            quiz.work_type = work_type.to_string();
+            assert!(!can_unsubmit(&quiz, None, 1, 1, 0));
+            assert!(!can_unsubmit(&quiz, None, 1, 0, 0));
+        }
+    }
+
+    #[test]
+    fn released_work_cant_be_unsubmitted() {
+        let mut text: Lesson = lesson("submitted", 0, 0);
+        text.work_type = String::from("text");
+        text.max_attempts = 1;
+        assert!(!can_unsubmit(&text, None, 1, 0, 0));
+
+        // A student with a later due date can still unsubmit
+        // until the solutions are released to them
+        text.solutions_release = String::from("due_date");
+        text.due_date = 200;
+        assert!(!can_unsubmit(&text, None, 1, 0, 200));
+        assert!(can_unsubmit(&text, Some(&extension(Some(300))), 1, 0, 200));
+    }
+
+    #[test]
+    fn draws_must_be_from_the_provided_units() {
+        let unit_ids: HashSet<String> = HashSet::from([String::from("a"), String::from("b")]);
+        assert!(is_valid_draws(r#"{"draws": [{"unit_id": "a", "count": 2}, {"unit_id": "b", "count": 1}]}"#, &unit_ids));
+        assert!(!is_valid_draws(r#"{"draws": [{"unit_id": "a", "count": 2}, {"unit_id": "c", "count": 1}]}"#, &unit_ids));
+        assert!(!is_valid_draws(r#"{"draws": []}"#, &unit_ids));
+    }
+
+    #[test]
+    fn only_untimed_quizzes_have_extra_attempts() {
+        assert!(is_valid_attempts("text", 0, 1));
+        assert!(is_valid_attempts("quiz", 600, 1));
+        assert!(is_valid_attempts("bank", 0, 3));
+        assert!(!is_valid_attempts("quiz", 0, 0));
+        assert!(!is_valid_attempts("text", 0, 2));
+        assert!(!is_valid_attempts("quiz", 600, 2));
+    }
+
+    fn prerequisite(lesson_id: &str, required_lesson_id: &str) -> (String, Vec<Prerequisite>) {
+        return (lesson_id.to_string(), vec![Prerequisite {
+            lesson_id: lesson_id.to_string(),
+            required_lesson_id: required_lesson_id.to_string(),
+            min_score: None
+        }]);
+    }
+
+    #[test]
+    fn detects_prerequisite_cycles() {
+        let prerequisites: HashMap<String, Vec<Prerequisite>> = HashMap::from([
+            prerequisite("b", "a"),
+            prerequisite("c", "b")
+        ]);
+        assert!(creates_cycle("a", &["a"], &prerequisites));
+        assert!(creates_cycle("a", &["b"], &prerequisites));
+        assert!(creates_cycle("a", &["d", "c"], &prerequisites));
+        assert!(!creates_cycle("d", &["c"], &prerequisites));
+        assert!(!creates_cycle("c", &["a"], &prerequisites));
+    }
+
+    #[test]
+    fn manual_policy() {
+        assert!(!solutions_released(&lesson("manual", 0, 100), None, 2, 200));
+    }
+}
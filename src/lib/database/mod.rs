@@ -3,4 +3,16 @@ pub mod users;
 pub mod whitelist;
 pub mod submissions;
 pub mod units;
-pub mod announcements;
\ No newline at end of file
+pub mod announcements;
+pub mod grades;
+pub mod lessons;
+pub mod preferences;
+pub mod notifications;
+pub mod comments;
+pub mod progress;
+pub mod questions;
+pub mod extensions;
+pub mod exams;
+pub mod cloning;
+pub mod bundles;
+pub mod quotas;
\ No newline at end of file
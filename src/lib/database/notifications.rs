@@ -0,0 +1,135 @@
+use crate::lib::{self, utils, structs::Notification};
+
+// Database Implementation
+impl lib::handlers::Database {
+    // The insert_user_notification() function is used to add a
+    // new notification to the provided user's inbox. A unique
+    // notification identifier is generated for marking it read.
+    pub async fn insert_user_notification(
+        &self,
+        user_id: &str,
+        class_id: &str,
+        kind: &str,
+        title: &str,
+        description: &str
+    ) -> bool {
+        // Generate a new notification id and get the current date
+        let notification_id: String = utils::generate_new_id(user_id);
+        let date: i64 = utils::get_time().as_secs() as i64;
+
+        // Insert the notification into the database
+        let query = sqlx::query!(
+            "INSERT INTO notifications (notification_id, user_id, class_id, kind, title, description, date, read) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+            notification_id, user_id, class_id, kind, title, description, date, 0
+        ).execute(&self.conn).await;
+
+        // Return query result
+        return match query {
+            Ok(r) => r.rows_affected() > 0,
+            Err(_) => false
+        };
+    }
+
+    // The insert_class_notification() function is used to add
+    // a new notification to the inbox of every student within
+    // the provided class's whitelist.
+    pub async fn insert_class_notification(
+        &self,
+        class_id: &str,
+        kind: &str,
+        title: &str,
+        description: &str
+    ) {
+        for student in self.get_class_students(class_id).await {
+            self.insert_user_notification(&student.user_id, class_id, kind, title, description).await;
+        }
+    }
+
+    // The get_user_notifications() function is used to get
+    // every notification within the bearer's inbox, with
+    // the newest notifications first.
+    pub async fn get_user_notifications(&self, bearer: &str) -> Option<Vec<serde_json::Value>> {
+        // Get the bearer's user id
+        let user_id: String = match self.get_user_id_by_bearer(bearer).await {
+            Some(r) => r,
+            None => return None
+        };
+
+        // Query the database
+        let query = sqlx::query_as!(Notification,
+            "SELECT notification_id, class_id, kind, title, description, date, read FROM notifications WHERE user_id=? ORDER BY date DESC, id DESC",
+            user_id
+        ).fetch_all(&self.conn).await;
+
+        // Return query result
+        return match query {
+            Err(_) => None,
+            Ok(r) => Some(r.iter().map(|n| {
+                serde_json::json!({
+                    "notification_id": n.notification_id,
+                    "class_id": n.class_id,
+                    "kind": n.kind,
+                    "title": n.title,
+                    "description": n.description,
+                    "date": n.date,
+                    "read": n.read == 1
+                })
+            }).collect())
+        };
+    }
+
+    // The get_unread_notification_count() function is used
+    // to get the amount of notifications the provided
+    // user hasn't read yet.
+    pub async fn get_unread_notification_count(&self, user_id: &str) -> i64 {
+        // Query the database
+        let query = sqlx::query!(
+            "SELECT COUNT(*) as count FROM notifications WHERE user_id=? AND read=0",
+            user_id
+        ).fetch_one(&self.conn).await;
+
+        // Return query result
+        return match query {
+            Ok(r) => r.count as i64,
+            Err(_) => 0
+        };
+    }
+
+    // The read_user_notifications() function is used to mark the
+    // provided notifications within the bearer's inbox as read.
+    // If no notification ids are provided, every notification
+    // within the bearer's inbox is marked as read.
+    pub async fn read_user_notifications(&self, bearer: &str, data: &serde_json::Value) -> bool {
+        // Get the bearer's user id
+        let user_id: String = match self.get_user_id_by_bearer(bearer).await {
+            Some(r) => r,
+            None => return false
+        };
+
+        // If no notification ids were provided, mark all of them read
+        let notification_ids: &Vec<serde_json::Value> = match data["notification_ids"].as_array() {
+            Some(ids) => ids,
+            None => {
+                return sqlx::query!(
+                    "UPDATE notifications SET read=1 WHERE user_id=?", user_id
+                ).execute(&self.conn).await.is_ok();
+            }
+        };
+
+        // Mark each of the provided notifications read
+        for notification_id in notification_ids {
+            let notification_id: &str = match notification_id.as_str() {
+                Some(id) => id,
+                None => return false
+            };
+            let query = sqlx::query!(
+                "UPDATE notifications SET read=1 WHERE user_id=? AND notification_id=?",
+                user_id, notification_id
+            ).execute(&self.conn).await;
+            if query.is_err() {
+                return false;
+            }
+        }
+        return true;
+    }
+}
@@ -0,0 +1,63 @@
+use crate::lib::{self, structs::NotificationPreferences};
+
+// Database Implementation
+impl lib::handlers::Database {
+    // The get_notification_preferences() function is used to get
+    // which email notifications the provided user wants to receive.
+    // If the user has never changed their preferences, every
+    // notification is enabled.
+    pub async fn get_notification_preferences(&self, user_id: &str) -> NotificationPreferences {
+        // Query the database
+        let query = sqlx::query_as!(NotificationPreferences,
+            "SELECT email_grades, email_announcements, email_deadlines FROM notification_preferences WHERE user_id=?",
+            user_id
+        ).fetch_one(&self.conn).await;
+
+        // Return query result
+        return match query {
+            Ok(r) => r,
+            Err(_) => NotificationPreferences {
+                email_grades: 1,
+                email_announcements: 1,
+                email_deadlines: 1
+            }
+        };
+    }
+
+    // The update_notification_preferences() function is used to
+    // change which email notifications the bearer's user wants
+    // to receive. Any value not provided is left as is.
+    pub async fn update_notification_preferences(&self, bearer: &str, data: &serde_json::Value) -> bool {
+        // Get the bearer's user id
+        let user_id: String = match self.get_user_id_by_bearer(bearer).await {
+            Some(r) => r,
+            None => return false
+        };
+
+        // Get the current preferences so that any
+        // missing values aren't overwritten
+        let prefs: NotificationPreferences = self.get_notification_preferences(&user_id).await;
+        let email_grades: i64 = data["email_grades"].as_bool().map_or(prefs.email_grades, |v| v as i64);
+        let email_announcements: i64 = data["email_announcements"].as_bool().map_or(prefs.email_announcements, |v| v as i64);
+        let email_deadlines: i64 = data["email_deadlines"].as_bool().map_or(prefs.email_deadlines, |v| v as i64);
+
+        // Replace the users preferences within the database
+        let mut tx = match self.conn.begin().await {
+            Ok(tx) => tx,
+            Err(_) => return false
+        };
+        let _ = sqlx::query!(
+            "DELETE FROM notification_preferences WHERE user_id=?", user_id
+        ).execute(&mut tx).await;
+        let query = sqlx::query!(
+            "INSERT INTO notification_preferences (user_id, email_grades, email_announcements, email_deadlines) VALUES (?, ?, ?, ?)",
+            user_id, email_grades, email_announcements, email_deadlines
+        ).execute(&mut tx).await;
+
+        // Return query result
+        return match query {
+            Ok(r) => r.rows_affected() > 0 && tx.commit().await.is_ok(),
+            Err(_) => false
+        };
+    }
+}
@@ -0,0 +1,183 @@
+use crate::lib::{
+    self, utils, database::{units, lessons}, structs::{LessonProgress, Lesson, StudentProgress}
+};
+use std::collections::HashMap;
+
+// Database Implementation
+impl lib::handlers::Database {
+    // The update_lesson_progress() function is used to record how far
+    // the bearer has gotten through the provided lesson. Students can
+    // mark the lesson complete and record how much of the video they've
+    // watched. The video progress only ever increases, so rewatching
+    // the start of a video doesn't undo the student's progress.
+    pub async fn update_lesson_progress(
+        &self,
+        bearer: &str,
+        class_id: &str,
+        unit_id: &str,
+        lesson_id: &str,
+        staff: bool,
+        data: &serde_json::Value
+    ) -> bool {
+        // Get the student's user id
+        let user_id: String = match self.get_user_id_by_bearer(bearer).await {
+            Some(r) => r,
+            None => return false
+        };
+
+        // Students can't make progress on the lessons of a locked unit
+        let now: i64 = utils::get_time().as_secs() as i64;
+        match self.get_class_unit(class_id, unit_id).await {
+            Some(unit) if staff || !units::is_unit_locked(&unit, now) => (),
+            _ => return false
+        };
+
+        // Make sure the lesson is within the unit
+        if self.get_lesson(unit_id, lesson_id).await.is_none() {
+            return false;
+        }
+
+        // Students can't make progress on a lesson until
+        // they've met all of it's prerequisites
+        if !staff && !lessons::prerequisites_met(lesson_id, &self.get_student_progress(bearer, class_id).await) {
+            return false;
+        }
+
+        // Get the request body variables. A value of -1
+        // means the value isn't changed.
+        let completed: i64 = data["completed"].as_bool().map_or(-1, |c| c as i64);
+        let video_progress: f64 = data["video_progress"].as_f64().map_or(-1.0, |p| p.clamp(0.0, 100.0));
+        if completed < 0 && video_progress < 0.0 {
+            return false;
+        }
+
+        // Insert the lesson progress into the database, updating
+        // the student's existing progress if there is any
+        let query = sqlx::query!(
+            "INSERT INTO lesson_progress (class_id, lesson_id, user_id, completed, video_progress, updated_at) VALUES (?, ?, ?, MAX(?, 0), MAX(?, 0), ?)
+            ON CONFLICT (user_id, lesson_id) DO UPDATE SET
+                completed=CASE WHEN ? < 0 THEN completed ELSE ? END,
+                video_progress=MAX(video_progress, excluded.video_progress),
+                updated_at=excluded.updated_at",
+            class_id, lesson_id, user_id, completed, video_progress, now, completed, completed
+        ).execute(&self.conn).await;
+
+        // Return query result
+        return match query {
+            Ok(r) => r.rows_affected() > 0,
+            Err(_) => false
+        };
+    }
+
+    // The get_user_progress() function is used to get how far the
+    // bearer has gotten through each lesson within the provided
+    // class. The progress is mapped by the lesson's id.
+    pub async fn get_user_progress(&self, bearer: &str, class_id: &str) -> HashMap<String, LessonProgress> {
+        // Get the user's id
+        let user_id: String = match self.get_user_id_by_bearer(bearer).await {
+            Some(r) => r,
+            None => return HashMap::new()
+        };
+
+        // Query the database
+        let query = sqlx::query_as!(LessonProgress,
+            "SELECT lesson_id, user_id, completed, video_progress FROM lesson_progress WHERE class_id=? AND user_id=?",
+            class_id, user_id
+        ).fetch_all(&self.conn).await;
+
+        // Return query result
+        return match query {
+            Ok(r) => r.into_iter().map(|p| (p.lesson_id.clone(), p)).collect(),
+            Err(_) => HashMap::new()
+        };
+    }
+
+    // The get_student_progress() function is used to get everything
+    // about the bearer's progress through the provided class. This
+    // is used to decide which lessons and solutions they can see.
+    pub async fn get_student_progress(&self, bearer: &str, class_id: &str) -> StudentProgress {
+        return StudentProgress {
+            submitted: self.get_submitted_lessons(bearer, class_id).await,
+            lessons: self.get_user_progress(bearer, class_id).await,
+            scores: self.get_lesson_scores(bearer, class_id).await,
+            prerequisites: self.get_class_prerequisites(class_id).await,
+            attempts: self.get_exam_attempts(bearer, class_id).await,
+            extensions: self.get_user_extensions(bearer, class_id).await
+        };
+    }
+
+    // The get_class_progress() function is used to get the progress
+    // of every student through every lesson within the provided class.
+    // The students are the class's whitelisted users along with
+    // anyone who has made progress in the class. Only the class
+    // owner can see the class's progress.
+    pub async fn get_class_progress(&self, bearer: &str, class_id: &str) -> Option<serde_json::Value> {
+        // Only the class owner can see everyone's progress
+        if !self.is_class_owner(bearer, class_id).await {
+            return None;
+        }
+
+        // Get every lesson within the class, in their set order
+        let query = sqlx::query!(
+            "SELECT unit_id FROM units WHERE class_id=? ORDER BY position, id", class_id
+        ).fetch_all(&self.conn).await;
+        let mut lessons: Vec<(String, Lesson)> = Vec::new();
+        for unit in match query { Ok(r) => r, Err(_) => return None } {
+            for lesson in self.get_unit_lessons(&unit.unit_id).await {
+                lessons.push((unit.unit_id.clone(), lesson));
+            }
+        }
+
+        // Get the class's students
+        let students = sqlx::query!(
+            "SELECT user_id, user_name FROM users WHERE user_id IN (
+                SELECT whitelisted_user_id FROM whitelists WHERE class_id=?
+                UNION SELECT user_id FROM lesson_progress WHERE class_id=?
+            ) ORDER BY user_name",
+            class_id, class_id
+        ).fetch_all(&self.conn).await;
+        let students = match students {
+            Ok(r) => r,
+            Err(_) => return None
+        };
+
+        // Get every student's progress, mapped by the
+        // student's id and then the lesson's id
+        let query = sqlx::query_as!(LessonProgress,
+            "SELECT lesson_id, user_id, completed, video_progress FROM lesson_progress WHERE class_id=?",
+            class_id
+        ).fetch_all(&self.conn).await;
+        let mut progress: HashMap<String, HashMap<String, LessonProgress>> = HashMap::new();
+        for p in match query { Ok(r) => r, Err(_) => return None } {
+            progress.entry(p.user_id.clone()).or_default().insert(p.lesson_id.clone(), p);
+        }
+
+        // Build the progress matrix
+        let empty: HashMap<String, LessonProgress> = HashMap::new();
+        return Some(serde_json::json!({
+            "lessons": lessons.iter().map(|(unit_id, l)| {
+                serde_json::json!({
+                    "unit_id": unit_id,
+                    "lesson_id": l.lesson_id,
+                    "title": l.title
+                })
+            }).collect::<Vec<serde_json::Value>>(),
+            "students": students.iter().map(|s| {
+                let student: &HashMap<String, LessonProgress> = progress.get(&s.user_id).unwrap_or(&empty);
+                let completed: usize = student.values().filter(|p| p.completed == 1).count();
+                serde_json::json!({
+                    "user_id": s.user_id,
+                    "user_name": s.user_name,
+                    "completed": completed,
+                    "progress": lessons.iter().map(|(_, l)| {
+                        let p: Option<&LessonProgress> = student.get(&l.lesson_id);
+                        serde_json::json!({
+                            "completed": p.is_some_and(|p| p.completed == 1),
+                            "video_progress": p.map_or(0.0, |p| p.video_progress)
+                        })
+                    }).collect::<Vec<serde_json::Value>>()
+                })
+            }).collect::<Vec<serde_json::Value>>()
+        }));
+    }
+}
@@ -0,0 +1,261 @@
+use crate::lib::{
+    self, utils, quiz, database::{units, lessons, exams}, structs::{BankQuestion, QuizDraw, Lesson, StudentProgress}
+};
+
+// Database Implementation
+impl lib::handlers::Database {
+    // The get_question_bank() function is used to get every
+    // question within the provided class's question bank.
+    pub async fn get_question_bank(&self, class_id: &str) -> Vec<BankQuestion> {
+        // Query the database
+        let query = sqlx::query_as!(BankQuestion,
+            "SELECT question_id, unit_id, question, answer FROM bank_questions WHERE class_id=? ORDER BY id",
+            class_id
+        ).fetch_all(&self.conn).await;
+
+        // Return query result
+        return match query {
+            Ok(r) => r,
+            Err(_) => Vec::new()
+        };
+    }
+
+    // The get_class_questions() function is used by the class
+    // owner to get every question within the class's question
+    // bank, along with each question's answer.
+    pub async fn get_class_questions(&self, bearer: &str, class_id: &str) -> Option<Vec<serde_json::Value>> {
+        // Only the class owner can see the question bank
+        if !self.is_class_owner(bearer, class_id).await {
+            return None;
+        }
+
+        // Convert the questions into json
+        return Some(self.get_question_bank(class_id).await.iter().map(|q| serde_json::json!({
+            "question_id": q.question_id,
+            "unit_id": q.unit_id,
+            "question": serde_json::from_str::<serde_json::Value>(&q.question).unwrap_or_default(),
+            "answer": serde_json::from_str::<serde_json::Value>(&q.answer).unwrap_or_default()
+        })).collect());
+    }
+
+    // The get_question_body() function is used to get the question
+    // and answer within the request body. The question must be tagged
+    // with a unit within the class, and must be able to be graded
+    // using the provided answer.
+    async fn get_question_body(
+        &self,
+        class_id: &str,
+        question_id: &str,
+        data: &serde_json::Value
+    ) -> Option<(String, String, String)> {
+        // Get the request body variables
+        let unit_id: &str = data["unit_id"].as_str()?;
+        let question: &serde_json::Value = &data["question"];
+        let answer: &serde_json::Value = &data["answer"];
+        if !question.is_object() || !is_valid_question(question_id, question, answer) {
+            return None;
+        }
+
+        // Make sure the unit is within the class
+        self.get_class_unit(class_id, unit_id).await?;
+        return Some((unit_id.to_string(), question.to_string(), answer.to_string()));
+    }
+
+    // The insert_class_question() function is used to add a
+    // new question to the provided class's question bank.
+    pub async fn insert_class_question(
+        &self,
+        bearer: &str,
+        class_id: &str,
+        question_id: &str,
+        data: &serde_json::Value
+    ) -> bool {
+        // Only the class owner can add questions
+        if !self.is_class_owner(bearer, class_id).await {
+            return false;
+        }
+
+        // Get the question from the request body
+        let (unit_id, question, answer): (String, String, String) = match self.get_question_body(class_id, question_id, data).await {
+            Some(r) => r,
+            None => return false
+        };
+
+        // Insert the question into the database
+        let query = sqlx::query!(
+            "INSERT INTO bank_questions (class_id, unit_id, question_id, question, answer) VALUES (?, ?, ?, ?, ?)",
+            class_id, unit_id, question_id, question, answer
+        ).execute(&self.conn).await;
+
+        // Return query result
+        return match query {
+            Ok(r) => r.rows_affected() > 0,
+            Err(_) => false
+        };
+    }
+
+    // The update_class_question() function is used to replace a
+    // question within the provided class's question bank. Quizzes
+    // that have already been drawn keep the original question.
+    pub async fn update_class_question(
+        &self,
+        bearer: &str,
+        class_id: &str,
+        question_id: &str,
+        data: &serde_json::Value
+    ) -> bool {
+        // Only the class owner can update questions
+        if !self.is_class_owner(bearer, class_id).await {
+            return false;
+        }
+
+        // Get the question from the request body
+        let (unit_id, question, answer): (String, String, String) = match self.get_question_body(class_id, question_id, data).await {
+            Some(r) => r,
+            None => return false
+        };
+
+        // Update the question in the database
+        let query = sqlx::query!(
+            "UPDATE bank_questions SET unit_id=?, question=?, answer=? WHERE question_id=? AND class_id=?",
+            unit_id, question, answer, question_id, class_id
+        ).execute(&self.conn).await;
+
+        // Return query result
+        return match query {
+            Ok(r) => r.rows_affected() > 0,
+            Err(_) => false
+        };
+    }
+
+    // The delete_class_question() function is used to delete a
+    // question from the provided class's question bank.
+    pub async fn delete_class_question(&self, bearer: &str, class_id: &str, question_id: &str) -> bool {
+        // Only the class owner can delete questions
+        if !self.is_class_owner(bearer, class_id).await {
+            return false;
+        }
+
+        // Query the database
+        let query = sqlx::query!(
+            "DELETE FROM bank_questions WHERE question_id=? AND class_id=?",
+            question_id, class_id
+        ).execute(&self.conn).await;
+
+        // Return query result
+        return match query {
+            Ok(r) => r.rows_affected() > 0,
+            Err(_) => false
+        };
+    }
+
+    // The get_stored_draw() function is used to get the questions
+    // that were drawn for the provided user on the provided lesson.
+    async fn get_stored_draw(&self, lesson_id: &str, user_id: &str) -> Option<QuizDraw> {
+        // Query the database
+        let query = sqlx::query_as!(QuizDraw,
+            "SELECT questions, answers FROM quiz_draws WHERE lesson_id=? AND user_id=?",
+            lesson_id, user_id
+        ).fetch_one(&self.conn).await;
+
+        // Return query result
+        return match query {
+            Ok(r) => Some(r),
+            Err(_) => None
+        };
+    }
+
+    // The get_quiz_draw() function is used to get the questions the
+    // bearer was given for the provided bank quiz. The first time the
+    // quiz is opened, the questions are drawn from the question bank
+    // and stored so that the bearer always sees the same questions,
+    // and so they can be graded and reviewed later. Students can't
+    // open quizzes within a locked unit, before meeting the lesson's
+    // prerequisites, or before starting a timed quiz.
+    pub async fn get_quiz_draw(
+        &self,
+        bearer: &str,
+        class_id: &str,
+        unit_id: &str,
+        lesson_id: &str,
+        staff: bool
+    ) -> Option<QuizDraw> {
+        // Get the user's id
+        let user_id: String = self.get_user_id_by_bearer(bearer).await?;
+
+        // Make sure the student can see the lesson
+        let now: i64 = utils::get_time().as_secs() as i64;
+        match self.get_class_unit(class_id, unit_id).await {
+            Some(unit) if staff || !units::is_unit_locked(&unit, now) => (),
+            _ => return None
+        };
+
+        // Get the bank quiz
+        let lesson: Lesson = self.get_lesson(unit_id, lesson_id).await?;
+        if lesson.work_type != "bank" {
+            return None;
+        }
+
+        // Students can't see the questions before meeting the lesson's
+        // prerequisites, or before starting a timed quiz
+        if !staff {
+            let student: StudentProgress = self.get_student_progress(bearer, class_id).await;
+            if !lessons::prerequisites_met(lesson_id, &student) || !exams::work_visible(&lesson, &student, now) {
+                return None;
+            }
+        }
+
+        // If the questions have already been drawn, return them
+        if let Some(draw) = self.get_stored_draw(lesson_id, &user_id).await {
+            return Some(draw);
+        }
+
+        // Draw the questions from the question bank
+        let draws: Vec<(String, usize)> = quiz::get_draws(&lesson.work)?;
+        let seed: String = format!("{}:{}", lesson_id, user_id);
+        let (questions, key) = quiz::draw(&self.get_question_bank(class_id).await, &draws, &seed)?;
+        let draw: QuizDraw = QuizDraw {
+            questions: serde_json::Value::from(questions).to_string(),
+            answers: key.to_string()
+        };
+
+        // Store the drawn questions. If the quiz was opened twice at
+        // the same time, the draw that was stored first is used.
+        let query = sqlx::query!(
+            "INSERT INTO quiz_draws (class_id, lesson_id, user_id, questions, answers, created_at) VALUES (?, ?, ?, ?, ?, ?) ON CONFLICT (lesson_id, user_id) DO NOTHING",
+            class_id, lesson_id, user_id, draw.questions, draw.answers, now
+        ).execute(&self.conn).await;
+
+        // Return the stored draw
+        return match query {
+            Ok(_) => self.get_stored_draw(lesson_id, &user_id).await,
+            Err(_) => None
+        };
+    }
+
+    // The get_student_quiz_draw() function is used by the class
+    // owner to review the questions and answer key that were
+    // drawn for a student on the provided bank quiz.
+    pub async fn get_student_quiz_draw(
+        &self,
+        bearer: &str,
+        class_id: &str,
+        lesson_id: &str,
+        user_id: &str
+    ) -> Option<QuizDraw> {
+        // Only the class owner can review a student's draw
+        if !self.is_class_owner(bearer, class_id).await || !self.is_class_lesson(class_id, lesson_id).await {
+            return None;
+        }
+        return self.get_stored_draw(lesson_id, user_id).await;
+    }
+}
+
+// The is_valid_question() function is used to check whether
+// the provided bank question can be graded using the
+// provided answer.
+pub fn is_valid_question(question_id: &str, question: &serde_json::Value, answer: &serde_json::Value) -> bool {
+    let mut question: serde_json::Value = question.clone();
+    question["question_id"] = serde_json::Value::from(question_id);
+    return quiz::is_valid(&[question], &serde_json::json!({ question_id: answer }));
+}
@@ -0,0 +1,135 @@
+use crate::lib;
+
+// Database Implementation
+impl lib::handlers::Database {
+    // The count_user_classes() function is used to get the number
+    // of classes owned by the provided bearer's user. Archived
+    // classes still count towards the user's class quota.
+    pub async fn count_user_classes(&self, bearer: &str) -> i64 {
+        // Get the bearer owner id
+        let owner_id: String = match self.get_user_id_by_bearer(bearer).await {
+            Some(r) => r,
+            None => return 0
+        };
+
+        // Query the database
+        let query = sqlx::query!(
+            "SELECT COUNT(*) as count FROM classes WHERE owner_id=?",
+            owner_id
+        ).fetch_one(&self.conn).await;
+
+        // Return query result
+        return match query {
+            Ok(r) => r.count as i64,
+            Err(_) => 0
+        };
+    }
+
+    // The count_class_units() function is used to get
+    // the number of units within the provided class.
+    pub async fn count_class_units(&self, class_id: &str) -> i64 {
+        // Query the database
+        let query = sqlx::query!(
+            "SELECT COUNT(*) as count FROM units WHERE class_id=?",
+            class_id
+        ).fetch_one(&self.conn).await;
+
+        // Return query result
+        return match query {
+            Ok(r) => r.count as i64,
+            Err(_) => 0
+        };
+    }
+
+    // The count_unit_lessons() function is used to get
+    // the number of lessons within the provided unit.
+    pub async fn count_unit_lessons(&self, unit_id: &str) -> i64 {
+        // Query the database
+        let query = sqlx::query!(
+            "SELECT COUNT(*) as count FROM lessons WHERE unit_id=?",
+            unit_id
+        ).fetch_one(&self.conn).await;
+
+        // Return query result
+        return match query {
+            Ok(r) => r.count as i64,
+            Err(_) => 0
+        };
+    }
+
+    // The count_class_students() function is used to get the
+    // number of students within the provided class's whitelist.
+    pub async fn count_class_students(&self, class_id: &str) -> i64 {
+        // Query the database
+        let query = sqlx::query!(
+            "SELECT COUNT(*) as count FROM whitelists WHERE class_id=?",
+            class_id
+        ).fetch_one(&self.conn).await;
+
+        // Return query result
+        return match query {
+            Ok(r) => r.count as i64,
+            Err(_) => 0
+        };
+    }
+
+    // The get_class_storage() function is used to get the number
+    // of bytes the provided class's content takes up. This is the
+    // text of it's lessons, question bank, announcements (including
+    // their edit history) and submissions. Comments have their own
+    // per-user quota.
+    pub async fn get_class_storage(&self, class_id: &str) -> i64 {
+        // Query the size of each kind of content
+        let lessons = sqlx::query!(
+            "SELECT SUM(LENGTH(CAST(lessons.title || lessons.description || lessons.video || lessons.work || lessons.work_solutions AS BLOB))) as \"size: i64\"
+            FROM lessons JOIN units ON units.unit_id=lessons.unit_id WHERE units.class_id=?",
+            class_id
+        ).fetch_one(&self.conn).await.map(|r| r.size);
+        let questions = sqlx::query!(
+            "SELECT SUM(LENGTH(CAST(question || answer AS BLOB))) as \"size: i64\" FROM bank_questions WHERE class_id=?",
+            class_id
+        ).fetch_one(&self.conn).await.map(|r| r.size);
+        let announcements = sqlx::query!(
+            "SELECT SUM(LENGTH(CAST(title || description || attachment AS BLOB))) as \"size: i64\" FROM announcements WHERE class_id=?",
+            class_id
+        ).fetch_one(&self.conn).await.map(|r| r.size);
+        let history = sqlx::query!(
+            "SELECT SUM(LENGTH(CAST(announcement_history.title || announcement_history.description || announcement_history.attachment AS BLOB))) as \"size: i64\"
+            FROM announcement_history JOIN announcements ON announcements.announcement_id=announcement_history.announcement_id WHERE announcements.class_id=?",
+            class_id
+        ).fetch_one(&self.conn).await.map(|r| r.size);
+        let submissions = sqlx::query!(
+            "SELECT SUM(LENGTH(CAST(data AS BLOB))) as \"size: i64\" FROM submissions WHERE class_id=?",
+            class_id
+        ).fetch_one(&self.conn).await.map(|r| r.size);
+
+        // Return the total size
+        return [lessons, questions, announcements, history, submissions]
+            .into_iter()
+            .map(|size| size.ok().flatten().unwrap_or(0))
+            .sum();
+    }
+
+    // The get_user_comment_storage() function is used to get the
+    // number of bytes the provided bearer's comments take up
+    // within the provided class.
+    pub async fn get_user_comment_storage(&self, bearer: &str, class_id: &str) -> i64 {
+        // Get the bearer user id
+        let user_id: String = match self.get_user_id_by_bearer(bearer).await {
+            Some(r) => r,
+            None => return 0
+        };
+
+        // Query the database
+        let query = sqlx::query!(
+            "SELECT SUM(LENGTH(CAST(content AS BLOB))) as \"size: i64\" FROM announcement_comments WHERE class_id=? AND user_id=?",
+            class_id, user_id
+        ).fetch_one(&self.conn).await;
+
+        // Return query result
+        return match query {
+            Ok(r) => r.size.unwrap_or(0),
+            Err(_) => 0
+        };
+    }
+}
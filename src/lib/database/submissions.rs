@@ -1,4 +1,7 @@
-use crate::lib::{self, utils, structs::Submission};
+use crate::lib::{
+    self, utils, quiz, database::{units, lessons}, structs::{Submission, Recipient, Lesson, QuizDraw, StudentProgress, Extension}
+};
+use std::collections::HashMap;
 
 // Database Implementation
 impl lib::handlers::Database {
@@ -13,19 +16,25 @@ impl lib::handlers::Database {
         ).fetch_one(&self.conn).await;
 
         // Return whether valid query data has been obtained
-        return !query.is_err();
+        return query.is_ok();
     }
 
     // The insert_class_submission() function is used to
     // insert a new work submission into the database
     // using the provided class hash. The function generates
     // a unique submission hash before inserting the data, which
-    // is used within the delete_class_submission() function
+    // is used within the delete_class_submission() function.
+    // If a lesson id is provided, it must be a lesson within the class
+    // that's still accepting submissions, within an unlocked unit, and
+    // whose prerequisites the submitter has met. Quizzes can only be
+    // submitted through the submit_lesson_quiz() function so that
+    // they're marked.
     pub async fn insert_class_submission(
         &self, 
         class_id: &str,
         submission_id: &str,
         submitter_bearer: &str,
+        lesson_id: &str,
         data: &str
     ) -> bool {
         // If the submission already exists, return
@@ -36,33 +45,172 @@ impl lib::handlers::Database {
         // Get the current date to put into the database
         let date: i64 = utils::get_time().as_secs() as i64;
 
+        // Make sure the lesson belongs to the class, that it isn't
+        // a quiz, and that the submitter can see it and can still
+        // submit work for it
+        if !lesson_id.is_empty() {
+            match self.get_lesson_unit(class_id, lesson_id).await {
+                Some(unit) if !units::is_unit_locked(&unit, date) => (),
+                _ => return false
+            };
+            if !lessons::prerequisites_met(lesson_id, &self.get_student_progress(submitter_bearer, class_id).await) {
+                return false;
+            }
+            match self.get_class_lesson(class_id, lesson_id).await {
+                Some(lesson) if !quiz::is_quiz(&lesson.work_type) && self.can_submit_lesson(submitter_bearer, &lesson).await => (),
+                _ => return false
+            };
+        }
+
         // Insert the data into the database
         let query = sqlx::query!(
-            "INSERT INTO submissions (class_id, submission_id, submitter_bearer, submission_date, data) VALUES (?, ?, ?, ?, ?)", 
-            class_id, submission_id, submitter_bearer, date, data
+            "INSERT INTO submissions (class_id, submission_id, submitter_bearer, lesson_id, submission_date, data) VALUES (?, ?, ?, ?, ?, ?)", 
+            class_id, submission_id, submitter_bearer, lesson_id, date, data
         ).execute(&self.conn).await;
 
-        // Return query result
+        // Close the submitter's attempt if the lesson is timed
         return match query {
-            Ok(r) => r.rows_affected() > 0,
-            Err(_) => false
+            Ok(r) if r.rows_affected() > 0 => {
+                self.close_exam_attempt(submitter_bearer, lesson_id).await;
+                true
+            },
+            _ => false
         };
     }
 
+    // The submit_lesson_quiz() function is used to submit the
+    // bearer's answers to the provided quiz lesson. The answers are
+    // marked against the lesson's answer key right away and stored
+    // as a graded submission within the lesson's grade category. Students can't submit quizzes within
+    // a locked unit, before meeting the lesson's prerequisites, once
+    // the quiz has closed or their time is up, once they've used all
+    // of their attempts, or once they've been shown the quiz's solutions.
+    // Which answers were correct is only returned once the solutions
+    // have been released to the student.
+    pub async fn submit_lesson_quiz(
+        &self,
+        submission: quiz::QuizSubmission<'_>,
+        data: &serde_json::Value
+    ) -> Option<quiz::QuizResult> {
+        let quiz::QuizSubmission { bearer, class_id, unit_id, lesson_id, submission_id, staff } = submission;
+
+        // If the submission already exists, return
+        if self.class_submission_exists(submission_id).await {
+            return None;
+        }
+
+        // Make sure the student can see the lesson
+        let now: i64 = utils::get_time().as_secs() as i64;
+        match self.get_class_unit(class_id, unit_id).await {
+            Some(unit) if staff || !units::is_unit_locked(&unit, now) => (),
+            _ => return None
+        };
+        let student: StudentProgress = self.get_student_progress(bearer, class_id).await;
+        if !staff && !lessons::prerequisites_met(lesson_id, &student) {
+            return None;
+        }
+
+        // Get the quiz, and make sure the bearer has attempts
+        // left and hasn't been shown it's solutions
+        let lesson: Lesson = self.get_lesson(unit_id, lesson_id).await?;
+        let extension: Option<&Extension> = student.extensions.get(lesson_id);
+        let attempts: i64 = student.submitted.get(lesson_id).copied().unwrap_or(0);
+        if !staff && (attempts >= lesson.max_attempts || lessons::solutions_released(&lesson, extension, attempts, now)) {
+            return None;
+        }
+
+        // Get the quiz's answer key. Bank quizzes are marked
+        // against the questions the bearer was given.
+        let (questions, key): (Vec<serde_json::Value>, serde_json::Value) = match lesson.work_type.as_str() {
+            "quiz" => (
+                quiz::get_questions(&lesson.work)?,
+                serde_json::from_str(&lesson.work_solutions).ok()?
+            ),
+            "bank" => {
+                let draw: QuizDraw = self.get_quiz_draw(bearer, class_id, unit_id, lesson_id, staff).await?;
+                (serde_json::from_str(&draw.questions).ok()?, serde_json::from_str(&draw.answers).ok()?)
+            },
+            _ => return None
+        };
+
+        // Make sure the lesson is open and the bearer still has time
+        if !staff && !self.can_submit_lesson(bearer, &lesson).await {
+            return None;
+        }
+
+        // Mark the student's answers
+        let answers: &serde_json::Value = &data["answers"];
+        if !answers.is_object() {
+            return None;
+        }
+        let mut result: quiz::QuizResult = quiz::grade(&questions, &key, answers);
+
+        // Insert the marked submission into the database. The bearer's
+        // attempts are counted within the insert so that submitting the
+        // quiz more than once at a time can't go past the attempt limit.
+        let answers: String = answers.to_string();
+        let query = sqlx::query!(
+            "INSERT INTO submissions (class_id, submission_id, submitter_bearer, lesson_id, submission_date, data, category_id, graded, mark, out_of)
+            SELECT ?, ?, ?, ?, ?, ?, ?, ?, ?, ?
+            WHERE ? OR (SELECT COUNT(*) FROM submissions WHERE submitter_bearer=? AND lesson_id=? AND graded=1) < ?",
+            class_id, submission_id, bearer, lesson_id, now, answers, lesson.category_id, 1, result.mark, result.out_of,
+            staff, bearer, lesson_id, lesson.max_attempts
+        ).execute(&self.conn).await;
+        if !matches!(query, Ok(r) if r.rows_affected() > 0) {
+            return None;
+        }
+
+        // Close the bearer's attempt, and hide which answers were
+        // correct until the solutions have been released
+        self.close_exam_attempt(bearer, lesson_id).await;
+        if !staff && !lessons::solutions_released(&lesson, extension, attempts + 1, now) {
+            result.results.clear();
+        }
+        return Some(result);
+    }
+
     // The delete_class_submission() function is used to
     // delete a submission from the database. This function
     // is called when a student wants to unsubmit a portion
-    // of their work.
+    // of their work. Marked work, quiz attempts and work whose
+    // solutions the student has seen can't be unsubmitted.
     pub async fn delete_class_submission(
         &self, 
         submitter_bearer: &str, 
         class_id: &str, 
         submission_id: &str
     ) -> bool {
+        // Get the submission
+        let submission = match sqlx::query!(
+            "SELECT lesson_id, graded FROM submissions WHERE submission_id=? AND submitter_bearer=? AND class_id=?",
+            submission_id, submitter_bearer, class_id
+        ).fetch_one(&self.conn).await {
+            Ok(s) => s,
+            Err(_) => return false
+        };
+
+        // Make sure the submission can be unsubmitted. Once the
+        // lesson's solutions have been shown to the submitter, their
+        // submission can't be deleted to hide them again.
+        let can_unsubmit: bool = match self.get_class_lesson(class_id, &submission.lesson_id).await {
+            Some(lesson) => {
+                let now: i64 = utils::get_time().as_secs() as i64;
+                let extensions: HashMap<String, Extension> = self.get_user_extensions(submitter_bearer, class_id).await;
+                let submitted: i64 = self.get_submitted_lessons(submitter_bearer, class_id).await
+                    .get(&lesson.lesson_id).copied().unwrap_or(0);
+                lessons::can_unsubmit(&lesson, extensions.get(&lesson.lesson_id), submitted, submission.graded, now)
+            },
+            None => submission.graded == 0
+        };
+        if !can_unsubmit {
+            return false;
+        }
+
         // Query the database, deleting all data revolving around
-        // the provided submission hash
+        // the provided submission hash. The submission is only
+        // deleted if it hasn't been marked in the meantime.
         let query = sqlx::query!(
-            "DELETE FROM submissions WHERE submission_id=? AND submitter_bearer=? AND class_id=?",
+            "DELETE FROM submissions WHERE submission_id=? AND submitter_bearer=? AND class_id=? AND graded=0",
             submission_id, submitter_bearer, class_id
         ).execute(&self.conn).await;
 
@@ -76,7 +224,86 @@ impl lib::handlers::Database {
         };
     }
 
-    
+    // The grade_class_submission() function is used by the
+    // class owner to mark a student's submission. The mark is
+    // stored along with the grade category it counts towards so
+    // that the student's running average can be computed. If the
+    // class uses grade categories, the mark must count towards one.
+    pub async fn grade_class_submission(
+        &self,
+        bearer: &str,
+        class_id: &str,
+        submission_id: &str,
+        data: &serde_json::Value
+    ) -> bool {
+        // Get the request body variables
+        let mark: f64 = match data["mark"].as_f64() {
+            Some(mark) => mark,
+            None => return false
+        };
+        let out_of: f64 = match data["out_of"].as_f64() {
+            Some(out_of) => out_of,
+            None => return false
+        };
+
+        // A submission can't be marked out of nothing
+        if mark < 0.0 || out_of <= 0.0 {
+            return false;
+        }
+
+        // Only the class owner is allowed to mark submissions
+        if !self.is_class_owner(bearer, class_id).await {
+            return false;
+        }
+
+        // Get the submission's grade category. If one isn't provided,
+        // the submission keeps the category it already counts towards.
+        let current = match sqlx::query!(
+            "SELECT category_id FROM submissions WHERE submission_id=? AND class_id=?", submission_id, class_id
+        ).fetch_one(&self.conn).await {
+            Ok(r) => r,
+            Err(_) => return false
+        };
+        let category_id: &str = data["category_id"].as_str().unwrap_or(&current.category_id);
+
+        // Make sure the category is within the class
+        if !self.is_valid_category(class_id, category_id, true).await {
+            return false;
+        }
+
+        // Query the database, updating the submission's mark
+        let query = sqlx::query!(
+            "UPDATE submissions SET category_id=?, graded=1, mark=?, out_of=? WHERE submission_id=? AND class_id=?",
+            category_id, mark, out_of, submission_id, class_id
+        ).execute(&self.conn).await;
+
+        // Return query result
+        return match query {
+            Ok(r) => r.rows_affected() > 0,
+            Err(_) => false
+        };
+    }
+
+    // The get_submission_recipient() function is used to get
+    // the name and email of the user who made the provided
+    // submission. This is used to email the student once
+    // their work has been marked.
+    pub async fn get_submission_recipient(&self, class_id: &str, submission_id: &str) -> Option<Recipient> {
+        // Query the database
+        let query = sqlx::query_as!(Recipient,
+            "SELECT users.user_id as \"user_id!\", users.user_name as \"user_name!\", users.email as \"email!\" FROM submissions
+            JOIN users ON users.bearer=submissions.submitter_bearer
+            WHERE submissions.class_id=? AND submissions.submission_id=?",
+            class_id, submission_id
+        ).fetch_one(&self.conn).await;
+
+        // Return query result
+        return match query {
+            Ok(r) => Some(r),
+            Err(_) => None
+        };
+    }
+
     // The get_submission_json() function is used to return
     // a string json map with all the submission data
     // that was retrieved from the database.
@@ -85,8 +312,14 @@ impl lib::handlers::Database {
             serde_json::json!({
                 "submitter_bearer": s.submitter_bearer,
                 "submission_id": s.submission_id,
+                "lesson_id": s.lesson_id,
                 "submission_date": s.submission_date,
-                "data": s.data
+                "data": s.data,
+                "category_id": s.category_id,
+                "graded": s.graded == 1,
+                "mark": s.mark,
+                "out_of": s.out_of,
+                "late": s.late == 1
             })
         }).collect();
     }
@@ -95,11 +328,21 @@ impl lib::handlers::Database {
     // return all the submissions for the provided class.
     // This function is used in the dashboard of the website
     // where the teachers can mark the students submitted work.
+    // Work submitted after the student's due date, including
+    // any extension they were given, is marked as late.
     pub async fn get_class_submissions(&self, class_id: &str) -> Option<Vec<serde_json::Value>> {
         // Query the database, selecting the submitter_bearer, submission_date
         // and the submission data from the submissions column
         let query = sqlx::query_as!(Submission, 
-            "SELECT submitter_bearer, submission_id, submission_date, data FROM submissions WHERE class_id=?", 
+            "SELECT submissions.submitter_bearer as \"submitter_bearer!\", submissions.submission_id as \"submission_id!\", submissions.lesson_id as \"lesson_id!\",
+            submissions.submission_date as \"submission_date!\", submissions.data as \"data!\", submissions.category_id as \"category_id!\",
+            submissions.graded as \"graded!\", submissions.mark as \"mark!\", submissions.out_of as \"out_of!\",
+            (COALESCE(lesson_extensions.due_date, lessons.due_date, 0) > 0 AND submissions.submission_date > COALESCE(lesson_extensions.due_date, lessons.due_date, 0)) as \"late!: i64\"
+            FROM submissions
+            LEFT JOIN lessons ON lessons.lesson_id=submissions.lesson_id
+            LEFT JOIN users ON users.bearer=submissions.submitter_bearer
+            LEFT JOIN lesson_extensions ON lesson_extensions.lesson_id=submissions.lesson_id AND lesson_extensions.user_id=users.user_id
+            WHERE submissions.class_id=?",
             class_id
         ).fetch_all(&self.conn).await;
 
@@ -119,7 +362,15 @@ impl lib::handlers::Database {
         // Query the database selecting the submitter_bearer, submission_id, submission_date
         // and the submission data from the submissions column.
         let query = sqlx::query_as!(Submission,
-            "SELECT submitter_bearer, submission_id, submission_date, data FROM submissions WHERE class_id=? AND submitter_bearer=?",
+            "SELECT submissions.submitter_bearer as \"submitter_bearer!\", submissions.submission_id as \"submission_id!\", submissions.lesson_id as \"lesson_id!\",
+            submissions.submission_date as \"submission_date!\", submissions.data as \"data!\", submissions.category_id as \"category_id!\",
+            submissions.graded as \"graded!\", submissions.mark as \"mark!\", submissions.out_of as \"out_of!\",
+            (COALESCE(lesson_extensions.due_date, lessons.due_date, 0) > 0 AND submissions.submission_date > COALESCE(lesson_extensions.due_date, lessons.due_date, 0)) as \"late!: i64\"
+            FROM submissions
+            LEFT JOIN lessons ON lessons.lesson_id=submissions.lesson_id
+            LEFT JOIN users ON users.bearer=submissions.submitter_bearer
+            LEFT JOIN lesson_extensions ON lesson_extensions.lesson_id=submissions.lesson_id AND lesson_extensions.user_id=users.user_id
+            WHERE submissions.class_id=? AND submissions.submitter_bearer=?",
             class_id, bearer
         ).fetch_all(&self.conn).await;
 
@@ -129,4 +380,44 @@ impl lib::handlers::Database {
             Err(_) => None
         };
     }
+
+    // The get_submitted_lessons() function is used to get how
+    // many times the provided bearer has submitted work for each
+    // lesson within the provided class, mapped by the lesson's id.
+    // Quiz attempts only count once they've been marked.
+    pub async fn get_submitted_lessons(&self, bearer: &str, class_id: &str) -> HashMap<String, i64> {
+        // Query the database
+        let query = sqlx::query!(
+            "SELECT submissions.lesson_id as \"lesson_id!\", COUNT(*) as \"attempts!: i64\" FROM submissions
+            JOIN lessons ON lessons.lesson_id=submissions.lesson_id
+            WHERE submissions.class_id=? AND submissions.submitter_bearer=? AND (submissions.graded=1 OR lessons.work_type='text')
+            GROUP BY submissions.lesson_id",
+            class_id, bearer
+        ).fetch_all(&self.conn).await;
+
+        // Return query result
+        return match query {
+            Ok(r) => r.into_iter().map(|s| (s.lesson_id, s.attempts)).collect(),
+            Err(_) => HashMap::new()
+        };
+    }
+
+    // The get_lesson_scores() function is used to get the bearer's
+    // best score (percentage) on each lesson's work within the
+    // provided class. Only marked submissions are counted.
+    pub async fn get_lesson_scores(&self, bearer: &str, class_id: &str) -> HashMap<String, f64> {
+        // Query the database
+        let query = sqlx::query!(
+            "SELECT lesson_id, MAX(mark * 100.0 / out_of) as \"score!: f64\" FROM submissions
+            WHERE class_id=? AND submitter_bearer=? AND graded=1 AND out_of>0 AND lesson_id!=''
+            GROUP BY lesson_id",
+            class_id, bearer
+        ).fetch_all(&self.conn).await;
+
+        // Return query result
+        return match query {
+            Ok(r) => r.into_iter().map(|s| (s.lesson_id, s.score)).collect(),
+            Err(_) => HashMap::new()
+        };
+    }
 }
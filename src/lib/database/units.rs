@@ -1,4 +1,5 @@
-use crate::lib;
+use crate::lib::{self, utils, structs::{Unit, UnlockedUnit}};
+use std::collections::HashSet;
 
 // Database Implementation
 impl lib::handlers::Database {
@@ -6,6 +7,7 @@ impl lib::handlers::Database {
     // The insert_class_unit() function is used to insert a new
     // unit into the database for the provided class. Students who
     // visit the class through the website, will see this unit appear.
+    // New units are placed after the class's existing units.
     pub async fn insert_class_unit(&self, bearer: &str, unit_id: &str, class_id: &str, unit_name: &str) -> bool {
         // If the unit already exists, return false
         if self.unit_exists(unit_id).await {
@@ -14,8 +16,8 @@ impl lib::handlers::Database {
 
         // Insert the data into the database
         let query = sqlx::query!(
-            "INSERT INTO units (owner_bearer, class_id, unit_id, unit_name, locked) VALUES (?, ?, ?, ?, ?)", 
-            bearer, class_id, unit_id, unit_name, 0
+            "INSERT INTO units (owner_bearer, class_id, unit_id, unit_name, locked, position) VALUES (?, ?, ?, ?, ?, (SELECT COALESCE(MAX(position) + 1, 0) FROM units WHERE class_id=?))", 
+            bearer, class_id, unit_id, unit_name, 0, class_id
         ).execute(&self.conn).await;
 
         // Return the result of the query
@@ -35,22 +37,132 @@ impl lib::handlers::Database {
             .await;
 
         // Return whether valid query data has been obtained
-        return !query.is_err();
+        return query.is_ok();
+    }
+
+    // The get_class_unit() function is used to get the
+    // unit data for the provided unit within the provided class.
+    pub async fn get_class_unit(&self, class_id: &str, unit_id: &str) -> Option<Unit> {
+        // Query the database
+        let query = sqlx::query_as!(Unit,
+            "SELECT unit_id, unit_name, locked, unlock_at, lock_at FROM units WHERE unit_id=? AND class_id=?",
+            unit_id, class_id
+        ).fetch_one(&self.conn).await;
+
+        // Return query result
+        return match query {
+            Ok(r) => Some(r),
+            Err(_) => None
+        };
+    }
+
+    // The get_lesson_unit() function is used to get the unit
+    // data for the unit that the provided lesson is within, as
+    // long as the unit is within the provided class.
+    pub async fn get_lesson_unit(&self, class_id: &str, lesson_id: &str) -> Option<Unit> {
+        // Query the database
+        let query = sqlx::query_as!(Unit,
+            "SELECT units.unit_id, units.unit_name, units.locked, units.unlock_at, units.lock_at
+            FROM units JOIN lessons ON lessons.unit_id=units.unit_id WHERE lessons.lesson_id=? AND units.class_id=?",
+            lesson_id, class_id
+        ).fetch_one(&self.conn).await;
+
+        // Return query result
+        return match query {
+            Ok(r) => Some(r),
+            Err(_) => None
+        };
+    }
+
+    // The get_class_unit_ids() function is used to get
+    // the id of every unit within the provided class.
+    pub async fn get_class_unit_ids(&self, class_id: &str) -> HashSet<String> {
+        // Query the database
+        let query = sqlx::query!(
+            "SELECT unit_id FROM units WHERE class_id=?", class_id
+        ).fetch_all(&self.conn).await;
+
+        // Return query result
+        return match query {
+            Ok(r) => r.into_iter().map(|u| u.unit_id).collect(),
+            Err(_) => HashSet::new()
+        };
+    }
+
+    // The is_unit_owner() function is used to check whether
+    // the provided bearer owns the provided unit and that the
+    // unit belongs to the provided class.
+    pub async fn is_unit_owner(&self, bearer: &str, class_id: &str, unit_id: &str) -> bool {
+        // Query the database
+        let query = sqlx::query!(
+            "SELECT id FROM units WHERE unit_id=? AND class_id=? AND owner_bearer=?",
+            unit_id, class_id, bearer
+        ).fetch_one(&self.conn).await;
+
+        // Return whether valid query data has been obtained
+        return query.is_ok();
     }
 
     // The delete_class_unit() function is used to delete a unit
     // from the units column wherever the provided unit_id
-    // is present. A maximum of 12 units is allowed per class.
+    // is present. The unit's question bank is deleted with it.
     pub async fn delete_class_unit(
         &self, 
         bearer: &str, 
         class_id: &str, 
         unit_id: &str
     ) -> bool {
+        let mut tx = match self.conn.begin().await {
+            Ok(tx) => tx,
+            Err(_) => return false
+        };
+
         // Query the database
         let query = sqlx::query!(
             "DELETE FROM units WHERE unit_id=? AND owner_bearer=? AND class_id=?",
             unit_id, bearer, class_id
+        ).execute(&mut tx).await;
+        if !matches!(query, Ok(r) if r.rows_affected() > 0) {
+            return false;
+        }
+
+        // Delete the unit's question bank
+        let query = sqlx::query!(
+            "DELETE FROM bank_questions WHERE unit_id=? AND class_id=?",
+            unit_id, class_id
+        ).execute(&mut tx).await;
+        if query.is_err() {
+            return false;
+        }
+
+        // Return whether the deletion was successful
+        return tx.commit().await.is_ok();
+    }
+
+    // The update_class_unit() function is used to update the
+    // provided unit's name, locked flag, or unlock and lock dates.
+    // Any value that isn't provided is left as is. If the unlock
+    // date is still to come, the students are notified by the
+    // scheduler once it passes.
+    pub async fn update_class_unit(
+        &self, 
+        bearer: &str, 
+        class_id: &str, 
+        unit_id: &str, 
+        data: &serde_json::Value
+    ) -> bool {
+        // Get the request body variables
+        let unit_name: Option<&str> = data["unit_name"].as_str();
+        let locked: Option<i64> = data["locked"].as_bool().map(|l| l as i64);
+        let unlock_at: Option<i64> = data["unlock_at"].as_i64();
+        let lock_at: Option<i64> = data["lock_at"].as_i64();
+        let now: i64 = utils::get_time().as_secs() as i64;
+
+        // Query the database
+        let query = sqlx::query!(
+            "UPDATE units SET unit_name=COALESCE(?, unit_name), locked=COALESCE(?, locked), unlock_at=COALESCE(?, unlock_at), lock_at=COALESCE(?, lock_at),
+            unlock_notified=(COALESCE(?, unlock_at) <= ?) WHERE unit_id=? AND owner_bearer=? AND class_id=?",
+            unit_name, locked, unlock_at, lock_at, unlock_at, now, unit_id, bearer, class_id
         ).execute(&self.conn).await;
 
         // Return query result
@@ -60,55 +172,140 @@ impl lib::handlers::Database {
         };
     }
 
-    // The generate_unit_update_query() function is used to generate
-    // a string that will be used for updating the unit data within
-    // the database. The function takes the request body as a parameter
-    // and returns a string that will be used in the update query.
-    async fn generate_unit_update_query(&self, data: serde_json::Value) -> String {
-        // Create a new string
-        let mut query: String = String::new();
+    // The reorder_class_units() function is used to change the order
+    // of the provided class's units. The request body must contain
+    // every unit within the class, in their new order. All the units
+    // are moved within a single transaction.
+    pub async fn reorder_class_units(&self, bearer: &str, class_id: &str, data: &serde_json::Value) -> bool {
+        // Only the class owner can reorder the units
+        if !self.is_class_owner(bearer, class_id).await {
+            return false;
+        }
 
-        // Get the unit name from the request body
-        match data.get("user_name") {
-            Some(name) => query.push_str(&format!("unit_name='{}',", name)),
-            None => ()
+        // Get the new order of the units
+        let unit_ids: Vec<&str> = match utils::get_ids(data, "unit_ids") {
+            Some(ids) => ids,
+            None => return false
         };
-        // Get the locked bool from the request body
-        match data.get("locked") {
-            Some(locked) => query.push_str(&format!("locked={},", locked.to_string())),
-            None => ()
+
+        // Make sure every unit within the class was provided
+        let query = sqlx::query!(
+            "SELECT unit_id FROM units WHERE class_id=?", class_id
+        ).fetch_all(&self.conn).await;
+        let current: Vec<String> = match query {
+            Ok(r) => r.into_iter().map(|u| u.unit_id).collect(),
+            Err(_) => return false
         };
+        if !utils::same_ids(&current, &unit_ids) {
+            return false;
+        }
 
-        // Remove the trailing comma
-        return query[..query.len() - 1].to_string();
+        // Update each unit's position within a single transaction
+        let mut tx = match self.conn.begin().await {
+            Ok(tx) => tx,
+            Err(_) => return false
+        };
+        for (position, unit_id) in unit_ids.iter().enumerate() {
+            let position: i64 = position as i64;
+            let query = sqlx::query!(
+                "UPDATE units SET position=? WHERE unit_id=? AND class_id=?",
+                position, unit_id, class_id
+            ).execute(&mut tx).await;
+            if query.is_err() {
+                return false;
+            }
+        }
+        return tx.commit().await.is_ok();
     }
 
-    // The update_class_unit() function is used to update a class's
-    // unit data replacing the current data, with that of the provided
-    // Json<UnitDataBody> values. In order to prevent null values
-    // being updated, the function first determines which values are null.
-    pub async fn update_class_unit(
-        &self, 
-        bearer: &str, 
-        class_id: &str, 
-        unit_id: &str, 
-        data: serde_json::Value
-    ) -> bool {
-        // Generate the query update data
-        let query_data: String = self.generate_unit_update_query(data).await;
+    // The unlock_due_units() function is used to get every unit
+    // whose unlock date has passed, that isn't locked by it's
+    // locked flag, and whose students haven't been notified yet.
+    // Units within archived classes are left until the class is
+    // restored. The units are marked as notified so the students
+    // aren't notified twice.
+    pub async fn unlock_due_units(&self) -> Vec<UnlockedUnit> {
+        let now: i64 = utils::get_time().as_secs() as i64;
+
+        // Query the database for the units that have unlocked
+        let query = sqlx::query_as!(UnlockedUnit,
+            "SELECT class_id, unit_id, unit_name FROM units WHERE unlock_notified=0 AND locked=0 AND unlock_at<=? AND (lock_at=0 OR lock_at>?) AND class_id NOT IN (SELECT class_id FROM classes WHERE archived=1)",
+            now, now
+        ).fetch_all(&self.conn).await;
+        let units: Vec<UnlockedUnit> = match query {
+            Ok(r) => r,
+            Err(_) => return Vec::new()
+        };
 
-        // Query the database, updating all the values
-        // in the above query_data: String that have the same
-        // unit_id as the one provided
-        let query = sqlx::query(&format!(
-            "UPDATE units SET {} WHERE unit_id='{}' AND owner_bearer='{}' AND class_id='{}'",
-            query_data, unit_id, bearer, class_id
-        )).execute(&self.conn).await;
+        // Mark each unit as notified, keeping only the ones
+        // that haven't already been marked by someone else
+        let mut unlocked: Vec<UnlockedUnit> = Vec::new();
+        for u in units {
+            let query = sqlx::query!(
+                "UPDATE units SET unlock_notified=1 WHERE unit_id=? AND unlock_notified=0",
+                u.unit_id
+            ).execute(&self.conn).await;
+            if let Ok(r) = query {
+                if r.rows_affected() > 0 {
+                    unlocked.push(u);
+                }
+            }
+        }
+        return unlocked;
+    }
+}
 
-        // Return query result
-        return match query {
-            Ok(r) => r.rows_affected() > 0,
-            Err(_) => false,
+// The is_unit_locked() function is used to get whether the
+// provided unit is locked at the provided time. The unit is
+// locked before it's unlock date and once it's lock date
+// passes. Within those dates, the unit's locked flag is used.
+pub fn is_unit_locked(unit: &Unit, now: i64) -> bool {
+    if unit.lock_at > 0 && now >= unit.lock_at {
+        return true;
+    }
+    if unit.unlock_at > 0 && now < unit.unlock_at {
+        return true;
+    }
+    return unit.locked == 1;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unit(locked: i64, unlock_at: i64, lock_at: i64) -> Unit {
+        return Unit {
+            unit_id: String::from("unit"),
+            unit_name: String::from("Unit"),
+            locked,
+            unlock_at,
+            lock_at
         };
     }
+
+    #[test]
+    fn uses_locked_flag_without_dates() {
+        assert!(!is_unit_locked(&unit(0, 0, 0), 100));
+        assert!(is_unit_locked(&unit(1, 0, 0), 100));
+    }
+
+    #[test]
+    fn locked_before_unlock_date() {
+        assert!(is_unit_locked(&unit(0, 200, 0), 100));
+        assert!(!is_unit_locked(&unit(0, 200, 0), 200));
+    }
+
+    #[test]
+    fn locked_after_lock_date() {
+        assert!(!is_unit_locked(&unit(0, 0, 200), 199));
+        assert!(is_unit_locked(&unit(0, 0, 200), 200));
+        assert!(is_unit_locked(&unit(0, 100, 200), 300));
+    }
+
+    #[test]
+    fn locked_flag_applies_within_dates() {
+        assert!(is_unit_locked(&unit(1, 100, 0), 150));
+        assert!(is_unit_locked(&unit(1, 100, 200), 150));
+        assert!(!is_unit_locked(&unit(0, 100, 200), 150));
+    }
 }
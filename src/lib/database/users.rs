@@ -56,7 +56,7 @@ impl lib::handlers::Database {
         ).fetch_one(&self.conn).await;
 
         // Return whether valid query data has been obtained
-        return !query.is_err();
+        return query.is_ok();
     }
 
     // The query_user_by_id() function is used to query
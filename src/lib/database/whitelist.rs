@@ -1,4 +1,4 @@
-use crate::lib;
+use crate::lib::{self, structs::Recipient};
 
 // Database Implementation
 impl lib::handlers::Database {
@@ -47,4 +47,38 @@ impl lib::handlers::Database {
             Err(_) => false,
         };
     }
+
+    // The is_class_student() function is used to check
+    // whether the provided user is within the provided
+    // class's whitelist.
+    pub async fn is_class_student(&self, class_id: &str, user_id: &str) -> bool {
+        // Query the database
+        let query = sqlx::query!(
+            "SELECT id FROM whitelists WHERE class_id=? AND whitelisted_user_id=?",
+            class_id, user_id
+        ).fetch_one(&self.conn).await;
+
+        // Return whether valid query data has been obtained
+        return query.is_ok();
+    }
+
+    // The get_class_students() function is used to get the
+    // name and email of every user within the provided class's
+    // whitelist. These are the users who receive the class's
+    // email notifications.
+    pub async fn get_class_students(&self, class_id: &str) -> Vec<Recipient> {
+        // Query the database
+        let query = sqlx::query_as!(Recipient,
+            "SELECT users.user_id as \"user_id!\", users.user_name as \"user_name!\", users.email as \"email!\" FROM whitelists
+            JOIN users ON users.user_id=whitelists.whitelisted_user_id
+            WHERE whitelists.class_id=?",
+            class_id
+        ).fetch_all(&self.conn).await;
+
+        // Return query result
+        return match query {
+            Ok(r) => r,
+            Err(_) => Vec::new()
+        };
+    }
 }
@@ -1,4 +1,4 @@
-use crate::lib::{self, utils, handlers::Database, http};
+use crate::lib::{self, utils, handlers::Database, http, mail::Mailer, events::Events, quotas::{self, Quotas}};
 use actix_web::{web, HttpRequest, HttpResponse};
 
 // The insert_class_announcement() endpoint is used to insert a new announcement into the database.
@@ -7,6 +7,9 @@ use actix_web::{web, HttpRequest, HttpResponse};
 async fn insert_class_announcement(
     req: HttpRequest,
     db: web::Data<Database>,
+    quotas: web::Data<Quotas>,
+    events: web::Data<Events>,
+    mailer: web::Data<Mailer>,
     body: web::Bytes,
 ) -> HttpResponse {
     // Get the request body
@@ -49,21 +52,75 @@ async fn insert_class_announcement(
         );
     }
 
+    // Only the class owner can post announcements
+    if !db.is_class_owner(&bearer, class_id).await {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid request"
+            }),
+        );
+    }
+
+    // Make sure the announcement fits within the class's storage quota.
+    // The quota stays locked until the announcement has been inserted.
+    let _quota = quotas.lock(class_id).await;
+    if let Err(r) = quotas::check("storage_per_class", quotas.storage_per_class, db.get_class_storage(class_id).await, body.to_string().len() as i64) {
+        return *r;
+    }
+
     // Generate a new announcement id
-    let announcement_id: String = utils::generate_new_id(&class_id);
+    let announcement_id: String = utils::generate_new_id(class_id);
+
+    // Check whether the announcement is scheduled for later
+    let now: i64 = utils::get_time().as_secs() as i64;
+    let scheduled: bool = body["publish_at"].as_i64().is_some_and(|t| t > now);
 
     // Insert the announcement into the database
     return match db
-        .insert_class_announcement(&bearer, &class_id, &announcement_id, &body)
+        .insert_class_announcement(&bearer, class_id, &announcement_id, &body)
         .await
     {
-        true => http::response(
-            http::Status::OK,
-            serde_json::json!({
-                "response": "Announcement successfully created",
-                "announcement_id": announcement_id
-            }),
-        ),
+        true if scheduled => {
+            // Scheduled announcements are only pushed to the class owner. The
+            // students are notified by the scheduler once it's published.
+            events.publish(class_id, "announcement_scheduled", serde_json::json!({
+                "announcement_id": announcement_id,
+                "publish_at": body["publish_at"]
+            }), true);
+            http::response(
+                http::Status::OK,
+                serde_json::json!({
+                    "response": "Announcement successfully scheduled",
+                    "announcement_id": announcement_id
+                }),
+            )
+        }
+        true => {
+            // Push the announcement to the class's event stream
+            events.publish(class_id, "announcement_created", serde_json::json!({
+                "announcement_id": announcement_id,
+                "title": body["title"],
+                "description": body["description"],
+                "attachment": body["attachment"]
+            }), false);
+
+            // Notify the class's students in the background
+            let class_id: String = class_id.to_string();
+            actix_web::rt::spawn(async move {
+                let title: &str = body["title"].as_str().unwrap_or("");
+                let description: &str = body["description"].as_str().unwrap_or("");
+                db.insert_class_notification(&class_id, "announcement", title, description).await;
+                mailer.notify_announcement(&db, &class_id, title, description).await;
+            });
+            http::response(
+                http::Status::OK,
+                serde_json::json!({
+                    "response": "Announcement successfully created",
+                    "announcement_id": announcement_id
+                }),
+            )
+        }
         false => http::response(
             http::Status::BAD_REQUEST,
             serde_json::json!({
@@ -77,7 +134,11 @@ async fn insert_class_announcement(
 // from the database. This function requires a bearer token which means the
 // user making the announcement must be signed in.
 #[actix_web::delete("/class/{class_id}/announcements/{announcement_id}")]
-async fn delete_class_announcement(req: HttpRequest, db: web::Data<Database>) -> HttpResponse {
+async fn delete_class_announcement(
+    req: HttpRequest,
+    db: web::Data<Database>,
+    events: web::Data<Events>,
+) -> HttpResponse {
     // Get the class id
     let class_id: &str = match req.match_info().get("class_id") {
         Some(id) => id,
@@ -123,16 +184,198 @@ async fn delete_class_announcement(req: HttpRequest, db: web::Data<Database>) ->
         .delete_class_announcement(&bearer, class_id, announcement_id)
         .await
     {
-        true => http::response(
-            http::Status::OK,
+        true => {
+            // Push the deletion to the class's event stream
+            events.publish(class_id, "announcement_deleted", serde_json::json!({ "announcement_id": announcement_id }), false);
+            http::response(
+                http::Status::OK,
+                serde_json::json!({
+                    "response": "Announcement succesfully deleted"
+                }),
+            )
+        }
+        false => http::response(
+            http::Status::BAD_REQUEST,
             serde_json::json!({
-                "response": "Announcement succesfully deleted"
+                "response": "Failed to delete announcement"
             }),
         ),
+    };
+}
+
+// The update_class_announcement() endpoint is used to edit or pin an announcement.
+// The previous version of the announcement is kept within it's edit history, so
+// fixing a typo no longer requires deleting the post and losing it's date.
+#[actix_web::post("/class/{class_id}/announcements/{announcement_id}")]
+async fn update_class_announcement(
+    req: HttpRequest,
+    db: web::Data<Database>,
+    quotas: web::Data<Quotas>,
+    events: web::Data<Events>,
+    body: web::Bytes,
+) -> HttpResponse {
+    // Get the request body
+    let body: serde_json::Value = match http::body(&body) {
+        Ok(body) => body,
+        Err(_) => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request body"
+                }),
+            )
+        }
+    };
+
+    // Get the class id
+    let class_id: &str = match req.match_info().get("class_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the announcement id
+    let announcement_id: &str = match req.match_info().get("announcement_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the bearer and access token from the request headers.
+    let bearer: String = http::header(&req, "authorization");
+    let access_token: String = http::header(&req, "access_token");
+
+    // Verify the provided authorization tokens
+    if !lib::auth::verify(&bearer, &access_token) {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid request"
+            }),
+        );
+    }
+
+    // Only the class owner can modify announcements
+    if !db.is_class_owner(&bearer, class_id).await {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid request"
+            }),
+        );
+    }
+
+    // Make sure the announcement fits within the class's storage quota.
+    // The quota stays locked until the announcement has been updated.
+    let _quota = quotas.lock(class_id).await;
+    if let Err(r) = quotas::check("storage_per_class", quotas.storage_per_class, db.get_class_storage(class_id).await, body.to_string().len() as i64) {
+        return *r;
+    }
+
+    // Update the announcement in the database
+    return match db
+        .update_class_announcement(&bearer, class_id, announcement_id, &body)
+        .await
+    {
+        true => {
+            // Push the change to the class's event stream. Changes to a
+            // scheduled announcement are only pushed to the class owner.
+            let scheduled: bool = !db.is_announcement_published(class_id, announcement_id).await.unwrap_or(false);
+            events.publish(class_id, "announcement_updated", serde_json::json!({ "announcement_id": announcement_id }), scheduled);
+            http::response(
+                http::Status::OK,
+                serde_json::json!({
+                    "response": "Announcement successfully updated"
+                }),
+            )
+        }
         false => http::response(
             http::Status::BAD_REQUEST,
             serde_json::json!({
-                "response": "Failed to delete announcement"
+                "response": "Failed to update announcement"
+            }),
+        ),
+    };
+}
+
+// The get_announcement_history() endpoint is used to get every previous
+// version of the provided announcement. Only users that can see the
+// class can see the announcement's history.
+#[actix_web::get("/class/{class_id}/announcements/{announcement_id}/history")]
+async fn get_announcement_history(req: HttpRequest, db: web::Data<Database>) -> HttpResponse {
+    // Get the class id
+    let class_id: &str = match req.match_info().get("class_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the announcement id
+    let announcement_id: &str = match req.match_info().get("announcement_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the bearer and access token from the request headers.
+    let bearer: String = http::header(&req, "authorization");
+    let access_token: String = http::header(&req, "access_token");
+
+    // Verify the provided authorization tokens
+    if !lib::auth::verify(&bearer, &access_token) {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid request"
+            }),
+        );
+    }
+
+    // Only users that can see the class can see the history
+    if !db.can_view_class(&bearer, class_id).await {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid request"
+            }),
+        );
+    }
+
+    // Return the announcement history
+    let staff: bool = db.is_class_owner(&bearer, class_id).await;
+    return match db.get_announcement_history(class_id, announcement_id, staff).await {
+        Some(history) => http::response(
+            http::Status::OK,
+            serde_json::json!({ "response": history }),
+        ),
+        None => http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Failed to fetch announcement history"
             }),
         ),
     };
@@ -0,0 +1,333 @@
+use crate::lib::{self, utils, bundle, imports, handlers::Database, http, quotas::{self, Quotas}};
+use actix_web::{web, HttpRequest, HttpResponse};
+
+// The export_class_bundle() endpoint is used by the class
+// owner to export their class as a portable bundle, for
+// backing up the class or moving it to another server. The
+// class's submissions are included if ?submissions=true.
+#[actix_web::get("/class/{class_id}/export")]
+async fn export_class_bundle(req: HttpRequest, db: web::Data<Database>) -> HttpResponse {
+    // Get the class id from the url parameters
+    let class_id: &str = match req.match_info().get("class_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the bearer and access token from the request headers.
+    let bearer: String = http::header(&req, "authorization");
+    let access_token: String = http::header(&req, "access_token");
+
+    // Verify the provided authorization tokens
+    if !lib::auth::verify(&bearer, &access_token) {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid request"
+            }),
+        );
+    }
+
+    // Export the class
+    let include_submissions: bool = http::query(&req, "submissions") == "true";
+    return match db.export_class_bundle(&bearer, class_id, include_submissions).await {
+        Some(bundle) => http::response(http::Status::OK, serde_json::json!({ "response": bundle })),
+        None => http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Failed to export class"
+            }),
+        ),
+    };
+}
+
+// The import_class_bundle() endpoint is used to create a new
+// class owned by the bearer from an exported class bundle.
+// The request body is the bundle itself.
+#[actix_web::put("/class/import")]
+async fn import_class_bundle(
+    req: HttpRequest,
+    db: web::Data<Database>,
+    quotas: web::Data<Quotas>,
+    payload: web::Payload,
+) -> HttpResponse {
+    // Get the bundle from the request body. Bundles
+    // can be larger than a regular request body.
+    let body: serde_json::Value = match http::payload(payload, bundle::MAX_BUNDLE_SIZE).await.and_then(|b| http::body(&b)) {
+        Ok(body) => body,
+        Err(_) => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request body"
+                }),
+            )
+        }
+    };
+
+    // Get the bearer and access token from the request headers.
+    let bearer: String = http::header(&req, "authorization");
+    let access_token: String = http::header(&req, "access_token");
+
+    // Verify the provided authorization tokens
+    if !lib::auth::verify(&bearer, &access_token) {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid request"
+            }),
+        );
+    }
+
+    // Make sure the bundle's version is supported
+    if body["version"].as_i64() != Some(bundle::BUNDLE_VERSION) {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Unsupported bundle version"
+            }),
+        );
+    }
+
+    // Make sure the user hasn't reached their class quota. The
+    // quota stays locked until the class has been imported.
+    let _quota = quotas.lock(&bearer).await;
+    if let Err(r) = quotas::check("classes_per_user", quotas.classes_per_user, db.count_user_classes(&bearer).await, 1) {
+        return *r;
+    }
+
+    // Make sure the imported class fits within the class quotas
+    if let Err(r) = quotas.check_bundle(&body) {
+        return *r;
+    }
+
+    // Generate a new class id
+    let class_id: String = utils::generate_new_id(&bearer);
+
+    // Import the bundle into the new class
+    return match db.import_class_bundle(&bearer, &class_id, &body).await {
+        true => http::response(
+            http::Status::OK,
+            serde_json::json!({
+                "response": "Imported class",
+                "class_id": class_id
+            }),
+        ),
+        false => http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid bundle"
+            }),
+        ),
+    };
+}
+
+// The import_course_archive() endpoint is used to create a new
+// class owned by the bearer from a course exported by another
+// platform. The request body is an IMS Common Cartridge or
+// a Moodle backup. Everything within the course that couldn't
+// be converted is returned as unmapped.
+#[actix_web::put("/class/import/archive")]
+async fn import_course_archive(
+    req: HttpRequest,
+    db: web::Data<Database>,
+    quotas: web::Data<Quotas>,
+    payload: web::Payload,
+) -> HttpResponse {
+    // Get the bearer and access token from the request headers. The
+    // request is verified before the archive is read, so that
+    // unauthorized uploads are rejected right away.
+    let bearer: String = http::header(&req, "authorization");
+    let access_token: String = http::header(&req, "access_token");
+
+    // Verify the provided authorization tokens
+    if !lib::auth::verify(&bearer, &access_token) {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid request"
+            }),
+        );
+    }
+
+    // Get the uploaded archive from the request body
+    let archive: web::Bytes = match http::payload(payload, imports::MAX_ARCHIVE_SIZE).await {
+        Ok(archive) => archive,
+        Err(_) => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request body"
+                }),
+            )
+        }
+    };
+
+    // Convert the course into a class bundle. Archives are converted
+    // on a blocking thread so they don't hold up other requests.
+    let result: imports::ImportResult = match web::block(move || imports::convert(&archive)).await {
+        Ok(Some(result)) => result,
+        _ => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Unsupported course archive"
+                }),
+            )
+        }
+    };
+
+    // Make sure the user hasn't reached their class quota. The
+    // quota stays locked until the class has been imported.
+    let _quota = quotas.lock(&bearer).await;
+    if let Err(r) = quotas::check("classes_per_user", quotas.classes_per_user, db.count_user_classes(&bearer).await, 1) {
+        return *r;
+    }
+
+    // Make sure the imported class fits within the class quotas
+    if let Err(r) = quotas.check_bundle(&result.bundle) {
+        return *r;
+    }
+
+    // Generate a new class id
+    let class_id: String = utils::generate_new_id(&bearer);
+
+    // Import the converted course into the new class
+    return match db.import_class_bundle(&bearer, &class_id, &result.bundle).await {
+        true => http::response(
+            http::Status::OK,
+            serde_json::json!({
+                "response": "Imported class",
+                "class_id": class_id,
+                "unmapped": result.unmapped
+            }),
+        ),
+        false => http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Failed to import course"
+            }),
+        ),
+    };
+}
+
+// The import_classroom_takeout() endpoint is used to create a new
+// class owned by the bearer from a Google Classroom Takeout archive.
+// If the archive contains more than one class, the class to import
+// must be chosen using ?class={class_name}. With ?dry_run=true,
+// a preview of every class is returned and nothing is imported.
+#[actix_web::put("/class/import/takeout")]
+async fn import_classroom_takeout(
+    req: HttpRequest,
+    db: web::Data<Database>,
+    quotas: web::Data<Quotas>,
+    payload: web::Payload,
+) -> HttpResponse {
+    // Get the bearer and access token from the request headers. The
+    // request is verified before the archive is read, so that
+    // unauthorized uploads are rejected right away.
+    let bearer: String = http::header(&req, "authorization");
+    let access_token: String = http::header(&req, "access_token");
+
+    // Verify the provided authorization tokens
+    if !lib::auth::verify(&bearer, &access_token) {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid request"
+            }),
+        );
+    }
+
+    // Get the uploaded archive from the request body
+    let archive: web::Bytes = match http::payload(payload, imports::MAX_ARCHIVE_SIZE).await {
+        Ok(archive) => archive,
+        Err(_) => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request body"
+                }),
+            )
+        }
+    };
+
+    // Convert the archive's classes into class bundles. Archives are
+    // converted on a blocking thread so they don't hold up other requests.
+    let mut classes: Vec<imports::ImportResult> = match web::block(move || imports::convert_takeout(&archive)).await {
+        Ok(Some(classes)) => classes,
+        _ => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Unsupported takeout archive"
+                }),
+            )
+        }
+    };
+
+    // Only keep the class that was chosen, if one was
+    let class_name: String = http::query(&req, "class");
+    if !class_name.is_empty() {
+        classes.retain(|c| c.bundle["class"]["class_name"] == class_name.as_str());
+    }
+
+    // Return a preview of the classes without importing them
+    if http::query(&req, "dry_run") == "true" {
+        return http::response(
+            http::Status::OK,
+            serde_json::json!({
+                "response": classes.iter().map(|c| c.preview()).collect::<Vec<serde_json::Value>>()
+            }),
+        );
+    }
+
+    // Make sure a single class is being imported
+    if classes.len() != 1 {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Choose a class to import"
+            }),
+        );
+    }
+
+    // Make sure the user hasn't reached their class quota. The
+    // quota stays locked until the class has been imported.
+    let _quota = quotas.lock(&bearer).await;
+    if let Err(r) = quotas::check("classes_per_user", quotas.classes_per_user, db.count_user_classes(&bearer).await, 1) {
+        return *r;
+    }
+
+    // Make sure the imported class fits within the class quotas
+    if let Err(r) = quotas.check_bundle(&classes[0].bundle) {
+        return *r;
+    }
+
+    // Generate a new class id
+    let class_id: String = utils::generate_new_id(&bearer);
+
+    // Import the converted class into the new class
+    return match db.import_class_bundle(&bearer, &class_id, &classes[0].bundle).await {
+        true => http::response(
+            http::Status::OK,
+            serde_json::json!({
+                "response": "Imported class",
+                "class_id": class_id,
+                "unmapped": classes[0].unmapped
+            }),
+        ),
+        false => http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Failed to import class"
+            }),
+        ),
+    };
+}
@@ -1,4 +1,4 @@
-use crate::lib::{self, utils, handlers::Database, http};
+use crate::lib::{self, utils, handlers::Database, http, events::Events, quotas::{self, Quotas}};
 use actix_web::{web, HttpRequest, HttpResponse};
 
 // The get_class_data() endpoint is used to get the class's
@@ -33,8 +33,10 @@ async fn get_class_data(req: HttpRequest, db: web::Data<Database>) -> HttpRespon
         );
     }
 
-    // Return the class data
-    return match db.get_class_data(&class_id).await {
+    // Return the class data. The class owner can
+    // see the class's unpublished data.
+    let staff: bool = db.is_class_owner(&bearer, class_id).await;
+    return match db.get_class_data(&bearer, class_id, staff).await {
         Some(data) => http::response(http::Status::OK, serde_json::json!({ "response": data })),
         None => http::response(
             http::Status::BAD_REQUEST,
@@ -52,6 +54,7 @@ async fn get_class_data(req: HttpRequest, db: web::Data<Database>) -> HttpRespon
 async fn update_class_data(
     req: HttpRequest,
     db: web::Data<Database>,
+    events: web::Data<Events>,
     body: web::Bytes,
 ) -> HttpResponse {
     // Get the request body
@@ -97,13 +100,22 @@ async fn update_class_data(
     // Generate a class update query which is the fastest way
     // for updating multiple values inside the database before
     // executing the database update using the below function
+    let whitelist_changed: bool = body.get("enable_whitelist").is_some();
     return match db.update_class_data(&bearer, class_id, body).await {
-        true => http::response(
-            http::Status::OK,
-            serde_json::json!({
-                "response": "Updated class data"
-            }),
-        ),
+        true => {
+            // Close the class's event streams if the whitelist setting
+            // changed, so that only the users who can still see the
+            // class can reconnect
+            if whitelist_changed {
+                events.disconnect(class_id, None);
+            }
+            http::response(
+                http::Status::OK,
+                serde_json::json!({
+                    "response": "Updated class data"
+                }),
+            )
+        }
         false => http::response(
             http::Status::BAD_REQUEST,
             serde_json::json!({
@@ -115,11 +127,13 @@ async fn update_class_data(
 
 // The insert_class_data() endpoint is used to
 // create a new class that contains all the default
-// values. A Maximum of 5 classes is allowed.
+// values. The number of classes a user can own
+// is limited by the classes_per_user quota.
 #[actix_web::put("/class")]
 async fn insert_class_data(
     req: HttpRequest,
     db: web::Data<Database>,
+    quotas: web::Data<Quotas>,
     body: web::Bytes,
 ) -> HttpResponse {
     // Get the request body
@@ -162,6 +176,13 @@ async fn insert_class_data(
         );
     }
 
+    // Make sure the user hasn't reached their class quota. The
+    // quota stays locked until the class has been inserted.
+    let _quota = quotas.lock(&bearer).await;
+    if let Err(r) = quotas::check("classes_per_user", quotas.classes_per_user, db.count_user_classes(&bearer).await, 1) {
+        return *r;
+    }
+
     // Generate a new class id
     let class_id: String = utils::generate_new_id(&bearer);
 
@@ -181,3 +202,342 @@ async fn insert_class_data(
         ),
     };
 }
+
+// The clone_class_data() endpoint is used by the class owner
+// to copy their class into a new class, such as for a new term.
+// The whitelist and submissions are never copied, and the
+// announcements are only copied if include_announcements is set.
+#[actix_web::post("/class/{class_id}/clone")]
+async fn clone_class_data(
+    req: HttpRequest,
+    db: web::Data<Database>,
+    quotas: web::Data<Quotas>,
+    body: web::Bytes,
+) -> HttpResponse {
+    // Get the request body
+    let body: serde_json::Value = match http::body(&body) {
+        Ok(body) => body,
+        Err(_) => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request body"
+                }),
+            )
+        }
+    };
+
+    // Get the class id from the url parameters
+    let class_id: &str = match req.match_info().get("class_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the bearer and access token from the request headers.
+    let bearer: String = http::header(&req, "authorization");
+    let access_token: String = http::header(&req, "access_token");
+
+    // Verify the provided authorization tokens
+    if !lib::auth::verify(&bearer, &access_token) {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid request"
+            }),
+        );
+    }
+
+    // Get the clone options from the request body. The
+    // copy keeps the class's name unless a new one is provided.
+    let class_name: Option<&str> = body["class_name"].as_str();
+    let include_announcements: bool = body["include_announcements"].as_bool().unwrap_or(false);
+
+    // Only the class owner can clone the class
+    if !db.is_class_owner(&bearer, class_id).await {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid request"
+            }),
+        );
+    }
+
+    // Make sure the user hasn't reached their class quota. The
+    // quota stays locked until the class has been copied.
+    let _quota = quotas.lock(&bearer).await;
+    if let Err(r) = quotas::check("classes_per_user", quotas.classes_per_user, db.count_user_classes(&bearer).await, 1) {
+        return *r;
+    }
+
+    // Generate a new class id
+    let new_class_id: String = utils::generate_new_id(&bearer);
+
+    // Copy the class into the new class
+    return match db.clone_class_data(&bearer, class_id, &new_class_id, class_name, include_announcements).await {
+        true => http::response(
+            http::Status::OK,
+            serde_json::json!({
+                "response": "Cloned class",
+                "class_id": new_class_id
+            }),
+        ),
+        false => http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Failed to clone class"
+            }),
+        ),
+    };
+}
+
+// The archive_class_data() endpoint is used by the class owner
+// to archive their class. Archived classes are read-only and
+// are hidden from the owner's active classes. The class's event
+// streams are closed, as nothing new will be published to them.
+#[actix_web::post("/class/{class_id}/archive")]
+async fn archive_class_data(
+    req: HttpRequest,
+    db: web::Data<Database>,
+    events: web::Data<Events>,
+) -> HttpResponse {
+    // Get the class id from the url parameters
+    let class_id: &str = match req.match_info().get("class_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the bearer and access token from the request headers.
+    let bearer: String = http::header(&req, "authorization");
+    let access_token: String = http::header(&req, "access_token");
+
+    // Verify the provided authorization tokens
+    if !lib::auth::verify(&bearer, &access_token) {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid request"
+            }),
+        );
+    }
+
+    // Archive the class
+    return match db.set_class_archived(&bearer, class_id, true).await {
+        true => {
+            events.publish(class_id, "class_archived", serde_json::json!({ "class_id": class_id }), false);
+            events.disconnect(class_id, None);
+            http::response(
+                http::Status::OK,
+                serde_json::json!({
+                    "response": "Archived class"
+                }),
+            )
+        }
+        false => http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Failed to archive class"
+            }),
+        ),
+    };
+}
+
+// The restore_class_data() endpoint is used by the class
+// owner to restore an archived class.
+#[actix_web::post("/class/{class_id}/restore")]
+async fn restore_class_data(
+    req: HttpRequest,
+    db: web::Data<Database>,
+    events: web::Data<Events>,
+) -> HttpResponse {
+    // Get the class id from the url parameters
+    let class_id: &str = match req.match_info().get("class_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the bearer and access token from the request headers.
+    let bearer: String = http::header(&req, "authorization");
+    let access_token: String = http::header(&req, "access_token");
+
+    // Verify the provided authorization tokens
+    if !lib::auth::verify(&bearer, &access_token) {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid request"
+            }),
+        );
+    }
+
+    // Restore the class
+    return match db.set_class_archived(&bearer, class_id, false).await {
+        true => {
+            events.publish(class_id, "class_restored", serde_json::json!({ "class_id": class_id }), false);
+            http::response(
+                http::Status::OK,
+                serde_json::json!({
+                    "response": "Restored class"
+                }),
+            )
+        }
+        false => http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Failed to restore class"
+            }),
+        ),
+    };
+}
+
+// The get_deletion_token() endpoint is used by the class owner
+// to get the confirmation token required for permanently
+// deleting their class. The token expires after a few minutes.
+#[actix_web::get("/class/{class_id}/deletion_token")]
+async fn get_deletion_token(req: HttpRequest, db: web::Data<Database>) -> HttpResponse {
+    // Get the class id from the url parameters
+    let class_id: &str = match req.match_info().get("class_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the bearer and access token from the request headers.
+    let bearer: String = http::header(&req, "authorization");
+    let access_token: String = http::header(&req, "access_token");
+
+    // Verify the provided authorization tokens
+    if !lib::auth::verify(&bearer, &access_token) {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid request"
+            }),
+        );
+    }
+
+    // Only the class owner can delete the class
+    return match db.is_class_owner(&bearer, class_id).await {
+        true => http::response(
+            http::Status::OK,
+            serde_json::json!({
+                "response": lib::auth::current_deletion_token(&bearer, class_id)
+            }),
+        ),
+        false => http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid request"
+            }),
+        ),
+    };
+}
+
+// The delete_class_data() endpoint is used by the class owner
+// to permanently delete their class, along with all of it's
+// units, lessons, announcements, whitelist and submissions.
+// The request body must contain the class's deletion token.
+#[actix_web::delete("/class/{class_id}")]
+async fn delete_class_data(
+    req: HttpRequest,
+    db: web::Data<Database>,
+    events: web::Data<Events>,
+    body: web::Bytes,
+) -> HttpResponse {
+    // Get the request body
+    let body: serde_json::Value = match http::body(&body) {
+        Ok(body) => body,
+        Err(_) => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request body"
+                }),
+            )
+        }
+    };
+
+    // Get the class id from the url parameters
+    let class_id: &str = match req.match_info().get("class_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the bearer and access token from the request headers.
+    let bearer: String = http::header(&req, "authorization");
+    let access_token: String = http::header(&req, "access_token");
+
+    // Verify the provided authorization tokens
+    if !lib::auth::verify(&bearer, &access_token) {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid request"
+            }),
+        );
+    }
+
+    // Verify the deletion token
+    let confirmation_token: &str = body["confirmation_token"].as_str().unwrap_or("");
+    if !lib::auth::verify_deletion_token(&bearer, class_id, confirmation_token) {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid confirmation token"
+            }),
+        );
+    }
+
+    // Delete the class and all of it's data
+    return match db.delete_class_data(&bearer, class_id).await {
+        true => {
+            events.publish(class_id, "class_deleted", serde_json::json!({ "class_id": class_id }), false);
+            events.disconnect(class_id, None);
+            http::response(
+                http::Status::OK,
+                serde_json::json!({
+                    "response": "Deleted class"
+                }),
+            )
+        }
+        false => http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Failed to delete class"
+            }),
+        ),
+    };
+}
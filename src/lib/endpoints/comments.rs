@@ -0,0 +1,369 @@
+use crate::lib::{self, utils, handlers::Database, http, events::Events, quotas::{self, Quotas}};
+use actix_web::{web, HttpRequest, HttpResponse};
+
+// The get_announcement_comments() endpoint is used to get the comment thread
+// under the provided announcement. Replies are nested beneath the comment
+// they're replying to.
+#[actix_web::get("/class/{class_id}/announcements/{announcement_id}/comments")]
+async fn get_announcement_comments(req: HttpRequest, db: web::Data<Database>) -> HttpResponse {
+    // Get the class id
+    let class_id: &str = match req.match_info().get("class_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the announcement id
+    let announcement_id: &str = match req.match_info().get("announcement_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the bearer and access token from the request headers.
+    let bearer: String = http::header(&req, "authorization");
+    let access_token: String = http::header(&req, "access_token");
+
+    // Verify the provided authorization tokens
+    if !lib::auth::verify(&bearer, &access_token) {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid request"
+            }),
+        );
+    }
+
+    // Only users that can see the class can see it's comments
+    if !db.can_view_class(&bearer, class_id).await {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid request"
+            }),
+        );
+    }
+
+    // Return the comment thread
+    let staff: bool = db.is_class_owner(&bearer, class_id).await;
+    return match db.get_announcement_comments(class_id, announcement_id, staff).await {
+        Some(comments) => http::response(
+            http::Status::OK,
+            serde_json::json!({ "response": comments }),
+        ),
+        None => http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Failed to fetch comments"
+            }),
+        ),
+    };
+}
+
+// The insert_announcement_comment() endpoint is used to comment under an
+// announcement, or to reply to another comment if a parent_id is provided.
+// A unique comment identifier is returned so the user can later delete it.
+#[actix_web::put("/class/{class_id}/announcements/{announcement_id}/comments")]
+async fn insert_announcement_comment(
+    req: HttpRequest,
+    db: web::Data<Database>,
+    quotas: web::Data<Quotas>,
+    events: web::Data<Events>,
+    body: web::Bytes,
+) -> HttpResponse {
+    // Get the request body
+    let body: serde_json::Value = match http::body(&body) {
+        Ok(body) => body,
+        Err(_) => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request body"
+                }),
+            )
+        }
+    };
+
+    // Get the class id
+    let class_id: &str = match req.match_info().get("class_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the announcement id
+    let announcement_id: &str = match req.match_info().get("announcement_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the bearer and access token from the request headers.
+    let bearer: String = http::header(&req, "authorization");
+    let access_token: String = http::header(&req, "access_token");
+
+    // Verify the provided authorization tokens
+    if !lib::auth::verify(&bearer, &access_token) {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid request"
+            }),
+        );
+    }
+
+    // Only users that can see the class can comment
+    if !db.can_view_class(&bearer, class_id).await {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid request"
+            }),
+        );
+    }
+
+    // Make sure the comment fits within the user's comment quota. Comments
+    // don't count towards the class's storage, so that students can't use
+    // up the space the class owner needs. The quota stays locked until
+    // the comment has been inserted.
+    let _quota = quotas.lock(class_id).await;
+    if let Err(r) = quotas::check("comment_storage_per_user", quotas.comment_storage_per_user, db.get_user_comment_storage(&bearer, class_id).await, body.to_string().len() as i64) {
+        return *r;
+    }
+
+    // Generate a new comment id
+    let comment_id: String = utils::generate_new_id(announcement_id);
+
+    // Insert the comment into the database
+    let staff: bool = db.is_class_owner(&bearer, class_id).await;
+    return match db
+        .insert_announcement_comment(&bearer, class_id, announcement_id, &comment_id, staff, &body)
+        .await
+    {
+        true => {
+            // Push the comment to the class's event stream
+            events.publish(class_id, "comment_created", serde_json::json!({
+                "announcement_id": announcement_id,
+                "comment_id": comment_id,
+                "parent_id": body["parent_id"].as_str().unwrap_or("")
+            }), false);
+            http::response(
+                http::Status::OK,
+                serde_json::json!({
+                    "response": "Comment successfully created",
+                    "comment_id": comment_id
+                }),
+            )
+        }
+        false => http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Failed to create comment"
+            }),
+        ),
+    };
+}
+
+// The delete_announcement_comment() endpoint is used to delete a comment along
+// with all of it's replies. Users can delete their own comments, while the
+// class owner can delete any comment within the class.
+#[actix_web::delete("/class/{class_id}/announcements/{announcement_id}/comments/{comment_id}")]
+async fn delete_announcement_comment(
+    req: HttpRequest,
+    db: web::Data<Database>,
+    events: web::Data<Events>,
+) -> HttpResponse {
+    // Get the class id
+    let class_id: &str = match req.match_info().get("class_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the announcement id
+    let announcement_id: &str = match req.match_info().get("announcement_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the comment id
+    let comment_id: &str = match req.match_info().get("comment_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the bearer and access token from the request headers.
+    let bearer: String = http::header(&req, "authorization");
+    let access_token: String = http::header(&req, "access_token");
+
+    // Verify the provided authorization tokens
+    if !lib::auth::verify(&bearer, &access_token) {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid request"
+            }),
+        );
+    }
+
+    // Delete the comment from the database
+    let staff: bool = db.is_class_owner(&bearer, class_id).await;
+    return match db
+        .delete_announcement_comment(&bearer, class_id, announcement_id, comment_id, staff)
+        .await
+    {
+        true => {
+            // Push the change to the class's event stream
+            events.publish(class_id, "comment_deleted", serde_json::json!({
+                "announcement_id": announcement_id,
+                "comment_id": comment_id
+            }), false);
+            http::response(
+                http::Status::OK,
+                serde_json::json!({
+                    "response": "Comment successfully deleted"
+                }),
+            )
+        }
+        false => http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Failed to delete comment"
+            }),
+        ),
+    };
+}
+
+// The lock_announcement_comments() endpoint is used to lock or unlock the
+// comment thread under an announcement. Students can't comment on a locked
+// thread, although the existing comments are still shown.
+#[actix_web::post("/class/{class_id}/announcements/{announcement_id}/comments/lock")]
+async fn lock_announcement_comments(
+    req: HttpRequest,
+    db: web::Data<Database>,
+    events: web::Data<Events>,
+    body: web::Bytes,
+) -> HttpResponse {
+    // Get the request body
+    let body: serde_json::Value = match http::body(&body) {
+        Ok(body) => body,
+        Err(_) => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request body"
+                }),
+            )
+        }
+    };
+
+    // Get the class id
+    let class_id: &str = match req.match_info().get("class_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the announcement id
+    let announcement_id: &str = match req.match_info().get("announcement_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the bearer and access token from the request headers.
+    let bearer: String = http::header(&req, "authorization");
+    let access_token: String = http::header(&req, "access_token");
+
+    // Verify the provided authorization tokens
+    if !lib::auth::verify(&bearer, &access_token) {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid request"
+            }),
+        );
+    }
+
+    // Lock the announcement's comments
+    return match db
+        .lock_announcement_comments(&bearer, class_id, announcement_id, &body)
+        .await
+    {
+        true => {
+            // Push the change to the class's event stream
+            events.publish(class_id, "comments_locked", serde_json::json!({
+                "announcement_id": announcement_id,
+                "locked": body["locked"]
+            }), false);
+            http::response(
+                http::Status::OK,
+                serde_json::json!({
+                    "response": "Comments successfully updated"
+                }),
+            )
+        }
+        false => http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Failed to update comments"
+            }),
+        ),
+    };
+}
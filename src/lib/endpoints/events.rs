@@ -0,0 +1,146 @@
+use crate::lib::{self, handlers::Database, http, events::Events};
+use actix_web::{web, HttpRequest, HttpResponse};
+use futures::StreamExt;
+
+// The get_class_events() endpoint is used to open a server-sent event stream for
+// the provided class. New announcements, unit changes and submission changes are
+// pushed to the stream so the frontend doesn't have to re-poll the class data.
+// As a browser EventSource can't send headers, a stream token from the
+// create_events_token() endpoint can be provided within the query string instead.
+#[actix_web::get("/class/{class_id}/events")]
+async fn get_class_events(
+    req: HttpRequest,
+    db: web::Data<Database>,
+    events: web::Data<Events>,
+) -> HttpResponse {
+    // Get the class id
+    let class_id: &str = match req.match_info().get("class_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the bearer from the request headers, or from the
+    // stream token if the headers weren't provided.
+    let mut bearer: String = http::header(&req, "authorization");
+    let verified: bool = match bearer.is_empty() {
+        true => match events.redeem_token(class_id, &http::query(&req, "token")) {
+            Some(r) => {
+                bearer = r;
+                true
+            }
+            None => false
+        },
+        false => lib::auth::verify(&bearer, &http::header(&req, "access_token"))
+    };
+
+    // Verify the provided authorization tokens
+    if !verified {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid request"
+            }),
+        );
+    }
+
+    // Only users that can see the class can receive it's events
+    if !db.can_view_class(&bearer, class_id).await {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid request"
+            }),
+        );
+    }
+
+    // Subscribe to the class's events. The first event
+    // lets the client know the stream has been opened.
+    let staff: bool = db.is_class_owner(&bearer, class_id).await;
+    let user_id: String = db.get_user_id_by_bearer(&bearer).await.unwrap_or_default();
+    let connected = web::Bytes::from(format!(
+        "event: connected\ndata: {}\n\n",
+        serde_json::json!({ "class_id": class_id })
+    ));
+    let stream = futures::stream::once(async { connected })
+        .chain(events.subscribe(class_id, &user_id, staff))
+        .map(Ok::<web::Bytes, actix_web::Error>);
+
+    // Return the event stream
+    return HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header(("Cache-Control", "no-cache"))
+        .streaming(stream);
+}
+
+// The create_events_token() endpoint is used to get a short-lived token for
+// opening the provided class's event stream. The token is sent within the
+// query string by clients that can't send headers, such as a browser
+// EventSource, so that the bearer itself is never put in a url. Each token
+// can only be used once, and expires after a minute.
+#[actix_web::post("/class/{class_id}/events/token")]
+async fn create_events_token(
+    req: HttpRequest,
+    db: web::Data<Database>,
+    events: web::Data<Events>,
+) -> HttpResponse {
+    // Get the class id
+    let class_id: &str = match req.match_info().get("class_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the bearer and access token from the request headers.
+    let bearer: String = http::header(&req, "authorization");
+    let access_token: String = http::header(&req, "access_token");
+
+    // Verify the provided authorization tokens
+    if !lib::auth::verify(&bearer, &access_token) {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid request"
+            }),
+        );
+    }
+
+    // Only users that can see the class can receive it's events
+    if !db.can_view_class(&bearer, class_id).await {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid request"
+            }),
+        );
+    }
+
+    // Create the stream token
+    return match events.create_token(class_id, &bearer) {
+        Some(token) => http::response(
+            http::Status::OK,
+            serde_json::json!({
+                "response": "Created stream token",
+                "token": token
+            }),
+        ),
+        None => http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Failed to create stream token"
+            }),
+        ),
+    };
+}
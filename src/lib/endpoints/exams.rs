@@ -0,0 +1,85 @@
+use crate::lib::{self, handlers::Database, http};
+use actix_web::{web, HttpRequest, HttpResponse};
+
+// The start_exam() endpoint is used by students to start a timed lesson.
+// The lesson's time limit counts down from the first time it's started.
+#[actix_web::post("/class/{class_id}/units/{unit_id}/lessons/{lesson_id}/start")]
+async fn start_exam(req: HttpRequest, db: web::Data<Database>) -> HttpResponse {
+    // Get the class id from the request parameters
+    let class_id: &str = match req.match_info().get("class_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the unit id from the request parameters
+    let unit_id: &str = match req.match_info().get("unit_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the lesson id from the request parameters
+    let lesson_id: &str = match req.match_info().get("lesson_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the bearer and access token from the request headers.
+    let bearer: String = http::header(&req, "authorization");
+    let access_token: String = http::header(&req, "access_token");
+
+    // Verify the provided authorization tokens
+    if !lib::auth::verify(&bearer, &access_token) {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid request"
+            }),
+        );
+    }
+
+    // Only users that can see the class can start it's lessons
+    if !db.can_view_class(&bearer, class_id).await {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid request"
+            }),
+        );
+    }
+
+    // Start the lesson's timer
+    let staff: bool = db.is_class_owner(&bearer, class_id).await;
+    return match db.start_exam(&bearer, class_id, unit_id, lesson_id, staff).await {
+        Some(attempt) => http::response(
+            http::Status::OK,
+            serde_json::json!({ "response": attempt }),
+        ),
+        None => http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Failed to start lesson"
+            }),
+        ),
+    };
+}
\ No newline at end of file
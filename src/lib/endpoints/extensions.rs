@@ -0,0 +1,272 @@
+use crate::lib::{self, handlers::Database, http};
+use actix_web::{web, HttpRequest, HttpResponse};
+
+// The get_lesson_extensions() endpoint is used by the unit owner to get
+// every extension that has been given on a lesson.
+#[actix_web::get("/class/{class_id}/units/{unit_id}/lessons/{lesson_id}/extensions")]
+async fn get_lesson_extensions(req: HttpRequest, db: web::Data<Database>) -> HttpResponse {
+    // Get the class id from the request parameters
+    let class_id: &str = match req.match_info().get("class_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the unit id from the request parameters
+    let unit_id: &str = match req.match_info().get("unit_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the lesson id from the request parameters
+    let lesson_id: &str = match req.match_info().get("lesson_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the bearer and access token from the request headers.
+    let bearer: String = http::header(&req, "authorization");
+    let access_token: String = http::header(&req, "access_token");
+
+    // Verify the provided authorization tokens
+    if !lib::auth::verify(&bearer, &access_token) {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid request"
+            }),
+        );
+    }
+
+    // Return the lesson's extensions
+    return match db.get_lesson_extensions(&bearer, class_id, unit_id, lesson_id).await {
+        Some(extensions) => http::response(
+            http::Status::OK,
+            serde_json::json!({ "response": extensions }),
+        ),
+        None => http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Failed to fetch extensions"
+            }),
+        ),
+    };
+}
+
+// The set_lesson_extension() endpoint is used by the unit owner to give a
+// student extra time, or their own due date and closing time, on a lesson.
+// The student's existing extension is replaced.
+#[actix_web::post("/class/{class_id}/units/{unit_id}/lessons/{lesson_id}/extensions/{user_id}")]
+async fn set_lesson_extension(
+    req: HttpRequest,
+    db: web::Data<Database>,
+    body: web::Bytes,
+) -> HttpResponse {
+    // Get the request body
+    let body: serde_json::Value = match http::body(&body) {
+        Ok(body) => body,
+        Err(_) => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request body"
+                }),
+            )
+        }
+    };
+
+    // Get the class id from the request parameters
+    let class_id: &str = match req.match_info().get("class_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the unit id from the request parameters
+    let unit_id: &str = match req.match_info().get("unit_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the lesson id from the request parameters
+    let lesson_id: &str = match req.match_info().get("lesson_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the student's user id from the request parameters
+    let user_id: &str = match req.match_info().get("user_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the bearer and access token from the request headers.
+    let bearer: String = http::header(&req, "authorization");
+    let access_token: String = http::header(&req, "access_token");
+
+    // Verify the provided authorization tokens
+    if !lib::auth::verify(&bearer, &access_token) {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid request"
+            }),
+        );
+    }
+
+    // Set the student's extension in the database
+    return match db
+        .set_lesson_extension(&bearer, class_id, unit_id, lesson_id, user_id, &body)
+        .await
+    {
+        true => http::response(
+            http::Status::OK,
+            serde_json::json!({
+                "response": "Extension successfully set"
+            }),
+        ),
+        false => http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Failed to set extension"
+            }),
+        ),
+    };
+}
+
+// The delete_lesson_extension() endpoint is used by the unit owner
+// to revoke a student's extension on a lesson.
+#[actix_web::delete("/class/{class_id}/units/{unit_id}/lessons/{lesson_id}/extensions/{user_id}")]
+async fn delete_lesson_extension(req: HttpRequest, db: web::Data<Database>) -> HttpResponse {
+    // Get the class id from the request parameters
+    let class_id: &str = match req.match_info().get("class_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the unit id from the request parameters
+    let unit_id: &str = match req.match_info().get("unit_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the lesson id from the request parameters
+    let lesson_id: &str = match req.match_info().get("lesson_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the student's user id from the request parameters
+    let user_id: &str = match req.match_info().get("user_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the bearer and access token from the request headers.
+    let bearer: String = http::header(&req, "authorization");
+    let access_token: String = http::header(&req, "access_token");
+
+    // Verify the provided authorization tokens
+    if !lib::auth::verify(&bearer, &access_token) {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid request"
+            }),
+        );
+    }
+
+    // Delete the student's extension from the database
+    return match db
+        .delete_lesson_extension(&bearer, class_id, unit_id, lesson_id, user_id)
+        .await
+    {
+        true => http::response(
+            http::Status::OK,
+            serde_json::json!({
+                "response": "Extension successfully revoked"
+            }),
+        ),
+        false => http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Failed to revoke extension"
+            }),
+        ),
+    };
+}
\ No newline at end of file
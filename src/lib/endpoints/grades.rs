@@ -0,0 +1,313 @@
+use crate::lib::{self, utils, handlers::Database, http};
+use actix_web::{web, HttpRequest, HttpResponse};
+
+// The get_class_grades() endpoint is used to get the running average of every
+// student within the provided class, along with the class average. This endpoint
+// is used in the website dashboard and only works for the class owner.
+#[actix_web::get("/class/{class_id}/grades")]
+async fn get_class_grades(req: HttpRequest, db: web::Data<Database>) -> HttpResponse {
+    // Get the class id
+    let class_id: &str = match req.match_info().get("class_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the bearer and access token from the request headers.
+    let bearer: String = http::header(&req, "authorization");
+    let access_token: String = http::header(&req, "access_token");
+
+    // Verify the provided authorization tokens
+    if !lib::auth::verify(&bearer, &access_token) {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid request"
+            }),
+        );
+    }
+
+    // Return the class grades
+    return match db.get_class_grades(&bearer, class_id).await {
+        Some(grades) => http::response(
+            http::Status::OK,
+            serde_json::json!({ "response": grades }),
+        ),
+        None => http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Failed to fetch class grades"
+            }),
+        ),
+    };
+}
+
+// The get_user_grades() endpoint is used to get the signed in student's
+// average within each grade category and their weighted running average.
+// This endpoint sits alongside the student's submissions endpoint.
+#[actix_web::get("/class/{class_id}/student/grades")]
+async fn get_user_grades(req: HttpRequest, db: web::Data<Database>) -> HttpResponse {
+    // Get the class id
+    let class_id: &str = match req.match_info().get("class_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the bearer and access token from the request headers.
+    let bearer: String = http::header(&req, "authorization");
+    let access_token: String = http::header(&req, "access_token");
+
+    // Verify the provided authorization tokens
+    if !lib::auth::verify(&bearer, &access_token) {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid request"
+            }),
+        );
+    }
+
+    // Return the student's grades
+    return match db.get_user_grades(class_id, &bearer).await {
+        Some(grades) => http::response(
+            http::Status::OK,
+            serde_json::json!({ "response": grades }),
+        ),
+        None => http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Failed to fetch student grades"
+            }),
+        ),
+    };
+}
+
+// The insert_grade_category() endpoint is used to create a new weighted
+// grade category for the provided class. (ex: homework 30%, tests 70%)
+#[actix_web::put("/class/{class_id}/grades/categories")]
+async fn insert_grade_category(
+    req: HttpRequest,
+    db: web::Data<Database>,
+    body: web::Bytes,
+) -> HttpResponse {
+    // Get the request body
+    let body: serde_json::Value = match http::body(&body) {
+        Ok(body) => body,
+        Err(_) => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request body"
+                }),
+            )
+        }
+    };
+
+    // Get the class id
+    let class_id: &str = match req.match_info().get("class_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the bearer and access token from the request headers.
+    let bearer: String = http::header(&req, "authorization");
+    let access_token: String = http::header(&req, "access_token");
+
+    // Verify the provided authorization tokens
+    if !lib::auth::verify(&bearer, &access_token) {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid request"
+            }),
+        );
+    }
+
+    // Generate a new category id
+    let category_id: String = utils::generate_new_id(class_id);
+
+    // Insert the category into the database
+    return match db
+        .insert_grade_category(&bearer, class_id, &category_id, &body)
+        .await
+    {
+        true => http::response(
+            http::Status::OK,
+            serde_json::json!({
+                "response": "Grade category created",
+                "category_id": category_id
+            }),
+        ),
+        false => http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Failed to create grade category"
+            }),
+        ),
+    };
+}
+
+// The update_grade_category() endpoint is used to modify the
+// name, weight or drop_lowest rule of a grade category.
+#[actix_web::post("/class/{class_id}/grades/categories/{category_id}")]
+async fn update_grade_category(
+    req: HttpRequest,
+    db: web::Data<Database>,
+    body: web::Bytes,
+) -> HttpResponse {
+    // Get the request body
+    let body: serde_json::Value = match http::body(&body) {
+        Ok(body) => body,
+        Err(_) => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request body"
+                }),
+            )
+        }
+    };
+
+    // Get the class id
+    let class_id: &str = match req.match_info().get("class_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the category id
+    let category_id: &str = match req.match_info().get("category_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the bearer and access token from the request headers.
+    let bearer: String = http::header(&req, "authorization");
+    let access_token: String = http::header(&req, "access_token");
+
+    // Verify the provided authorization tokens
+    if !lib::auth::verify(&bearer, &access_token) {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid request"
+            }),
+        );
+    }
+
+    // Update the category in the database
+    return match db
+        .update_grade_category(&bearer, class_id, category_id, &body)
+        .await
+    {
+        true => http::response(
+            http::Status::OK,
+            serde_json::json!({
+                "response": "Updated grade category"
+            }),
+        ),
+        false => http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Failed to update grade category"
+            }),
+        ),
+    };
+}
+
+// The delete_grade_category() endpoint is used to delete
+// a grade category from the provided class.
+#[actix_web::delete("/class/{class_id}/grades/categories/{category_id}")]
+async fn delete_grade_category(req: HttpRequest, db: web::Data<Database>) -> HttpResponse {
+    // Get the class id
+    let class_id: &str = match req.match_info().get("class_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the category id
+    let category_id: &str = match req.match_info().get("category_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the bearer and access token from the request headers.
+    let bearer: String = http::header(&req, "authorization");
+    let access_token: String = http::header(&req, "access_token");
+
+    // Verify the provided authorization tokens
+    if !lib::auth::verify(&bearer, &access_token) {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid request"
+            }),
+        );
+    }
+
+    // Delete the category from the database
+    return match db
+        .delete_grade_category(&bearer, class_id, category_id)
+        .await
+    {
+        true => http::response(
+            http::Status::OK,
+            serde_json::json!({
+                "response": "Grade category deleted"
+            }),
+        ),
+        false => http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Failed to delete grade category"
+            }),
+        ),
+    };
+}
@@ -0,0 +1,498 @@
+use crate::lib::{self, utils, handlers::Database, http, quotas::{self, Quotas}};
+use actix_web::{web, HttpRequest, HttpResponse};
+
+// The insert_unit_lesson() endpoint is used to create a new lesson within the
+// provided unit. Using the provided unit_id the function will generate a unique
+// lesson identifier using the following format: SHA256(unit_id:current_time)
+#[actix_web::put("/class/{class_id}/units/{unit_id}/lessons")]
+async fn insert_unit_lesson(
+    req: HttpRequest,
+    db: web::Data<Database>,
+    quotas: web::Data<Quotas>,
+    body: web::Bytes,
+) -> HttpResponse {
+    // Get the request body
+    let body: serde_json::Value = match http::body(&body) {
+        Ok(body) => body,
+        Err(_) => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request body"
+                }),
+            )
+        }
+    };
+
+    // Get the class id from the request parameters
+    let class_id: &str = match req.match_info().get("class_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the unit id from the request parameters
+    let unit_id: &str = match req.match_info().get("unit_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the bearer and access token from the request headers.
+    let bearer: String = http::header(&req, "authorization");
+    let access_token: String = http::header(&req, "access_token");
+
+    // Verify the provided authorization tokens
+    if !lib::auth::verify(&bearer, &access_token) {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid request"
+            }),
+        );
+    }
+
+    // Only the unit owner can add lessons
+    if !db.is_unit_owner(&bearer, class_id, unit_id).await {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid request"
+            }),
+        );
+    }
+
+    // Make sure the unit hasn't reached it's lesson quota. The
+    // quotas stay locked until the lesson has been inserted.
+    let _quota = quotas.lock(class_id).await;
+    if let Err(r) = quotas::check("lessons_per_unit", quotas.lessons_per_unit, db.count_unit_lessons(unit_id).await, 1) {
+        return *r;
+    }
+
+    // Make sure the lesson fits within the class's storage quota
+    if let Err(r) = quotas::check("storage_per_class", quotas.storage_per_class, db.get_class_storage(class_id).await, body.to_string().len() as i64) {
+        return *r;
+    }
+
+    // Generate a new lesson id
+    let lesson_id: String = utils::generate_new_id(unit_id);
+
+    // Insert the lesson into the database
+    return match db
+        .insert_unit_lesson(&bearer, class_id, unit_id, &lesson_id, &body)
+        .await
+    {
+        true => http::response(
+            http::Status::OK,
+            serde_json::json!({
+                "response": "Lesson created",
+                "lesson_id": lesson_id
+            }),
+        ),
+        false => http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Failed to create lesson"
+            }),
+        ),
+    };
+}
+
+// The update_unit_lesson() endpoint is used to
+// modify any data within the lesson's database row.
+#[actix_web::post("/class/{class_id}/units/{unit_id}/lessons/{lesson_id}")]
+async fn update_unit_lesson(
+    req: HttpRequest,
+    db: web::Data<Database>,
+    quotas: web::Data<Quotas>,
+    body: web::Bytes,
+) -> HttpResponse {
+    // Get the request body
+    let body: serde_json::Value = match http::body(&body) {
+        Ok(body) => body,
+        Err(_) => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request body"
+                }),
+            )
+        }
+    };
+
+    // Get the class id from the request parameters
+    let class_id: &str = match req.match_info().get("class_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the unit id from the request parameters
+    let unit_id: &str = match req.match_info().get("unit_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the lesson id from the request parameters
+    let lesson_id: &str = match req.match_info().get("lesson_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the bearer and access token from the request headers.
+    let bearer: String = http::header(&req, "authorization");
+    let access_token: String = http::header(&req, "access_token");
+
+    // Verify the provided authorization tokens
+    if !lib::auth::verify(&bearer, &access_token) {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid request"
+            }),
+        );
+    }
+
+    // Only the unit owner can modify lessons
+    if !db.is_unit_owner(&bearer, class_id, unit_id).await {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid request"
+            }),
+        );
+    }
+
+    // Make sure the lesson fits within the class's storage quota. Only
+    // the change in the lesson's size is counted. The quota stays
+    // locked until the lesson has been updated.
+    let _quota = quotas.lock(class_id).await;
+    let growth: i64 = db.get_lesson(unit_id, lesson_id).await.map_or(0, |lesson| quotas::lesson_growth(&lesson, &body));
+    if let Err(r) = quotas::check("storage_per_class", quotas.storage_per_class, db.get_class_storage(class_id).await, growth) {
+        return *r;
+    }
+
+    // Update the lesson data in the database
+    return match db
+        .update_unit_lesson(&bearer, class_id, unit_id, lesson_id, &body)
+        .await
+    {
+        true => http::response(
+            http::Status::OK,
+            serde_json::json!({
+                "response": "Updated lesson"
+            }),
+        ),
+        false => http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Failed to update lesson"
+            }),
+        ),
+    };
+}
+
+// The delete_unit_lesson() endpoint is used to
+// delete the provided lesson from the database.
+#[actix_web::delete("/class/{class_id}/units/{unit_id}/lessons/{lesson_id}")]
+async fn delete_unit_lesson(req: HttpRequest, db: web::Data<Database>) -> HttpResponse {
+    // Get the class id from the request parameters
+    let class_id: &str = match req.match_info().get("class_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the unit id from the request parameters
+    let unit_id: &str = match req.match_info().get("unit_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the lesson id from the request parameters
+    let lesson_id: &str = match req.match_info().get("lesson_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the bearer and access token from the request headers.
+    let bearer: String = http::header(&req, "authorization");
+    let access_token: String = http::header(&req, "access_token");
+
+    // Verify the provided authorization tokens
+    if !lib::auth::verify(&bearer, &access_token) {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid request"
+            }),
+        );
+    }
+
+    // Delete the lesson from the database
+    return match db
+        .delete_unit_lesson(&bearer, class_id, unit_id, lesson_id)
+        .await
+    {
+        true => http::response(
+            http::Status::OK,
+            serde_json::json!({
+                "response": "Lesson deleted"
+            }),
+        ),
+        false => http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Failed to delete lesson"
+            }),
+        ),
+    };
+}
+
+// The reorder_unit_lessons() endpoint is used to change the order of the
+// unit's lessons. The request body contains every lesson id in their new order.
+#[actix_web::post("/class/{class_id}/units/{unit_id}/lessons/order")]
+async fn reorder_unit_lessons(
+    req: HttpRequest,
+    db: web::Data<Database>,
+    body: web::Bytes,
+) -> HttpResponse {
+    // Get the request body
+    let body: serde_json::Value = match http::body(&body) {
+        Ok(body) => body,
+        Err(_) => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request body"
+                }),
+            )
+        }
+    };
+
+    // Get the class id from the request parameters
+    let class_id: &str = match req.match_info().get("class_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the unit id from the request parameters
+    let unit_id: &str = match req.match_info().get("unit_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the bearer and access token from the request headers.
+    let bearer: String = http::header(&req, "authorization");
+    let access_token: String = http::header(&req, "access_token");
+
+    // Verify the provided authorization tokens
+    if !lib::auth::verify(&bearer, &access_token) {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid request"
+            }),
+        );
+    }
+
+    // Reorder the lessons in the database
+    return match db
+        .reorder_unit_lessons(&bearer, class_id, unit_id, &body)
+        .await
+    {
+        true => http::response(
+            http::Status::OK,
+            serde_json::json!({
+                "response": "Reordered lessons"
+            }),
+        ),
+        false => http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Failed to reorder lessons"
+            }),
+        ),
+    };
+}
+
+// The move_unit_lesson() endpoint is used to move a lesson into
+// another unit within the same class, optionally at a set position.
+#[actix_web::post("/class/{class_id}/units/{unit_id}/lessons/{lesson_id}/move")]
+async fn move_unit_lesson(
+    req: HttpRequest,
+    db: web::Data<Database>,
+    quotas: web::Data<Quotas>,
+    body: web::Bytes,
+) -> HttpResponse {
+    // Get the request body
+    let body: serde_json::Value = match http::body(&body) {
+        Ok(body) => body,
+        Err(_) => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request body"
+                }),
+            )
+        }
+    };
+
+    // Get the class id from the request parameters
+    let class_id: &str = match req.match_info().get("class_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the unit id from the request parameters
+    let unit_id: &str = match req.match_info().get("unit_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the lesson id from the request parameters
+    let lesson_id: &str = match req.match_info().get("lesson_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the bearer and access token from the request headers.
+    let bearer: String = http::header(&req, "authorization");
+    let access_token: String = http::header(&req, "access_token");
+
+    // Verify the provided authorization tokens
+    if !lib::auth::verify(&bearer, &access_token) {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid request"
+            }),
+        );
+    }
+
+    // Only the owner of both units can move the lesson
+    let target_id: &str = body["unit_id"].as_str().unwrap_or(unit_id);
+    if !db.is_unit_owner(&bearer, class_id, unit_id).await || !db.is_unit_owner(&bearer, class_id, target_id).await {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid request"
+            }),
+        );
+    }
+
+    // Make sure the unit the lesson is moved into hasn't reached it's lesson
+    // quota. The quota stays locked until the lesson has been moved.
+    let _quota = quotas.lock(class_id).await;
+    if target_id != unit_id {
+        if let Err(r) = quotas::check("lessons_per_unit", quotas.lessons_per_unit, db.count_unit_lessons(target_id).await, 1) {
+            return *r;
+        }
+    }
+
+    // Move the lesson in the database
+    return match db
+        .move_unit_lesson(&bearer, class_id, unit_id, lesson_id, &body)
+        .await
+    {
+        true => http::response(
+            http::Status::OK,
+            serde_json::json!({
+                "response": "Moved lesson"
+            }),
+        ),
+        false => http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Failed to move lesson"
+            }),
+        ),
+    };
+}
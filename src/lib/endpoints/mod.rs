@@ -3,4 +3,14 @@ pub mod users;
 pub mod whitelist;
 pub mod submissions;
 pub mod units;
-pub mod announcements;
\ No newline at end of file
+pub mod announcements;
+pub mod grades;
+pub mod lessons;
+pub mod notifications;
+pub mod events;
+pub mod comments;
+pub mod progress;
+pub mod questions;
+pub mod exams;
+pub mod extensions;
+pub mod bundles;
\ No newline at end of file
@@ -0,0 +1,136 @@
+use crate::lib::{self, handlers::Database, http};
+use actix_web::{web, HttpRequest, HttpResponse};
+
+// The get_user_notifications() endpoint is used to get every notification within
+// the signed in user's inbox. Notifications are created when an announcement is
+// posted, a unit is unlocked or the user's work is marked.
+#[actix_web::get("/users/{user_id}/notifications")]
+async fn get_user_notifications(req: HttpRequest, db: web::Data<Database>) -> HttpResponse {
+    // Get the user id from the url parameters
+    let user_id: &str = match req.match_info().get("user_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the bearer and access token from the request headers.
+    let bearer: String = http::header(&req, "authorization");
+    let access_token: String = http::header(&req, "access_token");
+
+    // Verify the provided authorization tokens
+    if !lib::auth::verify(&bearer, &access_token) {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid request"
+            }),
+        );
+    }
+
+    // Users can only see their own notifications
+    if db.get_user_id_by_bearer(&bearer).await.as_deref() != Some(user_id) {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid request"
+            }),
+        );
+    }
+
+    // Return the users notifications
+    return match db.get_user_notifications(&bearer).await {
+        Some(notifications) => http::response(
+            http::Status::OK,
+            serde_json::json!({ "response": notifications }),
+        ),
+        None => http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Failed to fetch notifications"
+            }),
+        ),
+    };
+}
+
+// The read_user_notifications() endpoint is used to mark the provided
+// notifications within the signed in user's inbox as read. If the request
+// body doesn't contain any notification ids, every notification is marked read.
+#[actix_web::post("/users/{user_id}/notifications/read")]
+async fn read_user_notifications(
+    req: HttpRequest,
+    db: web::Data<Database>,
+    body: web::Bytes,
+) -> HttpResponse {
+    // Get the request body. An empty body marks every notification read.
+    let body: serde_json::Value = match http::body(&body) {
+        Ok(body) => body,
+        Err(_) if body.is_empty() => serde_json::json!({}),
+        Err(_) => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request body"
+                }),
+            )
+        }
+    };
+
+    // Get the user id from the url parameters
+    let user_id: &str = match req.match_info().get("user_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the bearer and access token from the request headers.
+    let bearer: String = http::header(&req, "authorization");
+    let access_token: String = http::header(&req, "access_token");
+
+    // Verify the provided authorization tokens
+    if !lib::auth::verify(&bearer, &access_token) {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid request"
+            }),
+        );
+    }
+
+    // Users can only read their own notifications
+    if db.get_user_id_by_bearer(&bearer).await.as_deref() != Some(user_id) {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid request"
+            }),
+        );
+    }
+
+    // Mark the notifications as read
+    return match db.read_user_notifications(&bearer, &body).await {
+        true => http::response(
+            http::Status::OK,
+            serde_json::json!({
+                "response": "Notifications marked as read"
+            }),
+        ),
+        false => http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Failed to mark notifications as read"
+            }),
+        ),
+    };
+}
@@ -0,0 +1,153 @@
+use crate::lib::{self, handlers::Database, http};
+use actix_web::{web, HttpRequest, HttpResponse};
+
+// The update_lesson_progress() endpoint is used by students to mark a lesson
+// complete, or to record how much of the lesson's video they've watched.
+#[actix_web::post("/class/{class_id}/units/{unit_id}/lessons/{lesson_id}/progress")]
+async fn update_lesson_progress(
+    req: HttpRequest,
+    db: web::Data<Database>,
+    body: web::Bytes,
+) -> HttpResponse {
+    // Get the request body
+    let body: serde_json::Value = match http::body(&body) {
+        Ok(body) => body,
+        Err(_) => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request body"
+                }),
+            )
+        }
+    };
+
+    // Get the class id from the request parameters
+    let class_id: &str = match req.match_info().get("class_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the unit id from the request parameters
+    let unit_id: &str = match req.match_info().get("unit_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the lesson id from the request parameters
+    let lesson_id: &str = match req.match_info().get("lesson_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the bearer and access token from the request headers.
+    let bearer: String = http::header(&req, "authorization");
+    let access_token: String = http::header(&req, "access_token");
+
+    // Verify the provided authorization tokens
+    if !lib::auth::verify(&bearer, &access_token) {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid request"
+            }),
+        );
+    }
+
+    // Only users that can see the class can make progress in it
+    if !db.can_view_class(&bearer, class_id).await {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid request"
+            }),
+        );
+    }
+
+    // Update the lesson progress in the database
+    let staff: bool = db.is_class_owner(&bearer, class_id).await;
+    return match db
+        .update_lesson_progress(&bearer, class_id, unit_id, lesson_id, staff, &body)
+        .await
+    {
+        true => http::response(
+            http::Status::OK,
+            serde_json::json!({
+                "response": "Updated lesson progress"
+            }),
+        ),
+        false => http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Failed to update lesson progress"
+            }),
+        ),
+    };
+}
+
+// The get_class_progress() endpoint is used by the class owner to see
+// the progress of every student through every lesson within the class.
+#[actix_web::get("/class/{class_id}/progress")]
+async fn get_class_progress(req: HttpRequest, db: web::Data<Database>) -> HttpResponse {
+    // Get the class id from the request parameters
+    let class_id: &str = match req.match_info().get("class_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the bearer and access token from the request headers.
+    let bearer: String = http::header(&req, "authorization");
+    let access_token: String = http::header(&req, "access_token");
+
+    // Verify the provided authorization tokens
+    if !lib::auth::verify(&bearer, &access_token) {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid request"
+            }),
+        );
+    }
+
+    // Return the class's progress matrix
+    return match db.get_class_progress(&bearer, class_id).await {
+        Some(progress) => http::response(
+            http::Status::OK,
+            serde_json::json!({ "response": progress }),
+        ),
+        None => http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Failed to fetch class progress"
+            }),
+        ),
+    };
+}
@@ -0,0 +1,390 @@
+use crate::lib::{self, utils, handlers::Database, http, structs::QuizDraw, quotas::{self, Quotas}};
+use actix_web::{web, HttpRequest, HttpResponse};
+
+// The get_class_questions() endpoint is used by the class owner to get
+// every question within the class's question bank, along with their answers.
+#[actix_web::get("/class/{class_id}/questions")]
+async fn get_class_questions(req: HttpRequest, db: web::Data<Database>) -> HttpResponse {
+    // Get the class id from the request parameters
+    let class_id: &str = match req.match_info().get("class_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the bearer and access token from the request headers.
+    let bearer: String = http::header(&req, "authorization");
+    let access_token: String = http::header(&req, "access_token");
+
+    // Verify the provided authorization tokens
+    if !lib::auth::verify(&bearer, &access_token) {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid request"
+            }),
+        );
+    }
+
+    // Return the question bank
+    return match db.get_class_questions(&bearer, class_id).await {
+        Some(questions) => http::response(
+            http::Status::OK,
+            serde_json::json!({ "response": questions }),
+        ),
+        None => http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Failed to fetch question bank"
+            }),
+        ),
+    };
+}
+
+// The insert_class_question() endpoint is used by the class owner to add a
+// question to the class's question bank. The question is tagged with a unit
+// so that quizzes can draw questions from it.
+#[actix_web::put("/class/{class_id}/questions")]
+async fn insert_class_question(
+    req: HttpRequest,
+    db: web::Data<Database>,
+    quotas: web::Data<Quotas>,
+    body: web::Bytes,
+) -> HttpResponse {
+    // Get the request body
+    let body: serde_json::Value = match http::body(&body) {
+        Ok(body) => body,
+        Err(_) => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request body"
+                }),
+            )
+        }
+    };
+
+    // Get the class id from the request parameters
+    let class_id: &str = match req.match_info().get("class_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the bearer and access token from the request headers.
+    let bearer: String = http::header(&req, "authorization");
+    let access_token: String = http::header(&req, "access_token");
+
+    // Verify the provided authorization tokens
+    if !lib::auth::verify(&bearer, &access_token) {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid request"
+            }),
+        );
+    }
+
+    // Only the class owner can add questions
+    if !db.is_class_owner(&bearer, class_id).await {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid request"
+            }),
+        );
+    }
+
+    // Make sure the question fits within the class's storage quota.
+    // The quota stays locked until the question has been inserted.
+    let _quota = quotas.lock(class_id).await;
+    if let Err(r) = quotas::check("storage_per_class", quotas.storage_per_class, db.get_class_storage(class_id).await, body.to_string().len() as i64) {
+        return *r;
+    }
+
+    // Generate a new question id
+    let question_id: String = utils::generate_new_id(class_id);
+
+    // Insert the question into the database
+    return match db
+        .insert_class_question(&bearer, class_id, &question_id, &body)
+        .await
+    {
+        true => http::response(
+            http::Status::OK,
+            serde_json::json!({
+                "response": "Question successfully created",
+                "question_id": question_id
+            }),
+        ),
+        false => http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Failed to create question"
+            }),
+        ),
+    };
+}
+
+// The update_class_question() endpoint is used by the class owner to
+// replace a question within the class's question bank.
+#[actix_web::post("/class/{class_id}/questions/{question_id}")]
+async fn update_class_question(
+    req: HttpRequest,
+    db: web::Data<Database>,
+    body: web::Bytes,
+) -> HttpResponse {
+    // Get the request body
+    let body: serde_json::Value = match http::body(&body) {
+        Ok(body) => body,
+        Err(_) => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request body"
+                }),
+            )
+        }
+    };
+
+    // Get the class id from the request parameters
+    let class_id: &str = match req.match_info().get("class_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the question id from the request parameters
+    let question_id: &str = match req.match_info().get("question_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the bearer and access token from the request headers.
+    let bearer: String = http::header(&req, "authorization");
+    let access_token: String = http::header(&req, "access_token");
+
+    // Verify the provided authorization tokens
+    if !lib::auth::verify(&bearer, &access_token) {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid request"
+            }),
+        );
+    }
+
+    // Update the question in the database
+    return match db
+        .update_class_question(&bearer, class_id, question_id, &body)
+        .await
+    {
+        true => http::response(
+            http::Status::OK,
+            serde_json::json!({
+                "response": "Question successfully updated"
+            }),
+        ),
+        false => http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Failed to update question"
+            }),
+        ),
+    };
+}
+
+// The delete_class_question() endpoint is used by the class owner to
+// delete a question from the class's question bank.
+#[actix_web::delete("/class/{class_id}/questions/{question_id}")]
+async fn delete_class_question(req: HttpRequest, db: web::Data<Database>) -> HttpResponse {
+    // Get the class id from the request parameters
+    let class_id: &str = match req.match_info().get("class_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the question id from the request parameters
+    let question_id: &str = match req.match_info().get("question_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the bearer and access token from the request headers.
+    let bearer: String = http::header(&req, "authorization");
+    let access_token: String = http::header(&req, "access_token");
+
+    // Verify the provided authorization tokens
+    if !lib::auth::verify(&bearer, &access_token) {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid request"
+            }),
+        );
+    }
+
+    // Delete the question from the database
+    return match db
+        .delete_class_question(&bearer, class_id, question_id)
+        .await
+    {
+        true => http::response(
+            http::Status::OK,
+            serde_json::json!({
+                "response": "Question successfully deleted"
+            }),
+        ),
+        false => http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Failed to delete question"
+            }),
+        ),
+    };
+}
+
+// The get_quiz_draw() endpoint is used to get the questions the user was
+// given for a bank quiz. The class owner can provide a user_id within the
+// query to review the questions and answer key a student was given.
+#[actix_web::get("/class/{class_id}/units/{unit_id}/lessons/{lesson_id}/quiz")]
+async fn get_quiz_draw(req: HttpRequest, db: web::Data<Database>) -> HttpResponse {
+    // Get the class id from the request parameters
+    let class_id: &str = match req.match_info().get("class_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the unit id from the request parameters
+    let unit_id: &str = match req.match_info().get("unit_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the lesson id from the request parameters
+    let lesson_id: &str = match req.match_info().get("lesson_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the bearer and access token from the request headers.
+    let bearer: String = http::header(&req, "authorization");
+    let access_token: String = http::header(&req, "access_token");
+
+    // Verify the provided authorization tokens
+    if !lib::auth::verify(&bearer, &access_token) {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid request"
+            }),
+        );
+    }
+
+    // Only users that can see the class can take it's quizzes
+    if !db.can_view_class(&bearer, class_id).await {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid request"
+            }),
+        );
+    }
+
+    // If a user id was provided, get that student's draw
+    let user_id: String = http::query(&req, "user_id");
+    let draw: Option<QuizDraw> = match user_id.is_empty() {
+        true => {
+            let staff: bool = db.is_class_owner(&bearer, class_id).await;
+            db.get_quiz_draw(&bearer, class_id, unit_id, lesson_id, staff).await
+        }
+        false => db.get_student_quiz_draw(&bearer, class_id, lesson_id, &user_id).await,
+    };
+
+    // Return the drawn questions. The answer key is only
+    // returned when reviewing a student's draw.
+    return match draw {
+        Some(draw) => {
+            let questions: serde_json::Value = serde_json::from_str(&draw.questions).unwrap_or_default();
+            let answers: serde_json::Value = match user_id.is_empty() {
+                true => serde_json::Value::Null,
+                false => serde_json::from_str(&draw.answers).unwrap_or_default(),
+            };
+            http::response(
+                http::Status::OK,
+                serde_json::json!({
+                    "response": {
+                        "questions": questions,
+                        "answers": answers
+                    }
+                }),
+            )
+        }
+        None => http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Failed to fetch quiz"
+            }),
+        ),
+    };
+}
\ No newline at end of file
@@ -1,4 +1,4 @@
-use crate::lib::{self, utils, handlers::Database, http};
+use crate::lib::{self, utils, quiz, handlers::Database, http, mail::Mailer, events::Events, quotas::{self, Quotas}};
 use actix_web::{web, HttpRequest, HttpResponse};
 
 // The get_class_submissions() endpoint is used to get all the work 
@@ -35,7 +35,7 @@ async fn get_class_submissions(req: HttpRequest, db: web::Data<Database>) -> Htt
     }
 
     // Return the class submissions
-    return match db.get_class_submissions(&class_id).await {
+    return match db.get_class_submissions(class_id).await {
         Some(submissions) => http::response(
             http::Status::OK,
             serde_json::json!({ "response": submissions }),
@@ -82,7 +82,7 @@ async fn get_user_submissions(req: HttpRequest, db: web::Data<Database>) -> Http
     }
 
     // Return the user submissions from the database
-    return match db.get_user_submissions(&class_id, &bearer).await {
+    return match db.get_user_submissions(class_id, &bearer).await {
         Some(submissions) => http::response(
             http::Status::OK,
             serde_json::json!({ "response": submissions }),
@@ -102,6 +102,8 @@ async fn get_user_submissions(req: HttpRequest, db: web::Data<Database>) -> Http
 async fn insert_class_submission(
     req: HttpRequest,
     db: web::Data<Database>,
+    quotas: web::Data<Quotas>,
+    events: web::Data<Events>,
     body: web::Bytes,
 ) -> HttpResponse {
     // Get the request body
@@ -157,18 +159,42 @@ async fn insert_class_submission(
         );
     }
 
+    // Only users that can see the class can submit work
+    if !db.can_view_class(&bearer, class_id).await {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid request"
+            }),
+        );
+    }
+
+    // Make sure the submission fits within the class's storage quota.
+    // The quota stays locked until the submission has been inserted.
+    let _quota = quotas.lock(class_id).await;
+    if let Err(r) = quotas::check("storage_per_class", quotas.storage_per_class, db.get_class_storage(class_id).await, data.len() as i64) {
+        return *r;
+    }
+
     // Generate a new submission id
     let submission_id: String = utils::generate_new_id(&bearer);
 
+    // Get the lesson the work is being submitted for
+    let lesson_id: &str = body["lesson_id"].as_str().unwrap_or("");
+
     // Insert the submission data into the database
     return match db
-        .insert_class_submission(&class_id, &submission_id, &bearer, &data)
+        .insert_class_submission(class_id, &submission_id, &bearer, lesson_id, &data)
         .await
     {
-        true => http::response(
-            http::Status::OK,
-            serde_json::json!({ "response": submission_id }),
-        ),
+        true => {
+            // Push the submission to the class owner's event stream
+            events.publish(class_id, "submission_created", serde_json::json!({ "submission_id": submission_id }), true);
+            http::response(
+                http::Status::OK,
+                serde_json::json!({ "response": submission_id }),
+            )
+        }
         false => http::response(
             http::Status::BAD_REQUEST,
             serde_json::json!({
@@ -182,7 +208,11 @@ async fn insert_class_submission(
 // the database. This endpoint is called when the signed in student wants to undo
 // their work submission.
 #[actix_web::delete("/class/{class_id}/submissions/{submission_id}")]
-async fn delete_class_submission(req: HttpRequest, db: web::Data<Database>) -> HttpResponse {
+async fn delete_class_submission(
+    req: HttpRequest,
+    db: web::Data<Database>,
+    events: web::Data<Events>,
+) -> HttpResponse {
     // Get the class id
     let class_id: &str = match req.match_info().get("class_id") {
         Some(id) => id,
@@ -225,19 +255,249 @@ async fn delete_class_submission(req: HttpRequest, db: web::Data<Database>) -> H
 
     // Delete the submission data from the database
     return match db
-        .delete_class_submission(&bearer, &class_id, &submission_id)
+        .delete_class_submission(&bearer, class_id, submission_id)
         .await
     {
-        true => http::response(
-            http::Status::OK,
+        true => {
+            // Push the deletion to the class owner's event stream
+            events.publish(class_id, "submission_deleted", serde_json::json!({ "submission_id": submission_id }), true);
+            http::response(
+                http::Status::OK,
+                serde_json::json!({
+                    "response": "Submission deleted"
+                }),
+            )
+        }
+        false => http::response(
+            http::Status::BAD_REQUEST,
             serde_json::json!({
-                "response": "Submission deleted"
+                "response": "Failed to delete submission"
             }),
         ),
+    };
+}
+
+// The grade_class_submission() endpoint is used by the class owner to mark a
+// student's submission. The body contains the mark, what it's out of and the
+// grade category the submission counts towards.
+#[actix_web::post("/class/{class_id}/submissions/{submission_id}")]
+async fn grade_class_submission(
+    req: HttpRequest,
+    db: web::Data<Database>,
+    events: web::Data<Events>,
+    mailer: web::Data<Mailer>,
+    body: web::Bytes,
+) -> HttpResponse {
+    // Get the request body
+    let body: serde_json::Value = match http::body(&body) {
+        Ok(body) => body,
+        Err(_) => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request body"
+                }),
+            )
+        }
+    };
+
+    // Get the class id
+    let class_id: &str = match req.match_info().get("class_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the submission id
+    let submission_id: &str = match req.match_info().get("submission_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the bearer and access token from the request headers.
+    let bearer: String = http::header(&req, "authorization");
+    let access_token: String = http::header(&req, "access_token");
+
+    // Verify the provided authorization tokens
+    if !lib::auth::verify(&bearer, &access_token) {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid request"
+            }),
+        );
+    }
+
+    // Mark the submission in the database
+    return match db
+        .grade_class_submission(&bearer, class_id, submission_id, &body)
+        .await
+    {
+        true => {
+            // Push the mark to the class owner's event stream
+            events.publish(class_id, "submission_graded", serde_json::json!({ "submission_id": submission_id }), true);
+
+            // Notify the student in the background
+            let (class_id, submission_id) = (class_id.to_string(), submission_id.to_string());
+            actix_web::rt::spawn(async move {
+                if let Some(student) = db.get_submission_recipient(&class_id, &submission_id).await {
+                    db.insert_user_notification(
+                        &student.user_id, &class_id, "graded", "Your work has been marked", ""
+                    ).await;
+                }
+                mailer.notify_graded(&db, &class_id, &submission_id).await;
+            });
+            http::response(
+                http::Status::OK,
+                serde_json::json!({
+                    "response": "Submission graded"
+                }),
+            )
+        }
         false => http::response(
             http::Status::BAD_REQUEST,
             serde_json::json!({
-                "response": "Failed to delete submission"
+                "response": "Failed to grade submission"
+            }),
+        ),
+    };
+}
+
+// The submit_lesson_quiz() endpoint is used by students to submit their answers
+// to a quiz lesson. The answers are marked automatically and the student's mark
+// is returned, along with which of their answers were correct once the quiz's
+// solutions have been released to them.
+#[actix_web::post("/class/{class_id}/units/{unit_id}/lessons/{lesson_id}/quiz")]
+async fn submit_lesson_quiz(
+    req: HttpRequest,
+    db: web::Data<Database>,
+    events: web::Data<Events>,
+    body: web::Bytes,
+) -> HttpResponse {
+    // Get the request body
+    let body: serde_json::Value = match http::body(&body) {
+        Ok(body) => body,
+        Err(_) => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request body"
+                }),
+            )
+        }
+    };
+
+    // Get the class id
+    let class_id: &str = match req.match_info().get("class_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the unit id
+    let unit_id: &str = match req.match_info().get("unit_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the lesson id
+    let lesson_id: &str = match req.match_info().get("lesson_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the bearer and access token from the request headers.
+    let bearer: String = http::header(&req, "authorization");
+    let access_token: String = http::header(&req, "access_token");
+
+    // Verify the provided authorization tokens
+    if !lib::auth::verify(&bearer, &access_token) {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid request"
+            }),
+        );
+    }
+
+    // Only users that can see the class can submit quizzes
+    if !db.can_view_class(&bearer, class_id).await {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid request"
+            }),
+        );
+    }
+
+    // Generate a new submission id
+    let submission_id: String = utils::generate_new_id(&bearer);
+
+    // Mark the quiz and insert the submission into the database
+    let submission: quiz::QuizSubmission = quiz::QuizSubmission {
+        bearer: &bearer,
+        class_id,
+        unit_id,
+        lesson_id,
+        submission_id: &submission_id,
+        staff: db.is_class_owner(&bearer, class_id).await,
+    };
+    return match db
+        .submit_lesson_quiz(submission, &body)
+        .await
+    {
+        Some(result) => {
+            // Push the submission to the class owner's event stream
+            events.publish(class_id, "submission_created", serde_json::json!({ "submission_id": submission_id }), true);
+            http::response(
+                http::Status::OK,
+                serde_json::json!({
+                    "response": {
+                        "submission_id": submission_id,
+                        "mark": result.mark,
+                        "out_of": result.out_of,
+                        "results": result.results
+                    }
+                }),
+            )
+        }
+        None => http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Failed to submit quiz"
             }),
         ),
     };
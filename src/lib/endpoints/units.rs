@@ -1,4 +1,4 @@
-use crate::lib::{self, utils, handlers::Database, http};
+use crate::lib::{self, utils, handlers::Database, http, events::Events, database::units, quotas::{self, Quotas}};
 use actix_web::{web, HttpRequest, HttpResponse};
 
 // The insert_class_unit() endpoint is used to create a new unit for the 
@@ -8,6 +8,8 @@ use actix_web::{web, HttpRequest, HttpResponse};
 async fn insert_class_unit(
     req: HttpRequest,
     db: web::Data<Database>,
+    quotas: web::Data<Quotas>,
+    events: web::Data<Events>,
     body: web::Bytes,
 ) -> HttpResponse {
     // Get the request body
@@ -63,21 +65,42 @@ async fn insert_class_unit(
         );
     }
 
+    // Only the class owner can add units
+    if !db.is_class_owner(&bearer, class_id).await {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid request"
+            }),
+        );
+    }
+
+    // Make sure the class hasn't reached it's unit quota. The
+    // quota stays locked until the unit has been inserted.
+    let _quota = quotas.lock(class_id).await;
+    if let Err(r) = quotas::check("units_per_class", quotas.units_per_class, db.count_class_units(class_id).await, 1) {
+        return *r;
+    }
+
     // Generate a new unit id
-    let unit_id: String = utils::generate_new_id(&class_id);
+    let unit_id: String = utils::generate_new_id(class_id);
 
     // Insert the unit data into the database
     return match db
-        .insert_class_unit(&bearer, &unit_id, &class_id, &unit_name)
+        .insert_class_unit(&bearer, &unit_id, class_id, &unit_name)
         .await
     {
-        true => http::response(
-            http::Status::OK,
-            serde_json::json!({
-                "response": "Unit created",
-                "unit_id": unit_id
-            }),
-        ),
+        true => {
+            // Push the new unit to the class's event stream
+            events.publish(class_id, "unit_created", serde_json::json!({ "unit_id": unit_id }), false);
+            http::response(
+                http::Status::OK,
+                serde_json::json!({
+                    "response": "Unit created",
+                    "unit_id": unit_id
+                }),
+            )
+        }
         false => http::response(
             http::Status::BAD_REQUEST,
             serde_json::json!({
@@ -93,6 +116,7 @@ async fn insert_class_unit(
 async fn update_class_unit(
     req: HttpRequest,
     db: web::Data<Database>,
+    events: web::Data<Events>,
     body: web::Bytes,
 ) -> HttpResponse {
     // Get the request body
@@ -148,14 +172,34 @@ async fn update_class_unit(
         );
     }
 
+    // Get whether the unit is locked before it's updated
+    let now: i64 = utils::get_time().as_secs() as i64;
+    let was_locked: bool = match db.get_class_unit(class_id, unit_id).await {
+        Some(unit) => units::is_unit_locked(&unit, now),
+        None => false,
+    };
+
     // Update the unit data in the database
-    return match db.update_class_unit(&bearer, class_id, unit_id, body).await {
-        true => http::response(
-            http::Status::OK,
-            serde_json::json!({
-                "response": "Updated unit"
-            }),
-        ),
+    return match db.update_class_unit(&bearer, class_id, unit_id, &body).await {
+        true => {
+            // Push the unit change to the class's event stream
+            events.publish(class_id, "unit_updated", serde_json::json!({ "unit_id": unit_id }), false);
+
+            // If the unit has been unlocked, notify the class's students
+            if was_locked {
+                if let Some(unit) = db.get_class_unit(class_id, unit_id).await {
+                    if !units::is_unit_locked(&unit, now) {
+                        db.insert_class_notification(class_id, "unit_unlocked", &unit.unit_name, "").await;
+                    }
+                }
+            }
+            http::response(
+                http::Status::OK,
+                serde_json::json!({
+                    "response": "Updated unit"
+                }),
+            )
+        }
         false => http::response(
             http::Status::BAD_REQUEST,
             serde_json::json!({
@@ -168,7 +212,11 @@ async fn update_class_unit(
 // The delete_class_unit() function is used to
 // delete the provided unit from the database.
 #[actix_web::delete("/class/{class_id}/units/{unit_id}")]
-async fn delete_class_unit(req: HttpRequest, db: web::Data<Database>) -> HttpResponse {
+async fn delete_class_unit(
+    req: HttpRequest,
+    db: web::Data<Database>,
+    events: web::Data<Events>,
+) -> HttpResponse {
     // Get the class id from the request headers
     let class_id: &str = match req.match_info().get("class_id") {
         Some(id) => id,
@@ -211,16 +259,93 @@ async fn delete_class_unit(req: HttpRequest, db: web::Data<Database>) -> HttpRes
 
     // Insert the unit data into the database
     return match db.delete_class_unit(&bearer, class_id, unit_id).await {
-        true => http::response(
-            http::Status::OK,
+        true => {
+            // Push the deletion to the class's event stream
+            events.publish(class_id, "unit_deleted", serde_json::json!({ "unit_id": unit_id }), false);
+            http::response(
+                http::Status::OK,
+                serde_json::json!({
+                    "response": "Unit deleted"
+                }),
+            )
+        }
+        false => http::response(
+            http::Status::BAD_REQUEST,
             serde_json::json!({
-                "response": "Unit deleted"
+                "response": "Failed to delete unit"
             }),
         ),
+    };
+}
+
+// The reorder_class_units() endpoint is used to change the order of the
+// class's units. The request body contains every unit id in their new order.
+#[actix_web::post("/class/{class_id}/units/order")]
+async fn reorder_class_units(
+    req: HttpRequest,
+    db: web::Data<Database>,
+    events: web::Data<Events>,
+    body: web::Bytes,
+) -> HttpResponse {
+    // Get the request body
+    let body: serde_json::Value = match http::body(&body) {
+        Ok(body) => body,
+        Err(_) => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request body"
+                }),
+            )
+        }
+    };
+
+    // Get the class id from the request headers
+    let class_id: &str = match req.match_info().get("class_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the bearer and access token from the request headers.
+    let bearer: String = http::header(&req, "authorization");
+    let access_token: String = http::header(&req, "access_token");
+
+    // Verify the provided authorization tokens
+    if !lib::auth::verify(&bearer, &access_token) {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid request"
+            }),
+        );
+    }
+
+    // Reorder the units in the database
+    return match db
+        .reorder_class_units(&bearer, class_id, &body)
+        .await
+    {
+        true => {
+            // Push the new order to the class's event stream
+            events.publish(class_id, "units_reordered", serde_json::json!({ "unit_ids": body["unit_ids"] }), false);
+            http::response(
+                http::Status::OK,
+                serde_json::json!({
+                    "response": "Reordered units"
+                }),
+            )
+        }
         false => http::response(
             http::Status::BAD_REQUEST,
             serde_json::json!({
-                "response": "Failed to delete unit"
+                "response": "Failed to reorder units"
             }),
         ),
     };
@@ -35,18 +35,32 @@ pub async fn get_user_data(req: HttpRequest, db: web::Data<Database>) -> HttpRes
     // Once the request has been verified, query the
     // database for the provided user_id. Once found,
     // return all the data from said user.
-    return match db.query_user_by_id(&user_id).await {
-        Some(user) => http::response(
-            http::Status::BAD_REQUEST,
-            serde_json::json!({
-                "status": "200",
-                "response": {
-                    "user_name": user.user_name,
-                    "user_id": user_id,
-                    "classes": "array of the users class_ids (select from classes where user_id = user_id)"
-                }
-            }),
-        ),
+    return match db.query_user_by_id(user_id).await {
+        Some(user) => {
+            // Only the user themself can see their classes and
+            // their unread notification count
+            let (unread_notifications, classes, archived_classes) = match user.bearer == bearer {
+                true => (
+                    Some(db.get_unread_notification_count(user_id).await),
+                    Some(db.get_user_classes(user_id, false).await),
+                    Some(db.get_user_classes(user_id, true).await),
+                ),
+                false => (None, None, None),
+            };
+            http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "status": "200",
+                    "response": {
+                        "user_name": user.user_name,
+                        "user_id": user_id,
+                        "classes": classes,
+                        "archived_classes": archived_classes,
+                        "unread_notifications": unread_notifications
+                    }
+                }),
+            )
+        }
         None => http::response(
             http::Status::BAD_REQUEST,
             serde_json::json!({
@@ -160,8 +174,8 @@ async fn insert_user_data(
     };
 
     // Get the user email from the request body
-    let email = match body.get("email") {
-        Some(email) => email.to_string(),
+    let email: &str = match body["email"].as_str() {
+        Some(email) => email,
         None => {
             return http::response(
                 http::Status::BAD_REQUEST,
@@ -189,7 +203,7 @@ async fn insert_user_data(
     // Insert the user into the database
     // Along with this insertion is the bearer, user_name
     // user's email and the time of registration
-    return match db.insert_user(&bearer, &user_name, &email).await {
+    return match db.insert_user(&bearer, &user_name, email).await {
         true => http::response(
             http::Status::OK,
             serde_json::json!({
@@ -204,3 +218,135 @@ async fn insert_user_data(
         ),
     };
 }
+
+// The get_user_preferences() endpoint is used to get which email
+// notifications the signed in user wants to receive. The provided
+// user_id must belong to the bearer.
+#[actix_web::get("/users/{user_id}/preferences")]
+async fn get_user_preferences(req: HttpRequest, db: web::Data<Database>) -> HttpResponse {
+    // Get the user id from the url parameters
+    let user_id: &str = match req.match_info().get("user_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the bearer and access token from the request headers.
+    let bearer: String = http::header(&req, "authorization");
+    let access_token: String = http::header(&req, "access_token");
+
+    // Verify the provided authorization tokens
+    if !lib::auth::verify(&bearer, &access_token) {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid request"
+            }),
+        );
+    }
+
+    // Users can only see their own preferences
+    if db.get_user_id_by_bearer(&bearer).await.as_deref() != Some(user_id) {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid request"
+            }),
+        );
+    }
+
+    // Return the users preferences
+    let prefs = db.get_notification_preferences(user_id).await;
+    return http::response(
+        http::Status::OK,
+        serde_json::json!({
+            "response": {
+                "email_grades": prefs.email_grades == 1,
+                "email_announcements": prefs.email_announcements == 1,
+                "email_deadlines": prefs.email_deadlines == 1
+            }
+        }),
+    );
+}
+
+// The update_user_preferences() endpoint is used to change which email
+// notifications the signed in user wants to receive. Any preference not
+// provided within the request body is left as is.
+#[actix_web::post("/users/{user_id}/preferences")]
+async fn update_user_preferences(
+    req: HttpRequest,
+    db: web::Data<Database>,
+    body: web::Bytes,
+) -> HttpResponse {
+    // Get the request body
+    let body: serde_json::Value = match http::body(&body) {
+        Ok(body) => body,
+        Err(_) => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request body"
+                }),
+            )
+        }
+    };
+
+    // Get the user id from the url parameters
+    let user_id: &str = match req.match_info().get("user_id") {
+        Some(id) => id,
+        None => {
+            return http::response(
+                http::Status::BAD_REQUEST,
+                serde_json::json!({
+                    "response": "Invalid request"
+                }),
+            )
+        }
+    };
+
+    // Get the bearer and access token from the request headers.
+    let bearer: String = http::header(&req, "authorization");
+    let access_token: String = http::header(&req, "access_token");
+
+    // Verify the provided authorization tokens
+    if !lib::auth::verify(&bearer, &access_token) {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid request"
+            }),
+        );
+    }
+
+    // Users can only change their own preferences
+    if db.get_user_id_by_bearer(&bearer).await.as_deref() != Some(user_id) {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid request"
+            }),
+        );
+    }
+
+    // Update the users preferences
+    return match db.update_notification_preferences(&bearer, &body).await {
+        true => http::response(
+            http::Status::OK,
+            serde_json::json!({
+                "response": "Updated user preferences"
+            }),
+        ),
+        false => http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Failed to update user preferences"
+            }),
+        ),
+    };
+}
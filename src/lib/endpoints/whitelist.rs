@@ -1,4 +1,4 @@
-use crate::lib::{self, handlers::Database, http};
+use crate::lib::{self, handlers::Database, http, events::Events, quotas::{self, Quotas}};
 use actix_web::{web, HttpRequest, HttpResponse};
 
 // The add_user_to_whitelist() endpoint is used to add an user to the provided class_id's
@@ -8,6 +8,7 @@ use actix_web::{web, HttpRequest, HttpResponse};
 async fn add_user_to_whitelist(
     req: HttpRequest,
     db: web::Data<Database>,
+    quotas: web::Data<Quotas>,
     body: web::Bytes,
 ) -> HttpResponse {
     // Get the request body
@@ -63,8 +64,25 @@ async fn add_user_to_whitelist(
         );
     }
 
+    // Only the class owner can whitelist students
+    if !db.is_class_owner(&bearer, class_id).await {
+        return http::response(
+            http::Status::BAD_REQUEST,
+            serde_json::json!({
+                "response": "Invalid request"
+            }),
+        );
+    }
+
+    // Make sure the class hasn't reached it's student quota. The
+    // quota stays locked until the student has been whitelisted.
+    let _quota = quotas.lock(class_id).await;
+    if let Err(r) = quotas::check("students_per_class", quotas.students_per_class, db.count_class_students(class_id).await, 1) {
+        return *r;
+    }
+
     // Insert the whitelist data into the database
-    return match db.insert_class_whitelist(&bearer, &class_id, user_id).await {
+    return match db.insert_class_whitelist(&bearer, class_id, user_id).await {
         true => http::response(
             http::Status::OK,
             serde_json::json!({
@@ -82,9 +100,14 @@ async fn add_user_to_whitelist(
 
 // The remove_user_from_whitelist() endpoint is used to delete the 
 // provided user from the provided class_id's whitelist. Anyone within 
-// this whitelist can access the given class_id.
+// this whitelist can access the given class_id. The user's event streams
+// for the class are closed once they've been removed.
 #[actix_web::delete("/class/{class_id}/whitelist/{user_id}")]
-async fn remove_user_from_whitelist(req: HttpRequest, db: web::Data<Database>) -> HttpResponse {
+async fn remove_user_from_whitelist(
+    req: HttpRequest,
+    db: web::Data<Database>,
+    events: web::Data<Events>,
+) -> HttpResponse {
     // Get the class id from the url parameters
     let class_id: &str = match req.match_info().get("class_id") {
         Some(id) => id,
@@ -127,15 +150,18 @@ async fn remove_user_from_whitelist(req: HttpRequest, db: web::Data<Database>) -
 
     // Delete the whitelist data into the database
     return match db
-        .remove_user_from_whitelist(&bearer, &class_id, user_id)
+        .remove_user_from_whitelist(&bearer, class_id, user_id)
         .await
     {
-        true => http::response(
-            http::Status::OK,
-            serde_json::json!({
-                "response": "Student removed from whitelist"
-            }),
-        ),
+        true => {
+            events.disconnect(class_id, Some(user_id));
+            http::response(
+                http::Status::OK,
+                serde_json::json!({
+                    "response": "Student removed from whitelist"
+                }),
+            )
+        }
         false => http::response(
             http::Status::BAD_REQUEST,
             serde_json::json!({
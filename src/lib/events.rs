@@ -0,0 +1,233 @@
+// Library Usages
+use super::utils;
+use actix_web::web::Bytes;
+use futures::channel::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use futures::{Stream, StreamExt};
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+// The STREAM_TOKEN_LIFETIME is how long a stream token
+// can be used to open a class's event stream.
+static STREAM_TOKEN_LIFETIME: Duration = Duration::from_secs(60);
+
+// The Subscriber struct is used to store a single
+// connected event stream along with the user on the
+// other end and whether they're the class owner.
+struct Subscriber {
+    id: u64,
+    user_id: String,
+    sender: UnboundedSender<Bytes>,
+    staff: bool,
+}
+
+// The StreamToken struct is used to store who a stream
+// token was given to, and the class it can be used for.
+struct StreamToken {
+    class_id: String,
+    bearer: String,
+    expires: Instant,
+}
+
+// Events Struct for globalizing every class's
+// connected event streams. Events published to
+// a class are pushed to all of it's subscribers.
+#[derive(Clone, Default)]
+pub struct Events {
+    subscribers: Arc<Mutex<HashMap<String, Vec<Subscriber>>>>,
+    tokens: Arc<Mutex<HashMap<String, StreamToken>>>,
+    next_id: Arc<AtomicU64>,
+}
+
+// The Subscription struct is the event stream returned to a
+// subscriber. Once the stream is dropped, such as when the
+// client disconnects, the subscriber is removed from it's class.
+pub struct Subscription {
+    events: Events,
+    class_id: String,
+    id: u64,
+    receiver: UnboundedReceiver<Bytes>,
+}
+
+// Subscription Implementation for receiving
+// the events published to the class
+impl Stream for Subscription {
+    type Item = Bytes;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Bytes>> {
+        return self.receiver.poll_next_unpin(cx);
+    }
+}
+
+// Subscription Implementation for removing
+// the subscriber once it disconnects
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        self.events.unsubscribe(&self.class_id, self.id);
+    }
+}
+
+// Events Implementation that contains all the
+// functions for subscribing and publishing events
+impl Events {
+    // The subscribe() function is used to open a new event
+    // stream for the provided user within the provided class.
+    // Staff subscribers also receive the events that only
+    // the class owner can see.
+    pub fn subscribe(&self, class_id: &str, user_id: &str, staff: bool) -> Subscription {
+        let (sender, receiver) = mpsc::unbounded();
+        let id: u64 = self.next_id.fetch_add(1, Ordering::Relaxed);
+
+        // Add the subscriber to the class
+        if let Ok(mut subscribers) = self.subscribers.lock() {
+            subscribers
+                .entry(class_id.to_string())
+                .or_default()
+                .push(Subscriber { id, user_id: user_id.to_string(), sender, staff });
+        }
+        return Subscription {
+            events: self.clone(),
+            class_id: class_id.to_string(),
+            id,
+            receiver,
+        };
+    }
+
+    // The unsubscribe() function is used to remove the provided
+    // subscriber from the provided class. If the class has no
+    // more subscribers, the class is removed.
+    fn unsubscribe(&self, class_id: &str, id: u64) {
+        if let Ok(mut subscribers) = self.subscribers.lock() {
+            if let Some(class) = subscribers.get_mut(class_id) {
+                class.retain(|s| s.id != id);
+                if class.is_empty() {
+                    subscribers.remove(class_id);
+                }
+            }
+        }
+    }
+
+    // The disconnect() function is used to close the event
+    // streams of the provided user within the provided class,
+    // or every stream within the class if no user is provided.
+    // This is used once a user can no longer see the class.
+    // Clients need to reconnect to keep receiving events.
+    pub fn disconnect(&self, class_id: &str, user_id: Option<&str>) {
+        if let Ok(mut subscribers) = self.subscribers.lock() {
+            match user_id {
+                Some(user_id) => if let Some(class) = subscribers.get_mut(class_id) {
+                    class.retain(|s| s.user_id != user_id);
+                },
+                None => {
+                    subscribers.remove(class_id);
+                }
+            }
+        }
+    }
+
+    // The publish() function is used to push an event to every
+    // subscriber of the provided class. Events are formatted as
+    // server-sent events. Any disconnected subscribers are removed.
+    pub fn publish(&self, class_id: &str, event: &str, data: serde_json::Value, staff_only: bool) {
+        let message: Bytes = Bytes::from(format!("event: {}\ndata: {}\n\n", event, data));
+
+        // Lock the subscribers so we can access them
+        let mut subscribers = match self.subscribers.lock() {
+            Ok(subscribers) => subscribers,
+            Err(_) => return,
+        };
+        let class: &mut Vec<Subscriber> = match subscribers.get_mut(class_id) {
+            Some(class) => class,
+            None => return,
+        };
+
+        // Send the event, keeping only the subscribers
+        // that are still connected
+        class.retain(|s| {
+            if staff_only && !s.staff {
+                return !s.sender.is_closed();
+            }
+            return s.sender.unbounded_send(message.clone()).is_ok();
+        });
+
+        // If the class has no more subscribers, remove it
+        if class.is_empty() {
+            subscribers.remove(class_id);
+        }
+    }
+
+    // The create_token() function is used to create a short-lived
+    // token that can open the provided class's event stream as the
+    // provided bearer. As a browser EventSource can't send headers,
+    // the token is sent within the query string instead of the
+    // bearer. Expired tokens are removed whenever a new one is made.
+    pub fn create_token(&self, class_id: &str, bearer: &str) -> Option<String> {
+        let token: String = utils::generate_new_id(&format!("{}:{}", class_id, bearer));
+        let now: Instant = Instant::now();
+        let mut tokens = self.tokens.lock().ok()?;
+        tokens.retain(|_, t| t.expires > now);
+        tokens.insert(token.clone(), StreamToken {
+            class_id: class_id.to_string(),
+            bearer: bearer.to_string(),
+            expires: now + STREAM_TOKEN_LIFETIME,
+        });
+        return Some(token);
+    }
+
+    // The redeem_token() function is used to get the bearer
+    // the provided stream token was created for. Each token
+    // can only be used once, and only for it's own class.
+    pub fn redeem_token(&self, class_id: &str, token: &str) -> Option<String> {
+        let token: StreamToken = self.tokens.lock().ok()?.remove(token)?;
+        if token.class_id != class_id || token.expires <= Instant::now() {
+            return None;
+        }
+        return Some(token.bearer);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn subscriber_count(events: &Events, class_id: &str) -> usize {
+        return events.subscribers.lock().unwrap().get(class_id).map_or(0, |c| c.len());
+    }
+
+    #[test]
+    fn removes_dropped_subscribers() {
+        let events: Events = Events::default();
+        let first: Subscription = events.subscribe("class", "a", false);
+        let second: Subscription = events.subscribe("class", "b", true);
+        assert_eq!(subscriber_count(&events, "class"), 2);
+        drop(first);
+        assert_eq!(subscriber_count(&events, "class"), 1);
+        drop(second);
+        assert!(events.subscribers.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn disconnects_users() {
+        let events: Events = Events::default();
+        let _a: Subscription = events.subscribe("class", "a", false);
+        let _b: Subscription = events.subscribe("class", "b", false);
+        events.disconnect("class", Some("a"));
+        assert_eq!(subscriber_count(&events, "class"), 1);
+        events.disconnect("class", None);
+        assert_eq!(subscriber_count(&events, "class"), 0);
+    }
+
+    #[test]
+    fn stream_tokens_are_single_use() {
+        let events: Events = Events::default();
+        let token: String = events.create_token("class", "bearer").unwrap();
+        assert_eq!(events.redeem_token("other", &token), None);
+
+        let token: String = events.create_token("class", "bearer").unwrap();
+        assert_eq!(events.redeem_token("class", &token), Some(String::from("bearer")));
+        assert_eq!(events.redeem_token("class", &token), None);
+    }
+}
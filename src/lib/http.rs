@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 pub type Status = actix_web::http::StatusCode;
 
 // The header() function is used to bypass
@@ -9,11 +10,21 @@ pub fn header(req: &actix_web::HttpRequest, key: &str) -> String {
     };
 }
 
+// The query() function is used to get a value from
+// the request's query string. This is used for requests
+// that can't send headers, such as a browser EventSource.
+pub fn query(req: &actix_web::HttpRequest, key: &str) -> String {
+    return match actix_web::web::Query::<HashMap<String, String>>::from_query(req.query_string()) {
+        Ok(q) => q.get(key).cloned().unwrap_or_default(),
+        Err(_) => "".to_string(),
+    };
+}
+
 // The body() function is used to bypass any invalid
 // body errors. This function is used to get the request
 // body then return it as an accessible serde_json::Value.
 pub fn body(body: &actix_web::web::Bytes) -> Result<serde_json::Value, ()> {
-    return match serde_json::from_slice(&body.to_vec()) {
+    return match serde_json::from_slice(body) {
         Ok(v) => Ok(v),
         Err(_) => Err(()),
     };
@@ -27,4 +38,19 @@ pub fn response(status: Status, json: serde_json::Value) -> actix_web::HttpRespo
         .status(status)
         .content_type("application/json")
         .json(json);
-}
\ No newline at end of file
+}
+// The payload() function is used to read a request body
+// that's larger than the default body limit, such as an
+// uploaded file. An error is returned if the body is
+// larger than the provided limit.
+pub async fn payload(mut payload: actix_web::web::Payload, limit: usize) -> Result<actix_web::web::Bytes, ()> {
+    let mut body: actix_web::web::BytesMut = actix_web::web::BytesMut::new();
+    while let Some(chunk) = futures::StreamExt::next(&mut payload).await {
+        let chunk: actix_web::web::Bytes = chunk.map_err(|_| ())?;
+        if body.len() + chunk.len() > limit {
+            return Err(());
+        }
+        body.extend_from_slice(&chunk);
+    }
+    return Ok(body.freeze());
+}
@@ -0,0 +1,219 @@
+// Library Usages
+use super::{child, child_text, html_body, is_video, text, Builder, ImportResult};
+use roxmltree::Node;
+use std::collections::HashMap;
+
+// HOW COMMON CARTRIDGES ARE CONVERTED:
+//
+//      A cartridge's imsmanifest.xml contains the course's organization,
+//      a tree of items, and the resources the items point to. Each
+//      top-level item (a module) becomes a unit, and every item within
+//      it that points to a resource becomes a lesson:
+//
+//          webcontent (.html)          => a lesson with the page as it's description
+//          imswl (web links)           => a lesson with the link, or it's video if
+//                                         the link is a Youtube video
+//          assignments                 => a lesson with the instructions as it's work
+//
+//      Top-level items that aren't within a module are added to a
+//      "General" unit. Every other resource, such as files, quizzes
+//      and discussions, is reported as unmapped.
+
+// The convert() function is used to convert the provided
+// Common Cartridge files into a class bundle.
+pub fn convert(files: &HashMap<String, Vec<u8>>) -> Option<ImportResult> {
+    let manifest: roxmltree::Document = roxmltree::Document::parse(text(files, "imsmanifest.xml")?).ok()?;
+    let root: Node = manifest.root_element();
+
+    // Get the course's title from the manifest's metadata
+    let class_name: String = root.descendants()
+        .find(|n| n.tag_name().name() == "general")
+        .and_then(|general| child(general, "title"))
+        .map(|title| title.descendants().filter(|n| n.is_text()).filter_map(|n| n.text()).collect::<String>())
+        .unwrap_or_default();
+
+    // Map the resources by their identifier
+    let resources: HashMap<&str, Node> = root.descendants()
+        .filter(|n| n.tag_name().name() == "resource")
+        .filter_map(|n| Some((n.attribute("identifier")?, n)))
+        .collect();
+
+    // Get the course's modules. Rooted organizations have a
+    // single item that contains all of the modules.
+    let mut builder: Builder = Builder::default();
+    let organization: Node = match root.descendants().find(|n| n.tag_name().name() == "organization") {
+        Some(o) => o,
+        None => return Some(builder.finish(&class_name)),
+    };
+    let mut modules: Vec<Node> = items(organization);
+    if modules.len() == 1 && modules[0].attribute("identifierref").is_none() {
+        modules = items(modules[0]);
+    }
+
+    // Convert each module into a unit
+    let mut general: Option<usize> = None;
+    for module in modules {
+        let title: String = child_text(module, "title");
+        if module.attribute("identifierref").is_some() {
+            let unit: usize = *general.get_or_insert_with(|| builder.unit("General"));
+            convert_item(files, &resources, &mut builder, unit, module);
+            continue;
+        }
+        let unit: usize = builder.unit(if title.is_empty() { "Untitled module" } else { &title });
+        for item in module.descendants().filter(|n| n.tag_name().name() == "item" && n.attribute("identifierref").is_some()) {
+            convert_item(files, &resources, &mut builder, unit, item);
+        }
+    }
+    return Some(builder.finish(&class_name));
+}
+
+// The items() function is used to get the
+// child items of the provided item.
+fn items<'a, 'input>(node: Node<'a, 'input>) -> Vec<Node<'a, 'input>> {
+    return node.children().filter(|n| n.tag_name().name() == "item").collect();
+}
+
+// The convert_item() function is used to convert the resource
+// that the provided item points to into a lesson within the
+// provided unit. Resources that can't be converted are
+// reported as unmapped.
+fn convert_item(
+    files: &HashMap<String, Vec<u8>>,
+    resources: &HashMap<&str, Node>,
+    builder: &mut Builder,
+    unit: usize,
+    item: Node,
+) {
+    let title: String = child_text(item, "title");
+    let resource: Node = match item.attribute("identifierref").and_then(|r| resources.get(r)) {
+        Some(r) => *r,
+        None => return builder.unmapped(&title, "", "The item's resource is missing"),
+    };
+    let kind: &str = resource.attribute("type").unwrap_or("");
+
+    // Get the resource's main file
+    let href: &str = resource.attribute("href")
+        .or_else(|| child(resource, "file").and_then(|f| f.attribute("href")))
+        .unwrap_or("");
+    let is_html: bool = href.to_lowercase().ends_with(".html") || href.to_lowercase().ends_with(".htm");
+    let content: Option<&str> = text(files, href);
+
+    // Web pages
+    if kind == "webcontent" && is_html {
+        return match content {
+            Some(html) => builder.lesson(unit, serde_json::json!({
+                "title": title,
+                "description": html_body(html)
+            })),
+            None => builder.unmapped(&title, kind, "The page's file is missing"),
+        };
+    }
+
+    // Web links
+    if kind.starts_with("imswl") {
+        let url: Option<String> = content
+            .and_then(|xml| roxmltree::Document::parse(xml).ok())
+            .and_then(|doc| child(doc.root_element(), "url").and_then(|u| u.attribute("href")).map(String::from));
+        return match url {
+            Some(url) if is_video(&url) => builder.lesson(unit, serde_json::json!({ "title": title, "video": url })),
+            Some(url) => builder.lesson(unit, serde_json::json!({ "title": title, "description": url })),
+            None => builder.unmapped(&title, kind, "The link's file is missing"),
+        };
+    }
+
+    // Assignments. Canvas exports it's assignments as
+    // learning application resources with an html page.
+    if kind.starts_with("assignment") {
+        let instructions: Option<String> = content
+            .and_then(|xml| roxmltree::Document::parse(xml).ok())
+            .map(|doc| child_text(doc.root_element(), "text"));
+        return match instructions {
+            Some(work) => builder.lesson(unit, serde_json::json!({ "title": title, "work": work })),
+            None => builder.unmapped(&title, kind, "The assignment's file is missing"),
+        };
+    }
+    if kind.ends_with("learning-application-resource") && is_html {
+        return match content {
+            Some(html) => builder.lesson(unit, serde_json::json!({
+                "title": title,
+                "work": html_body(html)
+            })),
+            None => builder.unmapped(&title, kind, "The assignment's file is missing"),
+        };
+    }
+
+    // Everything else can't be converted
+    let reason: &str = match kind {
+        k if k.starts_with("imsdt") => "Discussions aren't supported",
+        k if k.contains("imsqti") || k.contains("assessment") => "Quizzes aren't supported",
+        "webcontent" => "Files aren't supported",
+        _ => "Unsupported resource type",
+    };
+    builder.unmapped(&title, kind, reason);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    static MANIFEST: &str = r#"<?xml version="1.0"?>
+        <manifest xmlns:lom="http://ltsc.ieee.org/xsd/imsccv1p1/LOM/manifest">
+            <metadata><lom:lom><lom:general><lom:title><lom:string>Biology</lom:string></lom:title></lom:general></lom:lom></metadata>
+            <organizations><organization><item identifier="root">
+                <item identifier="m1">
+                    <title>Week 1</title>
+                    <item identifier="i1" identifierref="r1"><title>Cells</title></item>
+                    <item identifier="i2" identifierref="r2"><title>Video</title></item>
+                    <item identifier="i3" identifierref="r3"><title>Lab report</title></item>
+                    <item identifier="i4" identifierref="r4"><title>Quiz 1</title></item>
+                    <item identifier="i5" identifierref="missing"><title>Missing</title></item>
+                </item>
+                <item identifier="i6" identifierref="r1"><title>Loose page</title></item>
+            </item></organization></organizations>
+            <resources>
+                <resource identifier="r1" type="webcontent" href="cells.html"/>
+                <resource identifier="r2" type="imswl_xmlv1p1"><file href="video.xml"/></resource>
+                <resource identifier="r3" type="assignment_xmlv1p0"><file href="report.xml"/></resource>
+                <resource identifier="r4" type="imsqti_xmlv1p2/imscc_xmlv1p1/assessment"/>
+            </resources>
+        </manifest>"#;
+
+    fn files() -> HashMap<String, Vec<u8>> {
+        return [
+            ("imsmanifest.xml", MANIFEST),
+            ("cells.html", "<html><body><p>Cells are small</p></body></html>"),
+            ("video.xml", r#"<webLink><title>Video</title><url href="https://youtu.be/abc"/></webLink>"#),
+            ("report.xml", "<assignment><title>Lab report</title><text>Write it up</text></assignment>"),
+        ].iter().map(|(path, data)| (path.to_string(), data.as_bytes().to_vec())).collect();
+    }
+
+    #[test]
+    fn converts_modules_into_units() {
+        let result: ImportResult = convert(&files()).unwrap();
+        assert_eq!(result.bundle["class"]["class_name"], "Biology");
+
+        let units: Vec<&Value> = result.bundle["units"].as_array().unwrap().iter().collect();
+        assert_eq!(units.iter().map(|u| u["unit_name"].as_str().unwrap()).collect::<Vec<&str>>(), vec!["Week 1", "General"]);
+        let lessons: &Vec<Value> = units[0]["lessons"].as_array().unwrap();
+        assert_eq!(lessons.len(), 3);
+        assert_eq!(lessons[0]["description"], "<p>Cells are small</p>");
+        assert_eq!(lessons[1]["video"], "https://youtu.be/abc");
+        assert_eq!(lessons[2]["work"], "Write it up");
+        assert_eq!(units[1]["lessons"][0]["title"], "Loose page");
+    }
+
+    #[test]
+    fn reports_unmapped_resources() {
+        let result: ImportResult = convert(&files()).unwrap();
+        let reasons: Vec<&str> = result.unmapped.iter().map(|u| u["reason"].as_str().unwrap()).collect();
+        assert_eq!(reasons, vec!["Quizzes aren't supported", "The item's resource is missing"]);
+    }
+
+    #[test]
+    fn requires_a_manifest() {
+        let mut files: HashMap<String, Vec<u8>> = files();
+        files.insert(String::from("imsmanifest.xml"), b"<manifest".to_vec());
+        assert!(convert(&files).is_none());
+    }
+}
@@ -0,0 +1,326 @@
+// Library Usages
+use super::bundle;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::io::Read;
+
+pub mod cartridge;
+pub mod moodle;
+pub mod takeout;
+
+// The MAX_ARCHIVE_SIZE is the largest course archive
+// (in bytes) that can be uploaded.
+pub static MAX_ARCHIVE_SIZE: usize = 64 * 1024 * 1024;
+
+// The MAX_CONTENT_SIZE is the largest amount of text (in bytes)
+// that's read from an archive. Only the files that can be
+// converted are read, so videos and documents don't count.
+static MAX_CONTENT_SIZE: u64 = 64 * 1024 * 1024;
+
+// The CONTENT_EXTENSIONS are the kinds of files that are
+// read from an archive. Every other file is skipped.
+static CONTENT_EXTENSIONS: [&str; 4] = [".xml", ".html", ".htm", ".json"];
+
+// The ImportResult struct is used to store a course that was
+// converted from another platform. The bundle can be imported
+// like an exported class, and the unmapped items are the parts
+// of the course that couldn't be converted.
+pub struct ImportResult {
+    pub bundle: Value,
+    pub unmapped: Vec<Value>,
+}
+
+// The convert() function is used to convert the provided course
+// archive into a class bundle. IMS Common Cartridges and Moodle
+// backups (zip or tar.gz) are supported. None is returned if the
+// archive can't be read or isn't a known course format.
+pub fn convert(archive: &[u8]) -> Option<ImportResult> {
+    let files: HashMap<String, Vec<u8>> = read_archive(archive)?;
+    if files.contains_key("imsmanifest.xml") {
+        return cartridge::convert(&files);
+    }
+    if files.contains_key("moodle_backup.xml") {
+        return moodle::convert(&files);
+    }
+    return None;
+}
+
+// The convert_takeout() function is used to convert every
+// Google Classroom class within the provided Takeout archive
+// into a class bundle. None is returned if the archive can't
+// be read or doesn't contain any classes.
+pub fn convert_takeout(archive: &[u8]) -> Option<Vec<ImportResult>> {
+    return takeout::convert(&read_archive(archive)?);
+}
+
+// The read_archive() function is used to read the text files
+// within the provided zip or tar.gz archive, mapped by their
+// path. None is returned if the archive can't be read, or if
+// it contains too much text.
+fn read_archive(archive: &[u8]) -> Option<HashMap<String, Vec<u8>>> {
+    let mut files: HashMap<String, Vec<u8>> = HashMap::new();
+    let mut size: u64 = 0;
+
+    // Read a single file, making sure the size limit isn't passed
+    let mut read = |path: &str, file: &mut dyn Read| -> Option<()> {
+        let path: &str = path.trim_start_matches("./").trim_start_matches('/');
+        if !CONTENT_EXTENSIONS.iter().any(|e| path.to_lowercase().ends_with(e)) {
+            return Some(());
+        }
+        let mut data: Vec<u8> = Vec::new();
+        file.take(MAX_CONTENT_SIZE - size + 1).read_to_end(&mut data).ok()?;
+        size += data.len() as u64;
+        if size > MAX_CONTENT_SIZE {
+            return None;
+        }
+        files.insert(path.to_string(), data);
+        return Some(());
+    };
+
+    // Gzip archives are tar.gz, everything else is read as a zip
+    if archive.starts_with(&[0x1f, 0x8b]) {
+        let mut tar = tar::Archive::new(flate2::read::GzDecoder::new(archive));
+        for entry in tar.entries().ok()? {
+            let mut entry = entry.ok()?;
+            if entry.header().entry_type().is_file() {
+                let path: String = entry.path().ok()?.to_string_lossy().to_string();
+                read(&path, &mut entry)?;
+            }
+        }
+    } else {
+        let mut zip = zip::ZipArchive::new(std::io::Cursor::new(archive)).ok()?;
+        for i in 0..zip.len() {
+            let mut file = zip.by_index(i).ok()?;
+            if file.is_file() {
+                let path: String = file.name().to_string();
+                read(&path, &mut file)?;
+            }
+        }
+    }
+    return Some(files);
+}
+
+// ImportResult Implementation
+impl ImportResult {
+    // The preview() function is used to summarize what
+    // importing the converted course would create, without
+    // importing it.
+    pub fn preview(&self) -> Value {
+        return serde_json::json!({
+            "class_name": self.bundle["class"]["class_name"],
+            "units": bundle::array(&self.bundle, "units").iter().map(|u| serde_json::json!({
+                "unit_name": u["unit_name"],
+                "lessons": bundle::array(u, "lessons").iter().map(|l| l["title"].clone()).collect::<Vec<Value>>()
+            })).collect::<Vec<Value>>(),
+            "announcements": bundle::array(&self.bundle, "announcements").iter().map(|a| a["title"].clone()).collect::<Vec<Value>>(),
+            "unmapped": self.unmapped
+        });
+    }
+}
+
+// The Builder struct is used by the converters to build a
+// class bundle one unit and lesson at a time, while keeping
+// track of everything that couldn't be converted.
+#[derive(Default)]
+pub struct Builder {
+    units: Vec<Value>,
+    announcements: Vec<Value>,
+    unmapped: Vec<Value>,
+    ids: usize,
+}
+
+// Builder Implementation that contains the
+// functions for building a class bundle
+impl Builder {
+    // The next_id() function is used to generate an id for the
+    // bundle. These ids only link the bundle together, and
+    // are replaced when the bundle is imported.
+    fn next_id(&mut self, kind: &str) -> String {
+        self.ids += 1;
+        return format!("{}-{}", kind, self.ids);
+    }
+
+    // The unit() function is used to add a new unit to the
+    // bundle. The unit's index is returned so lessons can
+    // be added to it.
+    pub fn unit(&mut self, unit_name: &str) -> usize {
+        let unit_id: String = self.next_id("unit");
+        self.units.push(serde_json::json!({
+            "unit_id": unit_id,
+            "unit_name": unit_name,
+            "lessons": []
+        }));
+        return self.units.len() - 1;
+    }
+
+    // The lesson() function is used to add a lesson to the
+    // provided unit. Lessons without a title are given one,
+    // and an invalid availability window is dropped.
+    pub fn lesson(&mut self, unit: usize, mut lesson: Value) {
+        lesson["lesson_id"] = Value::from(self.next_id("lesson"));
+        if lesson["title"].as_str().is_none_or(|t| t.trim().is_empty()) {
+            lesson["title"] = Value::from("Untitled lesson");
+        }
+        let opens_at: i64 = lesson["opens_at"].as_i64().unwrap_or(0);
+        let closes_at: i64 = lesson["closes_at"].as_i64().unwrap_or(0);
+        if opens_at < 0 || (closes_at != 0 && closes_at <= opens_at) {
+            lesson["opens_at"] = Value::from(0);
+            lesson["closes_at"] = Value::from(0);
+        }
+        if let Some(lessons) = self.units[unit]["lessons"].as_array_mut() {
+            lessons.push(lesson);
+        }
+    }
+
+    // The announcement() function is used to add an
+    // announcement to the bundle.
+    pub fn announcement(&mut self, mut announcement: Value) {
+        announcement["announcement_id"] = Value::from(self.next_id("announcement"));
+        self.announcements.push(announcement);
+    }
+
+    // The unmapped() function is used to report an item
+    // that couldn't be converted, along with why.
+    pub fn unmapped(&mut self, title: &str, kind: &str, reason: &str) {
+        self.unmapped.push(serde_json::json!({
+            "title": title,
+            "type": kind,
+            "reason": reason
+        }));
+    }
+
+    // The finish() function is used to create the class
+    // bundle once everything has been added.
+    pub fn finish(self, class_name: &str) -> ImportResult {
+        let class_name: &str = match class_name.trim() {
+            "" => "Imported class",
+            name => name,
+        };
+        return ImportResult {
+            bundle: serde_json::json!({
+                "version": bundle::BUNDLE_VERSION,
+                "class": { "class_name": class_name, "enable_whitelist": false },
+                "units": self.units,
+                "announcements": self.announcements
+            }),
+            unmapped: self.unmapped,
+        };
+    }
+}
+
+// The text() function is used to get the provided file
+// from the archive as text.
+fn text<'a>(files: &'a HashMap<String, Vec<u8>>, path: &str) -> Option<&'a str> {
+    return std::str::from_utf8(files.get(path)?).ok();
+}
+
+// The child() function is used to get the first child element
+// of the provided xml node with the provided name. Namespace
+// prefixes are ignored.
+fn child<'a, 'input>(node: roxmltree::Node<'a, 'input>, name: &str) -> Option<roxmltree::Node<'a, 'input>> {
+    return node.children().find(|n| n.is_element() && n.tag_name().name() == name);
+}
+
+// The child_text() function is used to get the text within the
+// first child element with the provided name. An empty string
+// is returned if there isn't one.
+fn child_text(node: roxmltree::Node, name: &str) -> String {
+    return child(node, name).and_then(|n| n.text()).unwrap_or("").trim().to_string();
+}
+
+// The html_body() function is used to get the content within
+// the body of the provided html page, so it can be used as
+// a lesson's description.
+fn html_body(html: &str) -> String {
+    let lower: String = html.to_ascii_lowercase();
+    let start: usize = match lower.find("<body") {
+        Some(i) => lower[i..].find('>').map_or(0, |j| i + j + 1),
+        None => 0,
+    };
+    let end: usize = lower.rfind("</body>").filter(|e| *e >= start).unwrap_or(html.len());
+    return html[start..end].trim().to_string();
+}
+
+// The is_video() function is used to check whether the
+// provided url is a Youtube video, which can be used as
+// a lesson's video.
+fn is_video(url: &str) -> bool {
+    return url.contains("youtube.com/") || url.contains("youtu.be/");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn zip(files: &[(&str, &str)]) -> Vec<u8> {
+        let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        for (path, data) in files {
+            zip.start_file(*path, zip::write::FileOptions::default()).unwrap();
+            zip.write_all(data.as_bytes()).unwrap();
+        }
+        return zip.finish().unwrap().into_inner();
+    }
+
+    fn tar_gz(files: &[(&str, &str)]) -> Vec<u8> {
+        let mut tar = tar::Builder::new(flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default()));
+        for (path, data) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(data.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            tar.append_data(&mut header, path, data.as_bytes()).unwrap();
+        }
+        return tar.into_inner().unwrap().finish().unwrap();
+    }
+
+    #[test]
+    fn reads_only_text_files() {
+        let files: Vec<(&str, &str)> = vec![("./course/page.HTML", "<p>Hi</p>"), ("video.mp4", "..."), ("moodle_backup.xml", "<a/>")];
+        for archive in [zip(&files), tar_gz(&files)] {
+            let read: HashMap<String, Vec<u8>> = read_archive(&archive).unwrap();
+            assert_eq!(read.len(), 2);
+            assert_eq!(read["course/page.HTML"], b"<p>Hi</p>");
+            assert!(read.contains_key("moodle_backup.xml"));
+        }
+        assert!(read_archive(b"not an archive").is_none());
+    }
+
+    #[test]
+    fn unknown_archives_arent_converted() {
+        assert!(convert(&zip(&[("notes.html", "<p>Hi</p>")])).is_none());
+        assert!(convert_takeout(&zip(&[("notes.json", "{}")])).is_none());
+    }
+
+    #[test]
+    fn builds_valid_bundles() {
+        let mut builder: Builder = Builder::default();
+        let unit: usize = builder.unit("Unit");
+        builder.lesson(unit, serde_json::json!({ "title": " " }));
+        builder.lesson(unit, serde_json::json!({ "title": "Window", "opens_at": 200, "closes_at": 100 }));
+        builder.announcement(serde_json::json!({ "title": "Hello" }));
+        builder.unmapped("Quiz", "quiz", "Quizzes aren't supported");
+        let result: ImportResult = builder.finish("  ");
+
+        assert!(bundle::is_valid(&result.bundle));
+        assert_eq!(result.bundle["class"]["class_name"], "Imported class");
+        let lessons: &[Value] = bundle::array(&bundle::array(&result.bundle, "units")[0], "lessons");
+        assert_eq!(lessons[0]["title"], "Untitled lesson");
+        assert_eq!((lessons[1]["opens_at"].as_i64(), lessons[1]["closes_at"].as_i64()), (Some(0), Some(0)));
+        assert_eq!(result.unmapped.len(), 1);
+        assert_eq!(result.preview()["announcements"], serde_json::json!(["Hello"]));
+    }
+
+    #[test]
+    fn gets_html_bodies() {
+        assert_eq!(html_body("<html><BODY class=\"x\"> <p>Hi</p> </body></html>"), "<p>Hi</p>");
+        assert_eq!(html_body("<p>No body</p>"), "<p>No body</p>");
+    }
+
+    #[test]
+    fn detects_videos() {
+        assert!(is_video("https://www.youtube.com/watch?v=abc"));
+        assert!(is_video("https://youtu.be/abc"));
+        assert!(!is_video("https://example.com/youtube"));
+    }
+}
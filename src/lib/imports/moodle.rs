@@ -0,0 +1,191 @@
+// Library Usages
+use super::{child, child_text, is_video, text, Builder, ImportResult};
+use roxmltree::Node;
+use std::collections::HashMap;
+
+// HOW MOODLE BACKUPS ARE CONVERTED:
+//
+//      A backup's moodle_backup.xml lists the course's sections and
+//      activities, along with the directory each one is stored in.
+//      Each section becomes a unit, and the activities within it
+//      become lessons:
+//
+//          page        => a lesson with the page as it's description
+//          assign      => a lesson with the instructions as it's work, along
+//                         with it's due date and availability window
+//          url         => a lesson with the link, or it's video if the
+//                         link is a Youtube video
+//
+//      Every other activity, such as quizzes, forums and files,
+//      is reported as unmapped.
+
+// The NULL value is what Moodle uses for empty fields
+static NULL: &str = "$@NULL@$";
+
+// The convert() function is used to convert the provided
+// Moodle backup files into a class bundle.
+pub fn convert(files: &HashMap<String, Vec<u8>>) -> Option<ImportResult> {
+    let backup: roxmltree::Document = roxmltree::Document::parse(text(files, "moodle_backup.xml")?).ok()?;
+    let root: Node = backup.root_element();
+    let information: Node = child(root, "information")?;
+    let class_name: String = child_text(information, "original_course_fullname");
+    let contents: Node = child(information, "contents")?;
+
+    // Get the course's activities
+    let activities: Vec<Node> = child(contents, "activities")
+        .map(|a| a.children().filter(|n| n.tag_name().name() == "activity").collect())
+        .unwrap_or_default();
+
+    // Convert each section into a unit
+    let mut builder: Builder = Builder::default();
+    let sections: Vec<Node> = child(contents, "sections")
+        .map(|s| s.children().filter(|n| n.tag_name().name() == "section").collect())
+        .unwrap_or_default();
+    for (number, section) in sections.into_iter().enumerate() {
+        let section_id: String = child_text(section, "sectionid");
+        let unit_name: String = match field(section, "title") {
+            title if !title.is_empty() => title,
+            _ if number == 0 => "General".to_string(),
+            _ => format!("Section {}", number),
+        };
+        let unit: usize = builder.unit(&unit_name);
+
+        // Convert the section's activities into lessons
+        for activity in activities.iter().filter(|a| child_text(**a, "sectionid") == section_id) {
+            convert_activity(files, &mut builder, unit, *activity);
+        }
+    }
+    return Some(builder.finish(&class_name));
+}
+
+// The field() function is used to get the text within the
+// provided field, treating Moodle's null value as empty.
+fn field(node: Node, name: &str) -> String {
+    let value: String = child_text(node, name);
+    return if value == NULL { String::new() } else { value };
+}
+
+// The convert_activity() function is used to convert the provided
+// activity into a lesson within the provided unit. Activities that
+// can't be converted are reported as unmapped.
+fn convert_activity(files: &HashMap<String, Vec<u8>>, builder: &mut Builder, unit: usize, activity: Node) {
+    let title: String = field(activity, "title");
+    let kind: String = child_text(activity, "modulename");
+
+    // Report the activities that can't be converted
+    let reason: &str = match kind.as_str() {
+        "page" | "assign" | "url" => "",
+        "quiz" => "Quizzes aren't supported",
+        "forum" => "Forums aren't supported",
+        "resource" | "folder" => "Files aren't supported",
+        _ => "Unsupported activity type",
+    };
+    if !reason.is_empty() {
+        return builder.unmapped(&title, &kind, reason);
+    }
+
+    // Get the activity's data, which is stored in
+    // {directory}/{modulename}.xml as <activity><{modulename}>
+    let path: String = format!("{}/{}.xml", child_text(activity, "directory"), kind);
+    let document: Option<roxmltree::Document> = text(files, &path).and_then(|xml| roxmltree::Document::parse(xml).ok());
+    let data: Node = match document.as_ref().and_then(|doc| child(doc.root_element(), &kind)) {
+        Some(data) => data,
+        None => return builder.unmapped(&title, &kind, "The activity's file is missing"),
+    };
+    let time = |name: &str| -> i64 { field(data, name).parse().unwrap_or(0) };
+
+    match kind.as_str() {
+        "page" => builder.lesson(unit, serde_json::json!({
+            "title": title,
+            "description": match field(data, "content") {
+                content if content.is_empty() => field(data, "intro"),
+                content => content,
+            }
+        })),
+        "assign" => builder.lesson(unit, serde_json::json!({
+            "title": title,
+            "work": field(data, "intro"),
+            "due_date": time("duedate"),
+            "opens_at": time("allowsubmissionsfromdate"),
+            "closes_at": time("cutoffdate")
+        })),
+        _ => {
+            let url: String = field(data, "externalurl");
+            match is_video(&url) {
+                true => builder.lesson(unit, serde_json::json!({
+                    "title": title,
+                    "description": field(data, "intro"),
+                    "video": url
+                })),
+                false => builder.lesson(unit, serde_json::json!({
+                    "title": title,
+                    "description": format!("{}\n\n{}", url, field(data, "intro")).trim()
+                })),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    static BACKUP: &str = r#"<?xml version="1.0"?>
+        <moodle_backup><information>
+            <original_course_fullname>Chemistry</original_course_fullname>
+            <contents>
+                <activities>
+                    <activity><sectionid>1</sectionid><modulename>page</modulename><title>Atoms</title><directory>activities/page_1</directory></activity>
+                    <activity><sectionid>2</sectionid><modulename>assign</modulename><title>Lab</title><directory>activities/assign_2</directory></activity>
+                    <activity><sectionid>2</sectionid><modulename>url</modulename><title>Video</title><directory>activities/url_3</directory></activity>
+                    <activity><sectionid>2</sectionid><modulename>quiz</modulename><title>Quiz</title><directory>activities/quiz_4</directory></activity>
+                    <activity><sectionid>2</sectionid><modulename>page</modulename><title>Missing</title><directory>activities/page_5</directory></activity>
+                </activities>
+                <sections>
+                    <section><sectionid>1</sectionid><title>$@NULL@$</title></section>
+                    <section><sectionid>2</sectionid><title>Reactions</title></section>
+                    <section><sectionid>3</sectionid><title>$@NULL@$</title></section>
+                </sections>
+            </contents>
+        </information></moodle_backup>"#;
+
+    fn files() -> HashMap<String, Vec<u8>> {
+        return [
+            ("moodle_backup.xml", BACKUP),
+            ("activities/page_1/page.xml", "<activity><page><intro>Intro</intro><content>$@NULL@$</content></page></activity>"),
+            ("activities/assign_2/assign.xml", "<activity><assign><intro>Mix them</intro><duedate>300</duedate><allowsubmissionsfromdate>100</allowsubmissionsfromdate><cutoffdate>400</cutoffdate></assign></activity>"),
+            ("activities/url_3/url.xml", "<activity><url><intro>Watch</intro><externalurl>https://www.youtube.com/watch?v=abc</externalurl></url></activity>"),
+        ].iter().map(|(path, data)| (path.to_string(), data.as_bytes().to_vec())).collect();
+    }
+
+    #[test]
+    fn converts_sections_into_units() {
+        let result: ImportResult = convert(&files()).unwrap();
+        assert_eq!(result.bundle["class"]["class_name"], "Chemistry");
+
+        let units: &Vec<Value> = result.bundle["units"].as_array().unwrap();
+        assert_eq!(units.iter().map(|u| u["unit_name"].as_str().unwrap()).collect::<Vec<&str>>(), vec!["General", "Reactions", "Section 2"]);
+        assert_eq!(units[0]["lessons"][0]["description"], "Intro");
+
+        let lab: &Value = &units[1]["lessons"][0];
+        assert_eq!(lab["work"], "Mix them");
+        assert_eq!((lab["due_date"].as_i64(), lab["opens_at"].as_i64(), lab["closes_at"].as_i64()), (Some(300), Some(100), Some(400)));
+        assert_eq!(units[1]["lessons"][1]["video"], "https://www.youtube.com/watch?v=abc");
+        assert_eq!(units[1]["lessons"].as_array().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn reports_unmapped_activities() {
+        let result: ImportResult = convert(&files()).unwrap();
+        let reasons: Vec<&str> = result.unmapped.iter().map(|u| u["reason"].as_str().unwrap()).collect();
+        assert_eq!(reasons, vec!["Quizzes aren't supported", "The activity's file is missing"]);
+    }
+
+    #[test]
+    fn treats_null_fields_as_empty() {
+        let document = roxmltree::Document::parse("<a><b>$@NULL@$</b><c> text </c></a>").unwrap();
+        assert_eq!(field(document.root_element(), "b"), "");
+        assert_eq!(field(document.root_element(), "c"), "text");
+    }
+}
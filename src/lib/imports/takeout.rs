@@ -0,0 +1,277 @@
+// Library Usages
+use super::{Builder, ImportResult};
+use serde_json::Value;
+use std::collections::HashMap;
+
+// HOW GOOGLE CLASSROOM TAKEOUTS ARE CONVERTED:
+//
+//      A Takeout archive contains a json file for each class under
+//      Takeout/Classroom, with the class's name and it's posts:
+//
+//      { "name": "Biology", "posts": [{
+//          "creationTime": "2021-09-01T14:00:00.000Z",
+//          "state": "PUBLISHED" | "DRAFT",
+//          "topics": [{ "name": "Week 1" }],
+//          "courseWork": { "title", "description", "dueDate": { "year", "month", "day" },
+//                          "dueTime": { "hours", "minutes" } },
+//          "courseWorkMaterial": { "title", "description" },
+//          "announcement": { "text" },
+//          "materials": [{ "link": { "url", "title" } }, { "youtubeVideo": { "id", "title" } },
+//                        { "driveFile": { "title" } }, { "form": { "title" } }]
+//      }] }
+//
+//      Each topic becomes a unit, and posts without a topic are added
+//      to a "General" unit. Coursework becomes a lesson with it's
+//      description as the work, and materials become lessons with their
+//      description. Links are added to the lesson's description, and the
+//      first Youtube video becomes the lesson's video. Announcements are
+//      kept as announcements. Drive files, forms and draft posts are
+//      reported as unmapped.
+
+// The convert() function is used to convert every class within
+// the provided Takeout files into a class bundle. None is
+// returned if the files don't contain any classes.
+pub fn convert(files: &HashMap<String, Vec<u8>>) -> Option<Vec<ImportResult>> {
+    let mut classes: Vec<(&String, Value)> = files.iter()
+        .filter(|(path, _)| path.contains("Classroom/") && path.ends_with(".json"))
+        .filter_map(|(path, data)| Some((path, serde_json::from_slice::<Value>(data).ok()?)))
+        .filter(|(_, class)| class["name"].is_string() && class["posts"].is_array())
+        .collect();
+    if classes.is_empty() {
+        return None;
+    }
+    classes.sort_by(|a, b| a.0.cmp(b.0));
+    return Some(classes.iter().map(|(_, class)| convert_class(class)).collect());
+}
+
+// The convert_class() function is used to convert
+// a single Google Classroom class into a bundle.
+fn convert_class(class: &Value) -> ImportResult {
+    let mut builder: Builder = Builder::default();
+    let mut units: HashMap<String, usize> = HashMap::new();
+
+    // Convert the posts from oldest to newest
+    let mut posts: Vec<&Value> = class["posts"].as_array().map(|p| p.iter().collect()).unwrap_or_default();
+    posts.sort_by_key(|p| timestamp(p["creationTime"].as_str().unwrap_or("")));
+    for post in posts {
+        let (title, kind): (&str, &str) = match (&post["courseWork"], &post["courseWorkMaterial"], &post["announcement"]) {
+            (w, _, _) if w.is_object() => (w["title"].as_str().unwrap_or(""), "courseWork"),
+            (_, m, _) if m.is_object() => (m["title"].as_str().unwrap_or(""), "courseWorkMaterial"),
+            (_, _, a) if a.is_object() => (a["text"].as_str().unwrap_or(""), "announcement"),
+            _ => continue,
+        };
+        if post["state"].as_str() == Some("DRAFT") {
+            builder.unmapped(title, kind, "Draft posts aren't imported");
+            continue;
+        }
+        let date: i64 = timestamp(post["creationTime"].as_str().unwrap_or(""));
+
+        // Announcements are kept as announcements, using
+        // the first line of the announcement as it's title
+        if kind == "announcement" {
+            let title: String = title.lines().next().unwrap_or("").chars().take(100).collect();
+            builder.announcement(serde_json::json!({
+                "title": title,
+                "description": post["announcement"]["text"],
+                "date": date,
+                "publish_at": date
+            }));
+            continue;
+        }
+
+        // Get the post's links, videos and attachments
+        let mut description: String = post[kind]["description"].as_str().unwrap_or("").to_string();
+        let mut video: String = String::new();
+        for material in post["materials"].as_array().map_or(&[] as &[Value], |m| m.as_slice()) {
+            if let Some(url) = material["link"]["url"].as_str() {
+                description = format!("{}\n\n{}", description, url).trim().to_string();
+            } else if let Some(id) = material["youtubeVideo"]["id"].as_str() {
+                let url: String = format!("https://www.youtube.com/watch?v={}", id);
+                match video.is_empty() {
+                    true => video = url,
+                    false => description = format!("{}\n\n{}", description, url).trim().to_string(),
+                }
+            } else {
+                let kind: &str = if material["driveFile"].is_object() { "driveFile" } else { "form" };
+                let file: &Value = &material[kind];
+                let name: &str = file["driveFile"]["title"].as_str().or(file["title"].as_str()).unwrap_or(title);
+                builder.unmapped(name, kind, "Drive files and forms aren't supported");
+            }
+        }
+
+        // Add the lesson to the unit of the post's topic
+        let topic: &str = post["topics"][0]["name"].as_str().unwrap_or("General");
+        let unit: usize = match units.get(topic) {
+            Some(unit) => *unit,
+            None => {
+                let unit: usize = builder.unit(topic);
+                units.insert(topic.to_string(), unit);
+                unit
+            }
+        };
+        builder.lesson(unit, match kind {
+            "courseWork" => serde_json::json!({
+                "title": title,
+                "work": description,
+                "video": video,
+                "due_date": due_date(&post["courseWork"])
+            }),
+            _ => serde_json::json!({
+                "title": title,
+                "description": description,
+                "video": video
+            }),
+        });
+    }
+    return builder.finish(class["name"].as_str().unwrap_or(""));
+}
+
+// The due_date() function is used to get when the provided
+// coursework is due. 0 is returned if it has no due date.
+fn due_date(work: &Value) -> i64 {
+    let date: &Value = &work["dueDate"];
+    let (year, month, day) = match (date["year"].as_i64(), date["month"].as_i64(), date["day"].as_i64()) {
+        (Some(y), Some(m), Some(d)) => (y, m, d),
+        _ => return 0,
+    };
+
+    // Coursework without a due time is due at the end of the day.
+    // Google leaves out the fields of a due time that are zero.
+    let (hours, minutes): (i64, i64) = match work["dueTime"].is_object() {
+        true => (work["dueTime"]["hours"].as_i64().unwrap_or(0), work["dueTime"]["minutes"].as_i64().unwrap_or(0)),
+        false => (23, 59),
+    };
+    return days_from_civil(year, month, day) * 86400 + hours * 3600 + minutes * 60;
+}
+
+// The timestamp() function is used to convert an RFC 3339 date
+// (2021-09-01T14:00:00.000Z) into seconds since the unix epoch.
+// 0 is returned if the date can't be read.
+fn timestamp(date: &str) -> i64 {
+    let parse = |s: Option<&str>| -> Option<i64> { s?.parse().ok() };
+    let (day, time) = match date.split_once('T') {
+        Some(r) => r,
+        None => return 0,
+    };
+    let mut day = day.split('-');
+    let mut clock = time.get(..8).unwrap_or("").split(':');
+    let (year, month, day) = match (parse(day.next()), parse(day.next()), parse(day.next())) {
+        (Some(y), Some(m), Some(d)) => (y, m, d),
+        _ => return 0,
+    };
+    let seconds: i64 = match (parse(clock.next()), parse(clock.next()), parse(clock.next())) {
+        (Some(h), Some(m), Some(s)) => h * 3600 + m * 60 + s,
+        _ => return 0,
+    };
+
+    // Convert the time to UTC using it's offset (+hh:mm or -hh:mm)
+    let zone: &str = time.get(8..).unwrap_or("").trim_start_matches(|c: char| c == '.' || c.is_ascii_digit());
+    let offset: i64 = match zone.get(..1) {
+        Some(sign @ ("+" | "-")) => {
+            let hours: i64 = zone.get(1..3).and_then(|h| h.parse().ok()).unwrap_or(0);
+            let minutes: i64 = zone.get(4..6).and_then(|m| m.parse().ok()).unwrap_or(0);
+            (hours * 3600 + minutes * 60) * if sign == "-" { -1 } else { 1 }
+        }
+        _ => 0,
+    };
+    return days_from_civil(year, month, day) * 86400 + seconds - offset;
+}
+
+// The days_from_civil() function is used to get the number
+// of days between the unix epoch and the provided date.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year: i64 = if month <= 2 { year - 1 } else { year };
+    let era: i64 = if year >= 0 { year } else { year - 399 } / 400;
+    let year_of_era: i64 = year - era * 400;
+    let day_of_year: i64 = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day - 1;
+    let day_of_era: i64 = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    return era * 146097 + day_of_era - 719468;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn files(classes: &[(&str, Value)]) -> HashMap<String, Vec<u8>> {
+        return classes.iter().map(|(path, class)| (path.to_string(), class.to_string().into_bytes())).collect();
+    }
+
+    fn class() -> Value {
+        return json!({ "name": "Biology", "posts": [
+            {
+                "creationTime": "2021-09-02T14:00:00.000Z",
+                "topics": [{ "name": "Week 1" }],
+                "courseWork": { "title": "Lab", "description": "Write it up", "dueDate": { "year": 2021, "month": 9, "day": 10 } },
+                "materials": [
+                    { "youtubeVideo": { "id": "abc" } },
+                    { "link": { "url": "https://example.com" } },
+                    { "youtubeVideo": { "id": "def" } },
+                    { "driveFile": { "driveFile": { "title": "notes.pdf" } } }
+                ]
+            },
+            { "creationTime": "2021-09-01T14:00:00.000Z", "courseWorkMaterial": { "title": "Syllabus", "description": "Read me" } },
+            { "creationTime": "2021-09-03T14:00:00.000Z", "announcement": { "text": "Welcome!\nSee you soon" } },
+            { "creationTime": "2021-09-04T14:00:00.000Z", "state": "DRAFT", "courseWork": { "title": "Draft" } }
+        ] });
+    }
+
+    #[test]
+    fn converts_topics_into_units() {
+        let results: Vec<ImportResult> = convert(&files(&[("Takeout/Classroom/Biology.json", class())])).unwrap();
+        let bundle: &Value = &results[0].bundle;
+        assert_eq!(bundle["class"]["class_name"], "Biology");
+
+        // Posts are converted from oldest to newest
+        let units: &Vec<Value> = bundle["units"].as_array().unwrap();
+        assert_eq!(units.iter().map(|u| u["unit_name"].as_str().unwrap()).collect::<Vec<&str>>(), vec!["General", "Week 1"]);
+        assert_eq!(units[0]["lessons"][0]["description"], "Read me");
+
+        let lab: &Value = &units[1]["lessons"][0];
+        assert_eq!(lab["work"], "Write it up\n\nhttps://example.com\n\nhttps://www.youtube.com/watch?v=def");
+        assert_eq!(lab["video"], "https://www.youtube.com/watch?v=abc");
+        assert_eq!(lab["due_date"], timestamp("2021-09-10T23:59:00Z"));
+
+        assert_eq!(bundle["announcements"][0]["title"], "Welcome!");
+        assert_eq!(bundle["announcements"][0]["date"], timestamp("2021-09-03T14:00:00Z"));
+    }
+
+    #[test]
+    fn reports_drafts_and_drive_files() {
+        let results: Vec<ImportResult> = convert(&files(&[("Takeout/Classroom/Biology.json", class())])).unwrap();
+        let unmapped: Vec<(&str, &str)> = results[0].unmapped.iter()
+            .map(|u| (u["title"].as_str().unwrap(), u["type"].as_str().unwrap()))
+            .collect();
+        assert_eq!(unmapped, vec![("notes.pdf", "driveFile"), ("Draft", "courseWork")]);
+    }
+
+    #[test]
+    fn converts_every_class_in_order() {
+        let results: Vec<ImportResult> = convert(&files(&[
+            ("Takeout/Classroom/b.json", json!({ "name": "Second", "posts": [] })),
+            ("Takeout/Classroom/a.json", json!({ "name": "First", "posts": [] })),
+            ("Takeout/Classroom/c.json", json!({ "name": "Not a class" })),
+            ("Takeout/Drive/d.json", json!({ "name": "Elsewhere", "posts": [] }))
+        ])).unwrap();
+        assert_eq!(results.iter().map(|r| r.bundle["class"]["class_name"].as_str().unwrap()).collect::<Vec<&str>>(), vec!["First", "Second"]);
+        assert!(convert(&files(&[("Takeout/Drive/d.json", json!({ "name": "Elsewhere", "posts": [] }))])).is_none());
+    }
+
+    #[test]
+    fn reads_timestamps() {
+        assert_eq!(timestamp("1970-01-01T00:00:00Z"), 0);
+        assert_eq!(timestamp("2021-09-01T14:00:00.000Z"), 1630504800);
+        assert_eq!(timestamp("2021-09-01T16:00:00+02:00"), 1630504800);
+        assert_eq!(timestamp("2021-09-01T09:30:00.5-04:30"), 1630504800);
+        assert_eq!(timestamp("2021-09-01"), 0);
+        assert_eq!(timestamp("yesterday"), 0);
+    }
+
+    #[test]
+    fn reads_due_dates() {
+        assert_eq!(due_date(&json!({})), 0);
+        assert_eq!(due_date(&json!({ "dueDate": { "year": 2021, "month": 9, "day": 1 }, "dueTime": { "hours": 14 } })), 1630504800);
+        assert_eq!(due_date(&json!({ "dueDate": { "year": 2021, "month": 9, "day": 1 }, "dueTime": {} })), 1630454400);
+        assert_eq!(due_date(&json!({ "dueDate": { "year": 2020, "month": 2, "day": 29 } })), 1583020740);
+    }
+}
@@ -0,0 +1,252 @@
+// Library Usages
+use super::{handlers::Database, structs::Recipient, utils};
+use lettre::{
+    message::Mailbox, transport::smtp::authentication::Credentials,
+    AsyncFileTransport, AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+};
+use std::collections::HashSet;
+
+// The Template struct is used to store the subject
+// and body of an email. Any {key} within the subject
+// or body is replaced with it's value when rendered.
+pub struct Template {
+    pub subject: &'static str,
+    pub body: &'static str,
+}
+
+// The GRADED template is sent to a student
+// once their submission has been marked.
+pub static GRADED: Template = Template {
+    subject: "Your work in {class_name} has been marked",
+    body: "Hey {user_name},\n\nYour submission in {class_name} has been marked. \
+        Sign in to class.io to see your mark.\n",
+};
+
+// The ANNOUNCEMENT template is sent to the class's
+// students whenever a new announcement is posted.
+pub static ANNOUNCEMENT: Template = Template {
+    subject: "New announcement in {class_name}: {title}",
+    body: "Hey {user_name},\n\nA new announcement has been posted in {class_name}.\n\n\
+        {title}\n\n{description}\n",
+};
+
+// The DEADLINE template is sent to the class's students
+// when a lesson's due date is approaching.
+pub static DEADLINE: Template = Template {
+    subject: "{title} is due soon in {class_name}",
+    body: "Hey {user_name},\n\n{title} in {class_name} is due in {hours} hour(s). \
+        Don't forget to submit your work!\n",
+};
+
+// The Transport enum is used to determine how emails
+// are delivered. Smtp sends the email to an smtp server
+// (ex: MailHog), File writes each email to a .eml file
+// and Log prints each email to the console.
+#[derive(Clone)]
+enum Transport {
+    Smtp(AsyncSmtpTransport<Tokio1Executor>),
+    File(AsyncFileTransport<Tokio1Executor>),
+    Log,
+}
+
+// Mailer Struct for globalizing the
+// email transport and sender address
+#[derive(Clone)]
+pub struct Mailer {
+    transport: Transport,
+    from: Mailbox,
+}
+
+// Mailer Implementation that contains all the
+// functions for sending email notifications
+impl Mailer {
+    // Initialize a new mailer using the environment variables:
+    //
+    //      MAIL_TRANSPORT = "smtp" | "file" | "log" (default: "log")
+    //      MAIL_FROM = "class.io <noreply@classio.local>"
+    //      MAIL_DIR = "mail" (file transport only)
+    //      SMTP_HOST = "127.0.0.1", SMTP_PORT = 1025
+    //      SMTP_USERNAME, SMTP_PASSWORD (enables STARTTLS)
+    //
+    pub fn init() -> Self {
+        let env = |key: &str, default: &str| std::env::var(key).unwrap_or(default.to_string());
+
+        // Get the transport that the emails will be sent through
+        let transport: Transport = match env("MAIL_TRANSPORT", "log").as_str() {
+            "smtp" => {
+                let host: String = env("SMTP_HOST", "127.0.0.1");
+                let port: u16 = env("SMTP_PORT", "1025").parse().expect("Invalid SMTP_PORT");
+
+                // If credentials are provided, use an encrypted connection.
+                // Else, use a plain connection. (ex: a local MailHog server)
+                match (std::env::var("SMTP_USERNAME"), std::env::var("SMTP_PASSWORD")) {
+                    (Ok(username), Ok(password)) => Transport::Smtp(
+                        AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&host)
+                            .expect("Invalid SMTP_HOST")
+                            .port(port)
+                            .credentials(Credentials::new(username, password))
+                            .build(),
+                    ),
+                    _ => Transport::Smtp(
+                        AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&host)
+                            .port(port)
+                            .build(),
+                    ),
+                }
+            }
+            "file" => {
+                let dir: String = env("MAIL_DIR", "mail");
+                std::fs::create_dir_all(&dir).expect("Couldn't create MAIL_DIR");
+                Transport::File(AsyncFileTransport::<Tokio1Executor>::new(dir))
+            }
+            _ => Transport::Log,
+        };
+
+        // Return the new mailer
+        return Self {
+            transport,
+            from: env("MAIL_FROM", "class.io <noreply@classio.local>")
+                .parse()
+                .expect("Invalid MAIL_FROM"),
+        };
+    }
+
+    // The send() function is used to render the provided
+    // template with the provided values, then send it to
+    // the provided recipient. Returns whether the email was sent.
+    pub async fn send(&self, to: &Recipient, template: &Template, values: &[(&str, &str)]) -> bool {
+        // Render the template along with the recipients name
+        let subject: String = render(template.subject, &to.user_name, values);
+        let body: String = render(template.body, &to.user_name, values);
+
+        // Build the email
+        let mailbox: Mailbox = match to.email.parse() {
+            Ok(m) => m,
+            Err(_) => return false,
+        };
+        let message: Message = match Message::builder()
+            .from(self.from.clone())
+            .to(mailbox)
+            .subject(subject)
+            .body(body)
+        {
+            Ok(m) => m,
+            Err(_) => return false,
+        };
+
+        // Send the email through the transport
+        return match &self.transport {
+            Transport::Smtp(smtp) => smtp.send(message).await.is_ok(),
+            Transport::File(file) => file.send(message).await.is_ok(),
+            Transport::Log => {
+                println!("{}", String::from_utf8_lossy(&message.formatted()));
+                true
+            }
+        };
+    }
+
+    // The notify_graded() function is used to email the student
+    // who made the provided submission that their work has been
+    // marked, if they have grade notifications enabled.
+    pub async fn notify_graded(&self, db: &Database, class_id: &str, submission_id: &str) {
+        // Get the student who made the submission
+        let recipient: Recipient = match db.get_submission_recipient(class_id, submission_id).await {
+            Some(r) => r,
+            None => return,
+        };
+
+        // Check whether the student wants to be emailed
+        if db.get_notification_preferences(&recipient.user_id).await.email_grades != 1 {
+            return;
+        }
+
+        // Send the email
+        let class_name: String = class_name(db, class_id).await;
+        self.send(&recipient, &GRADED, &[("class_name", &class_name)]).await;
+    }
+
+    // The notify_announcement() function is used to email every
+    // student within the provided class that has announcement
+    // notifications enabled about a new announcement.
+    pub async fn notify_announcement(&self, db: &Database, class_id: &str, title: &str, description: &str) {
+        let class_name: String = class_name(db, class_id).await;
+
+        // Email each student within the class
+        for recipient in db.get_class_students(class_id).await {
+            if db.get_notification_preferences(&recipient.user_id).await.email_announcements != 1 {
+                continue;
+            }
+            self.send(&recipient, &ANNOUNCEMENT, &[
+                ("class_name", &class_name),
+                ("title", title),
+                ("description", description),
+            ]).await;
+        }
+    }
+
+    // The notify_deadlines() function is used to email the students
+    // of every lesson that is due within the next 24 hours. Each
+    // lesson's students are only reminded once. Students with their
+    // own due date are reminded of it instead of the lesson's.
+    pub async fn notify_deadlines(&self, db: &Database) {
+        let now: i64 = utils::get_time().as_secs() as i64;
+
+        // Email each student within the lesson's class
+        for lesson in db.get_due_lessons(now + 24 * 60 * 60).await {
+            let hours: String = ((lesson.due_date - now + 3599) / 3600).to_string();
+            let extended: HashSet<String> = db.get_extended_students(&lesson.lesson_id).await;
+            for recipient in db.get_class_students(&lesson.class_id).await {
+                if extended.contains(&recipient.user_id) {
+                    continue;
+                }
+                if db.get_notification_preferences(&recipient.user_id).await.email_deadlines != 1 {
+                    continue;
+                }
+                self.send(&recipient, &DEADLINE, &[
+                    ("class_name", &lesson.class_name),
+                    ("title", &lesson.title),
+                    ("hours", &hours),
+                ]).await;
+            }
+            db.set_deadline_reminded(&lesson.lesson_id).await;
+        }
+
+        // Email each student whose own due date is approaching
+        for extension in db.get_due_extensions(now + 24 * 60 * 60).await {
+            if db.get_notification_preferences(&extension.user_id).await.email_deadlines == 1 {
+                let hours: String = ((extension.due_date - now + 3599) / 3600).to_string();
+                let recipient: Recipient = Recipient {
+                    user_id: extension.user_id.clone(),
+                    user_name: extension.user_name,
+                    email: extension.email
+                };
+                self.send(&recipient, &DEADLINE, &[
+                    ("class_name", &extension.class_name),
+                    ("title", &extension.title),
+                    ("hours", &hours),
+                ]).await;
+            }
+            db.set_extension_reminded(&extension.lesson_id, &extension.user_id).await;
+        }
+    }
+}
+
+// The class_name() function is used to get the name
+// of the provided class for use within an email.
+async fn class_name(db: &Database, class_id: &str) -> String {
+    return match db.get_class_general_data(class_id).await {
+        Some(class) => class.class_name,
+        None => String::new(),
+    };
+}
+
+// The render() function is used to replace every {key}
+// within the provided text with it's value. The {user_name}
+// key is always replaced with the recipients name.
+fn render(text: &str, user_name: &str, values: &[(&str, &str)]) -> String {
+    let mut result: String = text.replace("{user_name}", user_name);
+    for (key, value) in values {
+        result = result.replace(&format!("{{{}}}", key), value);
+    }
+    return result;
+}
@@ -0,0 +1,215 @@
+// Library Usages
+use super::{auth, handlers::Database, http, ratelimit::{Limit, RateLimits}};
+use actix_web::{
+    body::EitherBody,
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    http::header::{HeaderMap, HeaderName, HeaderValue},
+    web, Error,
+};
+use futures::future::LocalBoxFuture;
+use std::future::{ready, Ready};
+use std::rc::Rc;
+
+// The ARCHIVE_EXEMPT_ROUTES are the class routes that can
+// still be used on an archived class, such as for
+// restoring the class, copying it into a new class or
+// opening it's event stream.
+static ARCHIVE_EXEMPT_ROUTES: [&str; 4] = ["archive", "restore", "clone", "events/token"];
+
+// The ArchivedClasses middleware is used to make archived
+// classes read-only. Any request that modifies an archived
+// class is rejected before it reaches the endpoint.
+pub struct ArchivedClasses;
+
+// Transform Implementation for creating the
+// ArchivedClasses middleware service
+impl<S: 'static, B> Transform<S, ServiceRequest> for ArchivedClasses
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Transform = ArchivedClassesMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(ArchivedClassesMiddleware { service: Rc::new(service) }))
+    }
+}
+
+// The ArchivedClassesMiddleware struct is the service
+// that wraps every endpoint
+pub struct ArchivedClassesMiddleware<S> {
+    service: Rc<S>,
+}
+
+// Service Implementation that contains the
+// function for checking each request
+impl<S: 'static, B> Service<ServiceRequest> for ArchivedClassesMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service: Rc<S> = self.service.clone();
+        Box::pin(async move {
+            // Get the class the request modifies, if any
+            let class_id: Option<String> = modified_class(&req);
+            let db: Option<web::Data<Database>> = req.app_data::<web::Data<Database>>().cloned();
+
+            // Reject the request if the class has been archived
+            if let (Some(class_id), Some(db)) = (class_id, db) {
+                if db.is_class_archived(&class_id).await {
+                    let response = http::response(
+                        http::Status::BAD_REQUEST,
+                        serde_json::json!({
+                            "response": "Class is archived"
+                        }),
+                    );
+                    return Ok(req.into_response(response).map_into_right_body());
+                }
+            }
+
+            // Otherwise, pass the request to the endpoint
+            let res: ServiceResponse<B> = service.call(req).await?;
+            return Ok(res.map_into_left_body());
+        })
+    }
+}
+
+// The modified_class() function is used to get the id of
+// the class that the provided request modifies. None is
+// returned for read requests, requests that don't target
+// a class and requests that are allowed on archived classes.
+fn modified_class(req: &ServiceRequest) -> Option<String> {
+    if req.method() == actix_web::http::Method::GET {
+        return None;
+    }
+
+    // Class routes are /class/{class_id}/...
+    let path: Vec<&str> = req.path().trim_matches('/').split('/').collect();
+    if path.len() < 2 || path[0] != "class" {
+        return None;
+    }
+
+    // Deleting the class, and the exempt routes are allowed
+    if path.len() == 2 && req.method() == actix_web::http::Method::DELETE {
+        return None;
+    }
+    if ARCHIVE_EXEMPT_ROUTES.contains(&path[2..].join("/").as_str()) {
+        return None;
+    }
+    return Some(path[1].to_string());
+}
+
+// The RateLimiting middleware is used to limit how many
+// requests each client can make to each route. Requests
+// over the limit are rejected with a 429 response. Every
+// response includes the client's RateLimit headers.
+pub struct RateLimiting(pub RateLimits);
+
+// Transform Implementation for creating the
+// RateLimiting middleware service
+impl<S: 'static, B> Transform<S, ServiceRequest> for RateLimiting
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Transform = RateLimitingMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RateLimitingMiddleware { service: Rc::new(service), limits: self.0.clone() }))
+    }
+}
+
+// The RateLimitingMiddleware struct is the service
+// that wraps every endpoint
+pub struct RateLimitingMiddleware<S> {
+    service: Rc<S>,
+    limits: RateLimits,
+}
+
+// Service Implementation that contains the
+// function for checking each request
+impl<S: 'static, B> Service<ServiceRequest> for RateLimitingMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service: Rc<S> = self.service.clone();
+        let limits: RateLimits = self.limits.clone();
+        Box::pin(async move {
+            // Take a token from the client's bucket for the route.
+            // Unknown routes share a single bucket.
+            let client: String = client(&req).await;
+            let route: String = req.match_pattern().unwrap_or_else(|| "*".to_string());
+            let limit: Limit = limits.take(&client, req.method().as_str(), &route);
+
+            // Reject the request if the bucket is empty
+            if !limit.allowed {
+                let mut response = http::response(
+                    http::Status::TOO_MANY_REQUESTS,
+                    serde_json::json!({
+                        "response": "Too many requests"
+                    }),
+                );
+                set_rate_limit_headers(response.headers_mut(), &limit);
+                response.headers_mut().insert(HeaderName::from_static("retry-after"), HeaderValue::from(limit.retry_after));
+                return Ok(req.into_response(response).map_into_right_body());
+            }
+
+            // Otherwise, pass the request to the endpoint
+            let mut res: ServiceResponse<B> = service.call(req).await?;
+            set_rate_limit_headers(res.headers_mut(), &limit);
+            return Ok(res.map_into_left_body());
+        })
+    }
+}
+
+// The client() function is used to get who the provided
+// request is from. Requests signed with the bearer of a known
+// user are limited by user, and every other request is limited
+// by it's ip address, so that sending someone else's bearer
+// can't use up their requests.
+async fn client(req: &ServiceRequest) -> String {
+    let header = |name: &str| -> &str { req.headers().get(name).and_then(|v| v.to_str().ok()).unwrap_or("") };
+    let bearer: &str = header("authorization");
+    if !bearer.is_empty() && auth::is_signed(bearer, header("access_token")) {
+        if let Some(db) = req.app_data::<web::Data<Database>>() {
+            if let Some(user_id) = db.get_user_id_by_bearer(bearer).await {
+                return format!("user:{}", user_id);
+            }
+        }
+    }
+    return match req.peer_addr() {
+        Some(addr) => format!("ip:{}", addr.ip()),
+        None => "ip:unknown".to_string(),
+    };
+}
+
+// The set_rate_limit_headers() function is used to add the
+// RateLimit headers for the provided limit to a response.
+fn set_rate_limit_headers(headers: &mut HeaderMap, limit: &Limit) {
+    headers.insert(HeaderName::from_static("ratelimit-limit"), HeaderValue::from(limit.limit));
+    headers.insert(HeaderName::from_static("ratelimit-remaining"), HeaderValue::from(limit.remaining));
+    headers.insert(HeaderName::from_static("ratelimit-reset"), HeaderValue::from(limit.reset));
+}
@@ -5,4 +5,14 @@ pub mod database;
 pub mod utils;
 pub mod structs;
 pub mod testing;
-pub mod http;
\ No newline at end of file
+pub mod http;
+pub mod mail;
+pub mod scheduler;
+pub mod events;
+pub mod quiz;
+
+pub mod middleware;
+pub mod bundle;
+pub mod imports;
+pub mod quotas;
+pub mod ratelimit;
\ No newline at end of file
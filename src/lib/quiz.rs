@@ -0,0 +1,407 @@
+// Library Usages
+use super::structs::BankQuestion;
+use serde_json::Value;
+use std::collections::HashSet;
+
+// HOW QUIZZES WORK:
+//
+//      A quiz lesson's work contains the questions:
+//
+//      { "questions": [{
+//          "question_id": "q1",
+//          "type": "multiple_choice" | "multi_select" | "numeric" | "short_answer",
+//          "prompt": "What is 2 + 2?",
+//          "options": ["3", "4"],              (multiple_choice and multi_select only)
+//          "points": 1                         (optional, defaults to 1)
+//      }] }
+//
+//      And the lesson's work_solutions contain the answer key:
+//
+//      {
+//          "q1": 1,                            (multiple_choice: the correct option index)
+//          "q2": [0, 2],                       (multi_select: every correct option index)
+//          "q3": { "value": 3.14, "tolerance": 0.01 },     (numeric)
+//          "q4": ["Ottawa", "ottawa, on"]      (short_answer: every accepted answer)
+//      }
+//
+//      A bank quiz's work instead contains how many questions to draw
+//      from the class's question bank for each unit:
+//
+//      { "draws": [{ "unit_id": "...", "count": 5 }] }
+//
+//      Each student is given their own draw, with the question and option
+//      order shuffled using the lesson and student as the seed.
+
+// The QUESTION_TYPES are the kinds of questions that can
+// be automatically graded.
+static QUESTION_TYPES: [&str; 4] = ["multiple_choice", "multi_select", "numeric", "short_answer"];
+
+// The QuizResult struct is used to store the mark a
+// student received on a quiz, along with whether
+// each of their answers was correct.
+pub struct QuizResult {
+    pub mark: f64,
+    pub out_of: f64,
+    pub results: Vec<Value>,
+}
+
+// The QuizSubmission struct is used to store who is
+// submitting their answers to which quiz lesson.
+pub struct QuizSubmission<'a> {
+    pub bearer: &'a str,
+    pub class_id: &'a str,
+    pub unit_id: &'a str,
+    pub lesson_id: &'a str,
+    pub submission_id: &'a str,
+    // Whether the bearer is the class owner
+    pub staff: bool,
+}
+
+// The is_quiz() function is used to check whether the provided
+// lesson work type is automatically graded, either as a quiz
+// or as a quiz drawn from the question bank.
+pub fn is_quiz(work_type: &str) -> bool {
+    return work_type == "quiz" || work_type == "bank";
+}
+
+// The get_questions() function is used to get the questions
+// within the provided quiz. None is returned if the
+// quiz isn't valid json or doesn't have any questions.
+pub fn get_questions(work: &str) -> Option<Vec<Value>> {
+    let work: Value = serde_json::from_str(work).ok()?;
+    let questions: &Vec<Value> = work["questions"].as_array()?;
+    if questions.is_empty() {
+        return None;
+    }
+    return Some(questions.clone());
+}
+
+// The is_valid() function is used to check whether the provided
+// questions and answer key can be graded. Every question needs
+// a unique id, a known type and a valid answer within the key.
+pub fn is_valid(questions: &[Value], key: &Value) -> bool {
+    let mut question_ids: HashSet<&str> = HashSet::new();
+    return questions.iter().all(|q| {
+        let question_id: &str = match q["question_id"].as_str() {
+            Some(id) if question_ids.insert(id) => id,
+            _ => return false,
+        };
+        let kind: &str = q["type"].as_str().unwrap_or("");
+        if !QUESTION_TYPES.contains(&kind) || q["points"].as_f64().is_some_and(|p| p <= 0.0) {
+            return false;
+        }
+        is_valid_answer(kind, q["options"].as_array().map_or(0, |o| o.len()), &key[question_id])
+    });
+}
+
+// The is_valid_answer() function is used to check whether the
+// answer key for a single question matches the question's type.
+fn is_valid_answer(kind: &str, options: usize, answer: &Value) -> bool {
+    return match kind {
+        "multiple_choice" => answer.as_u64().is_some_and(|i| (i as usize) < options),
+        "multi_select" => answer.as_array().is_some_and(|a| {
+            !a.is_empty() && a.iter().all(|i| i.as_u64().is_some_and(|i| (i as usize) < options))
+        }),
+        "numeric" => answer.as_f64().is_some() || answer["value"].as_f64().is_some(),
+        "short_answer" => answer.as_str().is_some()
+            || answer.as_array().is_some_and(|a| !a.is_empty() && a.iter().all(|s| s.as_str().is_some())),
+        _ => false,
+    };
+}
+
+// The grade() function is used to mark the student's answers
+// against the answer key. Each question is worth it's points,
+// and only fully correct answers receive them.
+pub fn grade(questions: &[Value], key: &Value, answers: &Value) -> QuizResult {
+    let mut result: QuizResult = QuizResult {
+        mark: 0.0,
+        out_of: 0.0,
+        results: Vec::new(),
+    };
+    for q in questions {
+        let question_id: &str = q["question_id"].as_str().unwrap_or("");
+        let points: f64 = q["points"].as_f64().unwrap_or(1.0);
+        let kind: &str = q["type"].as_str().unwrap_or("");
+
+        // Check the student's answer
+        let correct: bool = is_correct(kind, &key[question_id], &answers[question_id]);
+        if correct {
+            result.mark += points;
+        }
+        result.out_of += points;
+        result.results.push(serde_json::json!({
+            "question_id": question_id,
+            "correct": correct
+        }));
+    }
+    return result;
+}
+
+// The is_correct() function is used to check whether the
+// student's answer to a single question is correct.
+fn is_correct(kind: &str, key: &Value, answer: &Value) -> bool {
+    return match kind {
+        "multiple_choice" => answer.as_u64().is_some() && answer.as_u64() == key.as_u64(),
+        "multi_select" => {
+            let indices = |v: &Value| -> Option<HashSet<u64>> {
+                v.as_array()?.iter().map(|i| i.as_u64()).collect()
+            };
+            indices(answer).is_some() && indices(answer) == indices(key)
+        }
+        "numeric" => {
+            let value: f64 = key.as_f64().or(key["value"].as_f64()).unwrap_or(f64::NAN);
+            let tolerance: f64 = key["tolerance"].as_f64().unwrap_or(0.0).abs();
+            answer.as_f64().is_some_and(|a| (a - value).abs() <= tolerance)
+        }
+        "short_answer" => {
+            let answer: String = match answer.as_str() {
+                Some(a) => normalize(a),
+                None => return false,
+            };
+            match key.as_array() {
+                Some(accepted) => accepted.iter().any(|k| k.as_str().map(normalize) == Some(answer.clone())),
+                None => key.as_str().map(normalize) == Some(answer),
+            }
+        }
+        _ => false,
+    };
+}
+
+// The normalize() function is used to compare short answers
+// without caring about case or surrounding whitespace.
+fn normalize(answer: &str) -> String {
+    return answer.split_whitespace().collect::<Vec<&str>>().join(" ").to_lowercase();
+}
+
+// The Shuffler struct is used to shuffle the questions and
+// options of a drawn quiz. The same seed always produces
+// the same order, so every draw can be reproduced.
+struct Shuffler {
+    state: u64,
+}
+
+// Shuffler Implementation that contains the
+// functions for deterministically shuffling
+impl Shuffler {
+    // Initialize a new shuffler using the
+    // sha256 hash of the provided seed
+    fn new(seed: &str) -> Self {
+        let state: u64 = u64::from_str_radix(&sha256::digest(seed)[..16], 16).unwrap_or(0);
+        return Shuffler { state };
+    }
+
+    // The next() function is used to generate the
+    // next pseudo random number (splitmix64)
+    fn next(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z: u64 = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        return z ^ (z >> 31);
+    }
+
+    // The shuffle() function is used to shuffle the
+    // provided items in place (Fisher-Yates)
+    fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j: usize = (self.next() % (i as u64 + 1)) as usize;
+            items.swap(i, j);
+        }
+    }
+}
+
+// The get_draws() function is used to get how many questions
+// a bank quiz draws from each unit. None is returned if the
+// quiz isn't valid json or doesn't draw any questions.
+pub fn get_draws(work: &str) -> Option<Vec<(String, usize)>> {
+    let work: Value = serde_json::from_str(work).ok()?;
+    let draws: Vec<(String, usize)> = work["draws"].as_array()?.iter().map(|d| {
+        let unit_id: &str = d["unit_id"].as_str()?;
+        let count: u64 = d["count"].as_u64().filter(|c| *c > 0)?;
+        Some((unit_id.to_string(), count as usize))
+    }).collect::<Option<Vec<(String, usize)>>>()?;
+    if draws.is_empty() {
+        return None;
+    }
+    return Some(draws);
+}
+
+// The draw() function is used to draw a student's questions from
+// the question bank. The provided number of questions is drawn
+// for each unit, then the questions and each question's options
+// are shuffled. The answer key is remapped to the shuffled options.
+// The drawn questions and their answer key are returned. None is
+// returned if a unit doesn't have enough questions to draw from.
+pub fn draw(bank: &[BankQuestion], draws: &[(String, usize)], seed: &str) -> Option<(Vec<Value>, Value)> {
+    let mut shuffler: Shuffler = Shuffler::new(seed);
+
+    // Draw the questions for each unit
+    let mut drawn: Vec<&BankQuestion> = Vec::new();
+    for (unit_id, count) in draws {
+        let mut pool: Vec<&BankQuestion> = bank.iter().filter(|q| &q.unit_id == unit_id).collect();
+        pool.sort_by(|a, b| a.question_id.cmp(&b.question_id));
+        if pool.len() < *count {
+            return None;
+        }
+        shuffler.shuffle(&mut pool);
+        drawn.extend(pool.into_iter().take(*count));
+    }
+    if drawn.is_empty() {
+        return None;
+    }
+    shuffler.shuffle(&mut drawn);
+
+    // Shuffle each question's options and remap it's answer
+    let mut questions: Vec<Value> = Vec::new();
+    let mut key: serde_json::Map<String, Value> = serde_json::Map::new();
+    for q in drawn {
+        let mut question: Value = serde_json::from_str(&q.question).ok()?;
+        let mut answer: Value = serde_json::from_str(&q.answer).ok()?;
+        question["question_id"] = Value::from(q.question_id.as_str());
+
+        // Shuffle the options, if the question has any
+        if let Some(options) = question["options"].as_array().cloned() {
+            let mut order: Vec<usize> = (0..options.len()).collect();
+            Shuffler::new(&format!("{}:{}", seed, q.question_id)).shuffle(&mut order);
+            question["options"] = Value::from(order.iter().map(|i| options[*i].clone()).collect::<Vec<Value>>());
+
+            // The answer's option indices now point to the shuffled options
+            let position = |i: &Value| -> Value {
+                match i.as_u64().and_then(|i| order.iter().position(|o| *o as u64 == i)) {
+                    Some(p) => Value::from(p),
+                    None => Value::Null,
+                }
+            };
+            answer = match answer.as_array() {
+                Some(indices) => Value::from(indices.iter().map(position).collect::<Vec<Value>>()),
+                None => position(&answer),
+            };
+        }
+        key.insert(q.question_id.clone(), answer);
+        questions.push(question);
+    }
+    return Some((questions, Value::Object(key)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn questions() -> Vec<Value> {
+        return vec![
+            json!({ "question_id": "q1", "type": "multiple_choice", "options": ["3", "4"] }),
+            json!({ "question_id": "q2", "type": "multi_select", "options": ["1", "2", "4"], "points": 2 }),
+            json!({ "question_id": "q3", "type": "numeric" }),
+            json!({ "question_id": "q4", "type": "short_answer" })
+        ];
+    }
+
+    fn key() -> Value {
+        return json!({
+            "q1": 1,
+            "q2": [1, 2],
+            "q3": { "value": 9.81, "tolerance": 0.01 },
+            "q4": ["Ottawa", "ottawa, on"]
+        });
+    }
+
+    fn correct(result: &QuizResult) -> Vec<bool> {
+        return result.results.iter().map(|r| r["correct"].as_bool().unwrap()).collect();
+    }
+
+    #[test]
+    fn grades_correct_answers() {
+        let answers: Value = json!({ "q1": 1, "q2": [2, 1], "q3": 9.815, "q4": "  OTTAWA,   on " });
+        let result: QuizResult = grade(&questions(), &key(), &answers);
+        assert_eq!(result.mark, 5.0);
+        assert_eq!(result.out_of, 5.0);
+        assert_eq!(correct(&result), vec![true, true, true, true]);
+    }
+
+    #[test]
+    fn only_fully_correct_answers_get_points() {
+        let answers: Value = json!({ "q1": 0, "q2": [1], "q3": 9.9, "q4": "Toronto" });
+        let result: QuizResult = grade(&questions(), &key(), &answers);
+        assert_eq!(result.mark, 0.0);
+        assert_eq!(result.out_of, 5.0);
+        assert_eq!(correct(&result), vec![false, false, false, false]);
+    }
+
+    #[test]
+    fn missing_and_mistyped_answers_are_wrong() {
+        let answers: Value = json!({ "q1": "1", "q2": [1, "2"], "q4": 4 });
+        let result: QuizResult = grade(&questions(), &key(), &answers);
+        assert_eq!(result.mark, 0.0);
+        assert_eq!(correct(&result), vec![false, false, false, false]);
+    }
+
+    #[test]
+    fn numeric_answers_without_a_tolerance_must_match() {
+        let questions: Vec<Value> = vec![json!({ "question_id": "q1", "type": "numeric" })];
+        assert_eq!(grade(&questions, &json!({ "q1": 2.5 }), &json!({ "q1": 2.5 })).mark, 1.0);
+        assert_eq!(grade(&questions, &json!({ "q1": 2.5 }), &json!({ "q1": 2.51 })).mark, 0.0);
+    }
+
+    #[test]
+    fn validates_answer_keys() {
+        assert!(is_valid(&questions(), &key()));
+        assert!(!is_valid(&questions(), &json!({ "q1": 2, "q2": [1], "q3": 1, "q4": "a" })));
+        let duplicated: Vec<Value> = vec![questions()[0].clone(), questions()[0].clone()];
+        assert!(!is_valid(&duplicated, &key()));
+    }
+
+    fn bank() -> Vec<BankQuestion> {
+        return (0..6).map(|i| BankQuestion {
+            question_id: format!("b{}", i),
+            unit_id: String::from(if i < 4 { "a" } else { "b" }),
+            question: json!({ "type": "multiple_choice", "options": ["w", "x", "y", "z"] }).to_string(),
+            answer: json!(i % 4).to_string()
+        }).collect();
+    }
+
+    #[test]
+    fn shuffler_is_deterministic() {
+        let shuffled = |seed: &str| -> Vec<u32> {
+            let mut items: Vec<u32> = (0..20).collect();
+            Shuffler::new(seed).shuffle(&mut items);
+            items
+        };
+        assert_eq!(shuffled("lesson:student"), shuffled("lesson:student"));
+        assert_ne!(shuffled("lesson:student"), shuffled("lesson:other"));
+
+        let mut sorted: Vec<u32> = shuffled("lesson:student");
+        sorted.sort();
+        assert_eq!(sorted, (0..20).collect::<Vec<u32>>());
+    }
+
+    #[test]
+    fn draws_are_deterministic() {
+        let draws: Vec<(String, usize)> = vec![(String::from("a"), 2), (String::from("b"), 1)];
+        let (questions, key) = draw(&bank(), &draws, "lesson:student").unwrap();
+        assert_eq!(draw(&bank(), &draws, "lesson:student"), Some((questions.clone(), key.clone())));
+        assert_eq!(questions.len(), 3);
+
+        // The answer key still points at the original correct option
+        for q in &questions {
+            let question_id: &str = q["question_id"].as_str().unwrap();
+            let original: usize = question_id[1..].parse::<usize>().unwrap() % 4;
+            let answer: usize = key[question_id].as_u64().unwrap() as usize;
+            assert_eq!(q["options"][answer], json!(["w", "x", "y", "z"][original]));
+        }
+    }
+
+    #[test]
+    fn draws_only_come_from_the_provided_units() {
+        let draws: Vec<(String, usize)> = vec![(String::from("b"), 2)];
+        let (questions, _) = draw(&bank(), &draws, "lesson:student").unwrap();
+        assert_eq!(questions.len(), 2);
+        assert!(draw(&bank(), &[(String::from("c"), 1)], "lesson:student").is_none());
+    }
+
+    #[test]
+    fn draws_need_enough_questions() {
+        assert!(draw(&bank(), &[(String::from("b"), 3)], "lesson:student").is_none());
+        assert!(draw(&bank(), &[(String::from("a"), 4), (String::from("b"), 3)], "lesson:student").is_none());
+    }
+}
@@ -0,0 +1,167 @@
+use crate::lib::{bundle, http, structs::Lesson};
+use futures::lock::{Mutex, OwnedMutexGuard};
+use std::collections::HashMap;
+use std::sync::{Arc, PoisonError};
+
+// The Quotas struct is used to store the limits on how
+// much a user can create. A limit of 0 disables the quota.
+#[derive(Clone)]
+pub struct Quotas {
+    pub classes_per_user: i64,
+    pub units_per_class: i64,
+    pub lessons_per_unit: i64,
+    pub storage_per_class: i64,
+    pub students_per_class: i64,
+    pub comment_storage_per_user: i64,
+    locks: Arc<std::sync::Mutex<HashMap<String, Arc<Mutex<()>>>>>,
+}
+
+// Quotas Implementation that contains the
+// functions for checking the quotas
+impl Quotas {
+    // Initialize the quotas using the environment variables:
+    //
+    //      QUOTA_CLASSES_PER_USER = 5
+    //      QUOTA_UNITS_PER_CLASS = 12
+    //      QUOTA_LESSONS_PER_UNIT = 50
+    //      QUOTA_STORAGE_PER_CLASS = 67108864 (bytes, 64MB)
+    //      QUOTA_STUDENTS_PER_CLASS = 200
+    //      QUOTA_COMMENT_STORAGE_PER_USER = 1048576 (bytes, 1MB)
+    //
+    pub fn init() -> Self {
+        let env = |key: &str, default: i64| -> i64 {
+            match std::env::var(key) {
+                Ok(v) => v.parse().unwrap_or_else(|_| panic!("Invalid {}", key)),
+                Err(_) => default,
+            }
+        };
+        return Self {
+            classes_per_user: env("QUOTA_CLASSES_PER_USER", 5),
+            units_per_class: env("QUOTA_UNITS_PER_CLASS", 12),
+            lessons_per_unit: env("QUOTA_LESSONS_PER_UNIT", 50),
+            storage_per_class: env("QUOTA_STORAGE_PER_CLASS", 64 * 1024 * 1024),
+            students_per_class: env("QUOTA_STUDENTS_PER_CLASS", 200),
+            comment_storage_per_user: env("QUOTA_COMMENT_STORAGE_PER_USER", 1024 * 1024),
+            locks: Arc::new(std::sync::Mutex::new(HashMap::new())),
+        };
+    }
+
+    // The lock() function is used to wait for any other quota
+    // check on the provided class or user to finish. The guard should
+    // be held from checking the quota until the content has been
+    // inserted, so that two requests can't both fit under the same
+    // limit. Quotas of different classes and users aren't held up
+    // by each other.
+    pub async fn lock(&self, key: &str) -> OwnedMutexGuard<()> {
+        let lock: Arc<Mutex<()>> = {
+            let mut locks = self.locks.lock().unwrap_or_else(PoisonError::into_inner);
+
+            // Forget the locks that aren't being held or waited on
+            locks.retain(|_, lock| Arc::strong_count(lock) > 1);
+            locks.entry(key.to_string()).or_default().clone()
+        };
+        return lock.lock_owned().await;
+    }
+
+    // The check_bundle() function is used to check whether
+    // importing the provided class bundle would exceed the
+    // class's unit, lesson or storage quotas.
+    pub fn check_bundle(&self, bundle: &serde_json::Value) -> Result<(), Box<actix_web::HttpResponse>> {
+        let units: &[serde_json::Value] = bundle::array(bundle, "units");
+        check("units_per_class", self.units_per_class, 0, units.len() as i64)?;
+        for unit in units {
+            check("lessons_per_unit", self.lessons_per_unit, 0, bundle::array(unit, "lessons").len() as i64)?;
+        }
+        return check("storage_per_class", self.storage_per_class, 0, bundle.to_string().len() as i64);
+    }
+}
+
+// The lesson_growth() function is used to get how many bytes
+// updating the provided lesson with the request body would add
+// to the class's storage. Fields that aren't provided keep their
+// current size, and shrinking the lesson returns a negative size.
+pub fn lesson_growth(lesson: &Lesson, data: &serde_json::Value) -> i64 {
+    let fields: [(&str, &String); 5] = [
+        ("title", &lesson.title),
+        ("description", &lesson.description),
+        ("video", &lesson.video),
+        ("work", &lesson.work),
+        ("work_solutions", &lesson.work_solutions),
+    ];
+    return fields.iter().map(|(key, current)| {
+        data[*key].as_str().map_or(0, |new| new.len() as i64 - current.len() as i64)
+    }).sum();
+}
+
+// The check() function is used to check whether adding to the
+// provided amount would exceed the quota's limit. If it would,
+// a 409 response describing the quota is returned. The response
+// is boxed to keep the result small.
+pub fn check(quota: &str, limit: i64, used: i64, added: i64) -> Result<(), Box<actix_web::HttpResponse>> {
+    if limit <= 0 || used + added <= limit {
+        return Ok(());
+    }
+    let message: String = match quota {
+        "classes_per_user" => format!("A user can't own more than {} classes", limit),
+        "units_per_class" => format!("A class can't have more than {} units", limit),
+        "lessons_per_unit" => format!("A unit can't have more than {} lessons", limit),
+        "storage_per_class" => format!("A class can't store more than {} bytes", limit),
+        "students_per_class" => format!("A class can't have more than {} students", limit),
+        "comment_storage_per_user" => format!("A user's comments in a class can't take up more than {} bytes", limit),
+        _ => format!("The {} quota is {}", quota, limit),
+    };
+    return Err(Box::new(http::response(
+        http::Status::CONFLICT,
+        serde_json::json!({
+            "response": "Quota exceeded",
+            "quota": quota,
+            "limit": limit,
+            "message": message
+        }),
+    )));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_the_change_in_lesson_size() {
+        let lesson: Lesson = Lesson {
+            lesson_id: String::from("lesson"),
+            title: String::from("Lesson"),
+            description: String::from("Read the chapter"),
+            video: String::new(),
+            work: String::from("Summarize it"),
+            work_type: String::from("text"),
+            work_solutions: String::new(),
+            due_date: 0,
+            solutions_release: String::from("manual"),
+            solutions_released: 0,
+            opens_at: 0,
+            closes_at: 0,
+            time_limit: 0,
+            category_id: String::new(),
+            max_attempts: 1
+        };
+        assert_eq!(lesson_growth(&lesson, &serde_json::json!({ "due_date": 100 })), 0);
+        assert_eq!(lesson_growth(&lesson, &serde_json::json!({ "title": "Lesson 1" })), 2);
+        assert_eq!(lesson_growth(&lesson, &serde_json::json!({ "description": "", "work_solutions": "abc" })), -13);
+    }
+
+    #[test]
+    fn locks_each_key_separately() {
+        futures::executor::block_on(async {
+            let quotas: Quotas = Quotas::init();
+            let first = quotas.lock("class").await;
+            let other = quotas.lock("other").await;
+            assert!(quotas.locks.lock().unwrap()["class"].try_lock().is_none());
+            drop(first);
+            drop(other);
+
+            // Locks that aren't held anymore are forgotten
+            let _third = quotas.lock("third").await;
+            assert_eq!(quotas.locks.lock().unwrap().keys().collect::<Vec<&String>>(), ["third"]);
+        });
+    }
+}
@@ -0,0 +1,268 @@
+// Library Usages
+use std::collections::{BTreeSet, HashMap};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+// HOW RATE LIMITS WORK:
+//
+//      Every client has a bucket of tokens for each route. Each
+//      request takes a token from the bucket, and the bucket
+//      refills at a steady rate up to it's capacity. A request
+//      is rejected once it's bucket is empty.
+//
+//      Clients are the signed in user, or the ip address of
+//      requests without a valid access token. Reads are given larger
+//      budgets than writes, and the routes that do a lot of work,
+//      such as imports and exports, are given the smallest.
+
+// The Budget struct is used to store how many requests
+// can be made at once (the bucket's capacity), and how
+// many requests are allowed each minute after that.
+#[derive(Clone, Copy)]
+pub struct Budget {
+    pub capacity: u32,
+    pub per_minute: u32,
+}
+
+// The READ_BUDGET and WRITE_BUDGET are the budgets of
+// every route that doesn't have it's own budget.
+static READ_BUDGET: Budget = Budget { capacity: 120, per_minute: 120 };
+static WRITE_BUDGET: Budget = Budget { capacity: 30, per_minute: 30 };
+
+// The ROUTE_BUDGETS are the budgets of the routes that are
+// used more often, or that are more expensive, than others.
+static ROUTE_BUDGETS: [(&str, &str, Budget); 9] = [
+    ("POST", "/class/{class_id}/units/{unit_id}/lessons/{lesson_id}/progress", Budget { capacity: 60, per_minute: 60 }),
+    ("GET", "/class/{class_id}/events", Budget { capacity: 10, per_minute: 10 }),
+    ("GET", "/class/{class_id}/export", Budget { capacity: 5, per_minute: 5 }),
+    ("GET", "/class/{class_id}/deletion_token", Budget { capacity: 5, per_minute: 5 }),
+    ("POST", "/class/{class_id}/clone", Budget { capacity: 5, per_minute: 5 }),
+    ("PUT", "/class/import", Budget { capacity: 3, per_minute: 3 }),
+    ("PUT", "/class/import/archive", Budget { capacity: 3, per_minute: 3 }),
+    ("PUT", "/class/import/takeout", Budget { capacity: 3, per_minute: 3 }),
+    ("PUT", "/users", Budget { capacity: 5, per_minute: 5 }),
+];
+
+// The MAX_BUCKETS is how many buckets are kept before
+// the least recently used buckets are removed.
+static MAX_BUCKETS: usize = 10000;
+
+// The Bucket struct is used to store the tokens
+// left for a single client and route.
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+    budget: Budget,
+}
+
+// The Limit struct is used to store the result of taking
+// a token, which is sent back in the RateLimit headers.
+pub struct Limit {
+    pub allowed: bool,
+    pub limit: u32,
+    pub remaining: u32,
+    // Seconds until the bucket is full again
+    pub reset: u64,
+    // Seconds until the next token is available
+    pub retry_after: u64,
+}
+
+// The Buckets struct is used to store every client's
+// token buckets, along with the order they were last
+// used in so that the least recently used can be removed.
+#[derive(Default)]
+struct Buckets {
+    buckets: HashMap<String, Bucket>,
+    recent: BTreeSet<(Instant, String)>,
+}
+
+// RateLimits Struct for globalizing every
+// client's token buckets across workers
+#[derive(Clone, Default)]
+pub struct RateLimits {
+    buckets: Arc<Mutex<Buckets>>,
+}
+
+// Bucket Implementation that contains the
+// functions for refilling a bucket
+impl Bucket {
+    // The refill() function is used to add the tokens
+    // earned since the bucket was last updated.
+    fn refill(&mut self, now: Instant) {
+        let minutes: f64 = now.duration_since(self.updated).as_secs_f64() / 60.0;
+        self.tokens = (self.tokens + minutes * self.budget.per_minute as f64).min(self.budget.capacity as f64);
+        self.updated = now;
+    }
+
+    // The seconds_until() function is used to get how many
+    // seconds it'll take for the bucket to have the provided
+    // number of tokens.
+    fn seconds_until(&self, tokens: f64) -> u64 {
+        let missing: f64 = (tokens - self.tokens).max(0.0);
+        return (missing * 60.0 / self.budget.per_minute as f64).ceil() as u64;
+    }
+}
+
+// RateLimits Implementation that contains
+// the functions for taking tokens
+impl RateLimits {
+    // The take() function is used to take a token from the
+    // provided client's bucket for the provided route. The
+    // request should be rejected if it isn't allowed.
+    pub fn take(&self, client: &str, method: &str, route: &str) -> Limit {
+        return self.take_at(client, method, route, Instant::now());
+    }
+
+    // The take_at() function is used to take a token from the
+    // provided client's bucket for the provided route at the
+    // provided time.
+    fn take_at(&self, client: &str, method: &str, route: &str, now: Instant) -> Limit {
+        let budget: Budget = budget(method, route);
+
+        // Lock the buckets so we can access them. If the lock
+        // has been poisoned, let the request through.
+        let mut buckets = match self.buckets.lock() {
+            Ok(buckets) => buckets,
+            Err(_) => return Limit {
+                allowed: true,
+                limit: budget.capacity,
+                remaining: budget.capacity,
+                reset: 0,
+                retry_after: 0,
+            },
+        };
+
+        // Get the client's bucket for the route. New clients
+        // start with a full bucket.
+        let key: String = format!("{} {} {}", client, method, route);
+        let mut bucket: Bucket = match buckets.buckets.remove(&key) {
+            Some(bucket) => {
+                buckets.recent.remove(&(bucket.updated, key.clone()));
+                bucket
+            }
+            None => Bucket { tokens: budget.capacity as f64, updated: now, budget },
+        };
+
+        // Remove the least recently used buckets to make room
+        while buckets.buckets.len() >= MAX_BUCKETS {
+            match buckets.recent.pop_first() {
+                Some((_, oldest)) => buckets.buckets.remove(&oldest),
+                None => break,
+            };
+        }
+
+        // Take a token from the bucket
+        bucket.refill(now);
+        let allowed: bool = bucket.tokens >= 1.0;
+        if allowed {
+            bucket.tokens -= 1.0;
+        }
+        let limit: Limit = Limit {
+            allowed,
+            limit: budget.capacity,
+            remaining: bucket.tokens.floor() as u32,
+            reset: bucket.seconds_until(budget.capacity as f64),
+            retry_after: bucket.seconds_until(1.0),
+        };
+
+        // Put the bucket back as the most recently used
+        buckets.recent.insert((now, key.clone()));
+        buckets.buckets.insert(key, bucket);
+        return limit;
+    }
+}
+
+// The budget() function is used to get the budget of the
+// provided route. Reads and writes without their own
+// budget use the READ_BUDGET and WRITE_BUDGET.
+fn budget(method: &str, route: &str) -> Budget {
+    if let Some((_, _, budget)) = ROUTE_BUDGETS.iter().find(|(m, r, _)| *m == method && *r == route) {
+        return *budget;
+    }
+    return match method {
+        "GET" | "HEAD" | "OPTIONS" => READ_BUDGET,
+        _ => WRITE_BUDGET,
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn take_all(limits: &RateLimits, client: &str, now: Instant) -> Limit {
+        let mut limit: Limit = limits.take_at(client, "PUT", "/class", now);
+        while limit.allowed {
+            limit = limits.take_at(client, "PUT", "/class", now);
+        }
+        return limit;
+    }
+
+    #[test]
+    fn uses_the_route_budget() {
+        assert_eq!(budget("PUT", "/class/import").capacity, 3);
+        assert_eq!(budget("GET", "/class/{class_id}").capacity, READ_BUDGET.capacity);
+        assert_eq!(budget("DELETE", "/class/{class_id}").capacity, WRITE_BUDGET.capacity);
+    }
+
+    #[test]
+    fn rejects_requests_once_the_bucket_is_empty() {
+        let limits: RateLimits = RateLimits::default();
+        let now: Instant = Instant::now();
+        for remaining in (0..WRITE_BUDGET.capacity).rev() {
+            let limit: Limit = limits.take_at("a", "PUT", "/class", now);
+            assert!(limit.allowed);
+            assert_eq!(limit.remaining, remaining);
+        }
+        let limit: Limit = limits.take_at("a", "PUT", "/class", now);
+        assert!(!limit.allowed);
+        assert_eq!(limit.limit, WRITE_BUDGET.capacity);
+
+        // 30 requests each minute is a token every 2 seconds
+        assert_eq!(limit.retry_after, 2);
+        assert_eq!(limit.reset, 60);
+
+        // Other clients and routes have their own buckets
+        assert!(limits.take_at("b", "PUT", "/class", now).allowed);
+        assert!(limits.take_at("a", "GET", "/class", now).allowed);
+    }
+
+    #[test]
+    fn refills_at_the_budget_rate() {
+        let limits: RateLimits = RateLimits::default();
+        let now: Instant = Instant::now();
+        take_all(&limits, "a", now);
+
+        // Half a minute earns half of the minute's tokens
+        let limit: Limit = limits.take_at("a", "PUT", "/class", now + Duration::from_secs(30));
+        assert!(limit.allowed);
+        assert_eq!(limit.remaining, 14);
+        assert_eq!(limit.reset, 32);
+
+        // The bucket doesn't fill past it's capacity
+        let limit: Limit = limits.take_at("a", "PUT", "/class", now + Duration::from_secs(600));
+        assert_eq!(limit.remaining, WRITE_BUDGET.capacity - 1);
+        assert_eq!(limit.retry_after, 0);
+    }
+
+    #[test]
+    fn removes_the_least_recently_used_buckets() {
+        let limits: RateLimits = RateLimits::default();
+        let now: Instant = Instant::now();
+        take_all(&limits, "first", now);
+        take_all(&limits, "second", now);
+        limits.take_at("first", "PUT", "/class", now + Duration::from_millis(1));
+        for i in 0..MAX_BUCKETS - 2 {
+            limits.take_at(&i.to_string(), "GET", "/class", now + Duration::from_millis(2));
+        }
+        assert_eq!(limits.buckets.lock().unwrap().buckets.len(), MAX_BUCKETS);
+
+        // The second client's bucket was used least recently, so
+        // it's removed to make room for a new client's bucket
+        let later: Instant = now + Duration::from_millis(3);
+        assert!(limits.take_at("third", "PUT", "/class", later).allowed);
+        assert!(!limits.take_at("first", "PUT", "/class", later).allowed);
+        assert!(limits.take_at("second", "PUT", "/class", later).allowed);
+        assert_eq!(limits.buckets.lock().unwrap().buckets.len(), MAX_BUCKETS);
+    }
+}
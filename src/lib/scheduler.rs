@@ -0,0 +1,53 @@
+// Library Usages
+use super::{handlers::Database, mail::Mailer, events::Events};
+use std::time::Duration;
+
+// The INTERVAL is how often the scheduler
+// checks for any work that has come due.
+static INTERVAL: Duration = Duration::from_secs(60);
+
+// The start() function is used to spawn the background
+// task that handles all the time based work, such as
+// reminding students of approaching deadlines.
+pub fn start(db: Database, mailer: Mailer, events: Events) {
+    actix_web::rt::spawn(async move {
+        let mut interval = actix_web::rt::time::interval(INTERVAL);
+        loop {
+            interval.tick().await;
+
+            // Publish any scheduled announcements that have come due
+            publish_announcements(&db, &mailer, &events).await;
+
+            // Notify students of any units that have unlocked
+            unlock_units(&db, &events).await;
+
+            // Remind students of any approaching deadlines
+            mailer.notify_deadlines(&db).await;
+        }
+    });
+}
+
+// The publish_announcements() function is used to publish
+// every scheduled announcement that has come due, then notify
+// the class's students just like a regular announcement.
+async fn publish_announcements(db: &Database, mailer: &Mailer, events: &Events) {
+    for a in db.publish_due_announcements().await {
+        events.publish(&a.class_id, "announcement_created", serde_json::json!({
+            "announcement_id": a.announcement_id,
+            "title": a.title,
+            "description": a.description,
+            "attachment": a.attachment
+        }), false);
+        db.insert_class_notification(&a.class_id, "announcement", &a.title, &a.description).await;
+        mailer.notify_announcement(db, &a.class_id, &a.title, &a.description).await;
+    }
+}
+
+// The unlock_units() function is used to notify the class's
+// students of every unit whose unlock date has passed.
+async fn unlock_units(db: &Database, events: &Events) {
+    for u in db.unlock_due_units().await {
+        events.publish(&u.class_id, "unit_updated", serde_json::json!({ "unit_id": u.unit_id }), false);
+        db.insert_class_notification(&u.class_id, "unit_unlocked", &u.unit_name, "").await;
+    }
+}
@@ -1,7 +1,10 @@
+use std::collections::HashMap;
+
 
 // The User data struct is used to store
 // all of the users data from the database
 // into readable values
+#[allow(dead_code)]
 pub struct User {
     // Row Increment ID
     pub id: i64,
@@ -27,7 +30,9 @@ pub struct Class {
     // Unique class owner identifier
     pub owner_id: String,
     // Whether to the use the class whitelist
-    pub enable_whitelist: i64
+    pub enable_whitelist: i64,
+    // Whether the class has been archived
+    pub archived: i64
 }
 
 // The Announcement data struct is used to
@@ -35,20 +40,86 @@ pub struct Class {
 // the authors name, the announcement title and description,
 // along with any attachments the author has posted with it.
 pub struct Announcement {
-    // The announcement's author name
+    // The unique announcement identifier
+    pub announcement_id: String,
+    // The user id of the announcement's author
+    pub author_id: String,
+    // The announcement author's current name
     pub author_name: String,
     // The announcement title
     pub title: String,
     // The announcements content
     pub description: String,
     // Any images/videos attached with the announcement
-    pub attachment: String // Base64 encode images, etc.
+    pub attachment: String, // Base64 encode images, etc.
+    // When the announcement becomes visible to students
+    pub publish_at: i64,
+    // Whether the scheduler has published the announcement
+    pub published: i64,
+    // When the announcement was last edited (0 if never)
+    pub edited_at: i64,
+    // Whether the announcement stays on top of the others
+    pub pinned: i64,
+    // Whether students can no longer comment on the announcement
+    pub locked: i64,
+    // The amount of comments under the announcement
+    pub comment_count: i64
+}
+
+// The AnnouncementEdit data struct is used to store
+// a previous version of an announcement. A new edit
+// is stored every time the announcement is changed.
+pub struct AnnouncementEdit {
+    // The previous announcement title
+    pub title: String,
+    // The previous announcement content
+    pub description: String,
+    // The previous announcement attachment
+    pub attachment: String,
+    // When the announcement was changed from this version
+    pub edited_at: i64
+}
+
+// The Comment data struct is used to store a single
+// comment under an announcement. Replies store the
+// comment_id of the comment they're replying to.
+pub struct Comment {
+    // The unique comment identifier
+    pub comment_id: String,
+    // The comment being replied to (empty if none)
+    pub parent_id: String,
+    // The user id of the comment author
+    pub user_id: String,
+    // The current name of the comment author
+    pub user_name: String,
+    // The comment content
+    pub content: String,
+    // When the comment was posted
+    pub date: i64
+}
+
+// The ScheduledAnnouncement data struct is used for
+// querying the scheduled announcements that have come
+// due so that the class's students can be notified.
+pub struct ScheduledAnnouncement {
+    // The unique class identifier
+    pub class_id: String,
+    // The unique announcement identifier
+    pub announcement_id: String,
+    // The announcement title
+    pub title: String,
+    // The announcements content
+    pub description: String,
+    // Any images/videos attached with the announcement
+    pub attachment: String
 }
 
 // The Lesson data struct is used to store
 // the class unit's lesson title, description,
-// video_url, work and work_solutions.
+// video_url, work, work_solutions and due date.
 pub struct Lesson {
+    // The unique lesson identifier
+    pub lesson_id: String,
     // The Lesson Title
     pub title: String,
     // The Lesson Description
@@ -58,8 +129,123 @@ pub struct Lesson {
     // The Lesson Homework that can be
     // submitted and marked
     pub work: String,
+    // The kind of homework ("text" or "quiz")
+    pub work_type: String,
     // The Lesson Homework Solutions
-    pub work_solutions: String
+    pub work_solutions: String,
+    // When the Lesson Homework is due (0 for no due date)
+    pub due_date: i64,
+    // When the solutions are shown to students
+    // ("due_date", "submitted" or "manual")
+    pub solutions_release: String,
+    // Whether the teacher has released the solutions
+    pub solutions_released: i64,
+    // When the lesson's work can first be opened (0 for always)
+    pub opens_at: i64,
+    // When the lesson's work stops accepting submissions (0 for never)
+    pub closes_at: i64,
+    // How many seconds a student has to finish the work
+    // once they start it (0 for no time limit)
+    pub time_limit: i64,
+    // The grade category the lesson's work counts towards
+    pub category_id: String,
+    // How many times a student can submit the lesson's quiz
+    pub max_attempts: i64
+}
+
+// The LessonProgress data struct is used to store
+// how far a student has gotten through a lesson.
+pub struct LessonProgress {
+    // The unique lesson identifier
+    pub lesson_id: String,
+    // The student's user id
+    pub user_id: String,
+    // Whether the student has completed the lesson
+    pub completed: i64,
+    // How much of the lesson video the student has watched (0-100)
+    pub video_progress: f64
+}
+
+// The Prerequisite data struct is used to store a rule
+// that a student must meet before they can open a lesson.
+pub struct Prerequisite {
+    // The lesson the rule belongs to
+    pub lesson_id: String,
+    // The lesson the student must get through first
+    pub required_lesson_id: String,
+    // The minimum score (percentage) the student needs on the
+    // required lesson's work. If none, the student only needs
+    // to complete the required lesson.
+    pub min_score: Option<f64>
+}
+
+// The StudentProgress data struct is used to store everything
+// about a student's progress through a class. This is used to
+// decide which lessons and solutions the student can see.
+pub struct StudentProgress {
+    // How many times the student has submitted work for each lesson
+    pub submitted: HashMap<String, i64>,
+    // How far the student has gotten through each lesson
+    pub lessons: HashMap<String, LessonProgress>,
+    // The student's best score (percentage) on each lesson's work
+    pub scores: HashMap<String, f64>,
+    // The prerequisites of each lesson within the class
+    pub prerequisites: HashMap<String, Vec<Prerequisite>>,
+    // The student's attempts at each timed lesson
+    pub attempts: HashMap<String, ExamAttempt>,
+    // The extensions the student has been given on each lesson
+    pub extensions: HashMap<String, Extension>
+}
+
+// The ExamAttempt data struct is used to store when
+// a student started a timed lesson, and whether
+// they've already submitted it.
+pub struct ExamAttempt {
+    // The unique lesson identifier
+    pub lesson_id: String,
+    // When the student started the lesson
+    pub started_at: i64,
+    // When the student submitted the lesson (0 if they haven't)
+    pub submitted_at: i64
+}
+
+// The Extension data struct is used to store the
+// accommodations a student has been given on a lesson.
+pub struct Extension {
+    // The unique lesson identifier
+    pub lesson_id: String,
+    // The student's user id
+    pub user_id: String,
+    // How many extra seconds the student is given
+    pub extra_time: i64,
+    // The student's own due date (None to use the lesson's)
+    pub due_date: Option<i64>,
+    // The student's own closing time (None to use the lesson's)
+    pub closes_at: Option<i64>
+}
+
+// The BankQuestion data struct is used to store a
+// question within a class's question bank. Quizzes
+// draw their questions from the bank by unit.
+pub struct BankQuestion {
+    // The unique question identifier
+    pub question_id: String,
+    // The unit the question is tagged with
+    pub unit_id: String,
+    // The question's type, prompt, options and points (json)
+    pub question: String,
+    // The question's answer (json)
+    pub answer: String
+}
+
+// The QuizDraw data struct is used to store the questions
+// a student was given for a quiz, exactly as they were
+// shown, along with the answer key for those questions.
+pub struct QuizDraw {
+    // The drawn questions in the order they were shown (json)
+    pub questions: String,
+    // The answer key for the drawn questions (json)
+    pub answers: String
 }
 
 // The Unit data struct is used to store
@@ -72,7 +258,23 @@ pub struct Unit {
     // The Unit's Name
     pub unit_name: String,
     // Whether students can access this unit yet
-    pub locked: i64
+    pub locked: i64,
+    // When the unit unlocks for students (0 if never)
+    pub unlock_at: i64,
+    // When the unit locks again for students (0 if never)
+    pub lock_at: i64
+}
+
+// The UnlockedUnit data struct is used for querying
+// the units whose unlock date has passed so that the
+// class's students can be notified.
+pub struct UnlockedUnit {
+    // The unique class identifier
+    pub class_id: String,
+    // The unique unit identifier
+    pub unit_id: String,
+    // The Unit's Name
+    pub unit_name: String
 }
 
 // The Whitelist data struct is used for querying
@@ -92,8 +294,131 @@ pub struct Submission {
     pub submitter_bearer: String,
     // The unique identifier of the submission
     pub submission_id: String,
+    // The lesson the work was submitted for (empty if none)
+    pub lesson_id: String,
     // The date the work was submitted
     pub submission_date: i64,
     // The submission data. (ex: the file, the answers, etc.)
-    pub data: String
+    pub data: String,
+    // The grade category the submission counts towards
+    pub category_id: String,
+    // Whether the teacher has marked the submission yet
+    pub graded: i64,
+    // The mark the teacher gave the submission
+    pub mark: f64,
+    // The maximum mark the submission could receive
+    pub out_of: f64,
+    // Whether the work was submitted after the student's due date
+    pub late: i64
+}
+
+// The GradeCategory data struct is used to store
+// a class's weighted grade categories. (ex: homework 30%,
+// tests 70%) Along with the weight, each category can
+// drop a number of the student's lowest marks.
+pub struct GradeCategory {
+    // The unique category identifier
+    pub category_id: String,
+    // The category name
+    pub category_name: String,
+    // How much the category counts towards the final grade
+    pub weight: f64,
+    // The amount of lowest marks to ignore
+    pub drop_lowest: i64
+}
+
+// The Grade data struct is used for querying
+// the marked submissions within a class so that
+// each student's running average can be computed.
+pub struct Grade {
+    // The user who submitted the work's unique identifier
+    pub submitter_bearer: String,
+    // The lesson the work was submitted for
+    pub lesson_id: String,
+    // The grade category the submission counts towards
+    pub category_id: String,
+    // The mark the teacher gave the submission
+    pub mark: f64,
+    // The maximum mark the submission could receive
+    pub out_of: f64
+}
+
+// The Recipient data struct is used to store
+// the user data required for sending an user
+// an email notification.
+pub struct Recipient {
+    // The unique user identifier
+    pub user_id: String,
+    // The users name
+    pub user_name: String,
+    // The users email
+    pub email: String
+}
+
+// The NotificationPreferences data struct is used
+// to store which email notifications an user
+// wants to receive.
+pub struct NotificationPreferences {
+    // Whether to email the user when their work is marked
+    pub email_grades: i64,
+    // Whether to email the user when an announcement is posted
+    pub email_announcements: i64,
+    // Whether to email the user when a deadline is approaching
+    pub email_deadlines: i64
+}
+
+// The DueLesson data struct is used for querying
+// the lessons whose due date is approaching so
+// that the class's students can be reminded.
+pub struct DueLesson {
+    // The unique lesson identifier
+    pub lesson_id: String,
+    // The Lesson Title
+    pub title: String,
+    // When the Lesson Homework is due
+    pub due_date: i64,
+    // The unique class identifier
+    pub class_id: String,
+    // The Class Name
+    pub class_name: String
+}
+
+// The DueExtension data struct is used for querying
+// the students whose extended due date is approaching,
+// so they can be reminded of their own deadline.
+pub struct DueExtension {
+    // The unique lesson identifier
+    pub lesson_id: String,
+    // The Lesson Title
+    pub title: String,
+    // When the student's work is due
+    pub due_date: i64,
+    // The Class Name
+    pub class_name: String,
+    // The student's user id
+    pub user_id: String,
+    // The student's name
+    pub user_name: String,
+    // The student's email
+    pub email: String
+}
+
+// The Notification data struct is used to store
+// an in-app notification for an user. (ex: a new
+// announcement, an unlocked unit, marked work)
+pub struct Notification {
+    // The unique notification identifier
+    pub notification_id: String,
+    // The class the notification came from
+    pub class_id: String,
+    // The type of notification (announcement, unit_unlocked, graded)
+    pub kind: String,
+    // The notification title
+    pub title: String,
+    // The notification content
+    pub description: String,
+    // The date the notification was created
+    pub date: i64,
+    // Whether the user has read the notification
+    pub read: i64
 }
@@ -17,3 +17,21 @@ pub fn generate_new_id(identifier: &str) -> String {
         "{}:{}", identifier, get_time().as_nanos()
     ));
 }
+
+// The get_ids() function is used to get the array of
+// ids under the provided key of the request body. None is
+// returned if any of the ids aren't a string.
+pub fn get_ids<'a>(data: &'a serde_json::Value, key: &str) -> Option<Vec<&'a str>> {
+    return data[key].as_array()?.iter().map(|id| id.as_str()).collect();
+}
+
+// The same_ids() function is used to check whether the
+// provided ids contain every current id exactly once. This
+// is used to validate a new ordering of units or lessons.
+pub fn same_ids(current: &[String], ids: &[&str]) -> bool {
+    let mut current: Vec<&str> = current.iter().map(|id| id.as_str()).collect();
+    let mut ids: Vec<&str> = ids.to_vec();
+    current.sort_unstable();
+    ids.sort_unstable();
+    return current == ids;
+}
@@ -1,7 +1,12 @@
+// The codebase returns explicitly at the end of functions, and
+// matches on query results rather than converting them
+#![allow(clippy::needless_return, clippy::manual_ok_err, clippy::manual_unwrap_or_default, clippy::single_match)]
+// The library's modules live under src/lib
+#![allow(special_module_name)]
+
 mod lib;
 use actix_web::{web::Data, App, HttpServer};
-use lib::{endpoints, handlers::Database};
-use actix_ratelimit::{MemoryStore, MemoryStoreActor, RateLimiter};
+use lib::{endpoints, events::Events, handlers::Database, mail::Mailer, quotas::Quotas, ratelimit::RateLimits};
 
 // Main Actix-Web function
 #[actix_web::main]
@@ -12,29 +17,77 @@ async fn main() -> std::io::Result<()> {
     let _ = db.insert_test_user().await;
     // db.insert_test_class().await;
 
+    // Establish the email transport and the class event streams
+    let mailer: Mailer = Mailer::init();
+    let events: Events = Events::default();
+
+    // Load the quotas on how much each user can create
+    let quotas: Quotas = Quotas::init();
+
+    // Establish the rate limits shared by every worker
+    let limits: RateLimits = RateLimits::default();
+
+    // Start the background scheduler for time based work
+    lib::scheduler::start(db.clone(), mailer.clone(), events.clone());
+
     // Establish a connection to http://127.0.0.1:8080/
-    let store = MemoryStore::new();
     HttpServer::new(move || {
         App::new()
+            // Archived classes are read-only
+            .wrap(lib::middleware::ArchivedClasses)
+            // Requests are rate limited by user, or by ip address
+            .wrap(lib::middleware::RateLimiting(limits.clone()))
             .wrap(actix_cors::Cors::permissive())
-            .wrap(
-                RateLimiter::new(MemoryStoreActor::from(store.clone()).start())
-                    .with_interval(std::time::Duration::from_secs(60))
-                    .with_max_requests(10),
-            )
             .app_data(Data::new(db.clone()))
+            .app_data(Data::new(mailer.clone()))
+            .app_data(Data::new(events.clone()))
+            .app_data(Data::new(quotas.clone()))
             // User data
             .service(endpoints::users::get_user_data)
             .service(endpoints::users::update_user_data)
             .service(endpoints::users::insert_user_data)
-            // Class data
+            .service(endpoints::users::get_user_preferences)
+            .service(endpoints::users::update_user_preferences)
+            // User Notifications
+            .service(endpoints::notifications::get_user_notifications)
+            .service(endpoints::notifications::read_user_notifications)
+            // Class data. The import route is registered before
+            // the {class_id} routes so it isn't matched as an id
+            .service(endpoints::bundles::import_class_bundle)
+            .service(endpoints::bundles::import_course_archive)
+            .service(endpoints::bundles::import_classroom_takeout)
             .service(endpoints::classes::update_class_data)
             .service(endpoints::classes::get_class_data)
             .service(endpoints::classes::insert_class_data)
+            .service(endpoints::classes::clone_class_data)
+            .service(endpoints::classes::archive_class_data)
+            .service(endpoints::classes::restore_class_data)
+            .service(endpoints::classes::get_deletion_token)
+            .service(endpoints::classes::delete_class_data)
+            .service(endpoints::bundles::export_class_bundle)
+            .service(endpoints::events::get_class_events)
+            .service(endpoints::events::create_events_token)
             // Class Units
             .service(endpoints::units::insert_class_unit)
+            // The order routes are registered before the {unit_id}
+            // and {lesson_id} routes so they aren't matched as ids
+            .service(endpoints::units::reorder_class_units)
             .service(endpoints::units::delete_class_unit)
             .service(endpoints::units::update_class_unit)
+            // Unit Lessons
+            .service(endpoints::lessons::insert_unit_lesson)
+            .service(endpoints::lessons::reorder_unit_lessons)
+            .service(endpoints::lessons::move_unit_lesson)
+            .service(endpoints::lessons::update_unit_lesson)
+            .service(endpoints::lessons::delete_unit_lesson)
+            // Lesson Progress
+            .service(endpoints::progress::update_lesson_progress)
+            .service(endpoints::progress::get_class_progress)
+            // Timed Lessons
+            .service(endpoints::exams::start_exam)
+            .service(endpoints::extensions::get_lesson_extensions)
+            .service(endpoints::extensions::set_lesson_extension)
+            .service(endpoints::extensions::delete_lesson_extension)
             // Class Whitelist
             .service(endpoints::whitelist::remove_user_from_whitelist)
             .service(endpoints::whitelist::add_user_to_whitelist)
@@ -43,9 +96,30 @@ async fn main() -> std::io::Result<()> {
             .service(endpoints::submissions::insert_class_submission)
             .service(endpoints::submissions::get_user_submissions)
             .service(endpoints::submissions::get_class_submissions)
+            .service(endpoints::submissions::grade_class_submission)
+            .service(endpoints::submissions::submit_lesson_quiz)
+            // Question Banks
+            .service(endpoints::questions::get_class_questions)
+            .service(endpoints::questions::insert_class_question)
+            .service(endpoints::questions::update_class_question)
+            .service(endpoints::questions::delete_class_question)
+            .service(endpoints::questions::get_quiz_draw)
+            // Class Grades
+            .service(endpoints::grades::get_class_grades)
+            .service(endpoints::grades::get_user_grades)
+            .service(endpoints::grades::insert_grade_category)
+            .service(endpoints::grades::update_grade_category)
+            .service(endpoints::grades::delete_grade_category)
             // Class Announcements
             .service(endpoints::announcements::insert_class_announcement)
             .service(endpoints::announcements::delete_class_announcement)
+            .service(endpoints::announcements::update_class_announcement)
+            .service(endpoints::announcements::get_announcement_history)
+            // Announcement Comments
+            .service(endpoints::comments::get_announcement_comments)
+            .service(endpoints::comments::insert_announcement_comment)
+            .service(endpoints::comments::lock_announcement_comments)
+            .service(endpoints::comments::delete_announcement_comment)
             // Trim path trailing slashes
             .wrap(actix_web::middleware::NormalizePath::trim())
     })
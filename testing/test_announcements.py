@@ -0,0 +1,99 @@
+import requests, time, hashlib
+
+# // Constant Variables
+# // BEARER: str -> The class owner's bearer
+BEARER: str = "822f3d5b9c91b570a4f1848c5d147b4709d2fb96"
+# // SUPER_SECRET_CODE: str -> Secret Code for Preventing Abuse
+SUPER_SECRET_CODE: str = "SUPER_SECRET_CODE"
+# // CLASS_ID: str -> A class owned by the bearer's user
+CLASS_ID: str = ""
+# // API_URL: str -> Where the api is running
+API_URL: str = "http://127.0.0.1:8080"
+
+# // Function used for SHA256 encryption
+def sha256_encode(v: str) -> str:
+    return hashlib.sha256(v.encode('utf-8')).hexdigest()
+
+# // Function used to send a request to the api. Access tokens can
+# // only be used once, so wait for a new one before each request
+def send(method: str, path: str, body: dict = None) -> requests.Response:
+    time.sleep(1)
+    access_token: str = sha256_encode(f"{BEARER}:{int(time.time())}:{SUPER_SECRET_CODE}")
+    start_time = time.time()
+    r = requests.request(method, f"{API_URL}{path}", json=body, headers={
+        "authorization": BEARER,
+        "access_token": access_token
+    })
+    print(f" >> {method} {path}: {time.time()-start_time} -> {r.text}")
+    return r
+
+# // Test the create announcement endpoint, both
+# // right away and scheduled for an hour from now
+def test_create_announcements() -> str:
+    r = send("PUT", f"/class/{CLASS_ID}/announcements", {
+        "title": "Welcome",
+        "description": "Welcome to the class!",
+        "attachment": ""
+    })
+    send("PUT", f"/class/{CLASS_ID}/announcements", {
+        "title": "Reminder",
+        "description": "The first quiz is tomorrow",
+        "attachment": "",
+        "publish_at": int(time.time()) + 3600
+    })
+    return r.json().get("announcement_id", "")
+
+# // Test the update announcement and edit history endpoints
+def test_update_announcement(announcement_id: str):
+    send("POST", f"/class/{CLASS_ID}/announcements/{announcement_id}", {
+        "title": "Welcome!",
+        "description": "Welcome to the class, read the syllabus first",
+        "attachment": "",
+        "pinned": True
+    })
+    send("GET", f"/class/{CLASS_ID}/announcements/{announcement_id}/history")
+    # {
+        # "response": [
+            # { "title": "Welcome", "description": "Welcome to the class!", "attachment": "", "edited_at": 0 }
+        # ]
+    # }
+
+# // Test the comment endpoints
+def test_comments(announcement_id: str):
+    path: str = f"/class/{CLASS_ID}/announcements/{announcement_id}/comments"
+    r = send("PUT", path, {"content": "Thanks!"})
+    comment_id: str = r.json().get("comment_id", "")
+    send("PUT", path, {"content": "You're welcome", "parent_id": comment_id})
+    send("GET", path)
+
+    # // Only the class owner can comment while they're locked
+    send("POST", f"{path}/lock", {"locked": True})
+    send("PUT", path, {"content": "Comments are closed"})
+    send("POST", f"{path}/lock", {"locked": False})
+
+    # // Deleting a comment also deletes it's replies
+    send("DELETE", f"{path}/{comment_id}")
+    send("GET", path)
+
+# // Test the event stream token endpoint
+def test_stream_token():
+    r = send("POST", f"/class/{CLASS_ID}/events/token")
+    token: str = r.json().get("token", "")
+
+    # // Open the stream and read the connected event
+    r = requests.get(f"{API_URL}/class/{CLASS_ID}/events?token={token}", stream=True, timeout=5)
+    print(f" >> Stream: {r.status_code} -> {next(r.iter_lines(), b'').decode()}")
+    r.close()
+
+# // Test the delete announcement endpoint
+def test_delete_announcement(announcement_id: str):
+    send("DELETE", f"/class/{CLASS_ID}/announcements/{announcement_id}")
+
+
+# // Run the test functions
+if __name__ == "__main__":
+    announcement_id: str = test_create_announcements()
+    test_update_announcement(announcement_id)
+    test_comments(announcement_id)
+    test_stream_token()
+    test_delete_announcement(announcement_id)
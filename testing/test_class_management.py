@@ -0,0 +1,82 @@
+import requests, time, hashlib, sys
+
+# // Constant Variables
+# // BEARER: str -> The class owner's bearer
+BEARER: str = "822f3d5b9c91b570a4f1848c5d147b4709d2fb96"
+# // SUPER_SECRET_CODE: str -> Secret Code for Preventing Abuse
+SUPER_SECRET_CODE: str = "SUPER_SECRET_CODE"
+# // CLASS_ID: str -> A class owned by the bearer's user
+CLASS_ID: str = ""
+# // API_URL: str -> Where the api is running
+API_URL: str = "http://127.0.0.1:8080"
+
+# // Function used for SHA256 encryption
+def sha256_encode(v: str) -> str:
+    return hashlib.sha256(v.encode('utf-8')).hexdigest()
+
+# // Function used to create the auth headers. Access tokens can
+# // only be used once, so wait for a new one before each request
+def auth_headers() -> dict:
+    time.sleep(1)
+    access_token: str = sha256_encode(f"{BEARER}:{int(time.time())}:{SUPER_SECRET_CODE}")
+    return {"authorization": BEARER, "access_token": access_token}
+
+# // Function used to send a request to the api
+def send(method: str, path: str, body: dict = None) -> requests.Response:
+    start_time = time.time()
+    r = requests.request(method, f"{API_URL}{path}", json=body, headers=auth_headers())
+    print(f" >> {method} {path}: {time.time()-start_time} -> {r.text[:200]}")
+    return r
+
+# // Test the clone class endpoint
+def test_clone() -> str:
+    r = send("POST", f"/class/{CLASS_ID}/clone", {
+        "class_name": "Next Term",
+        "include_announcements": True
+    })
+    # {
+        # "response": "Class cloned",
+        # "class_id": "..."
+    # }
+    return r.json().get("class_id", "")
+
+# // Test the export and import class endpoints
+def test_export_import() -> str:
+    r = send("GET", f"/class/{CLASS_ID}/export?submissions=true")
+    r = send("PUT", "/class/import", r.json().get("response", {}))
+    return r.json().get("class_id", "")
+
+# // Test the course archive and takeout import endpoints
+# // with the files provided on the command line
+def test_archive_imports(paths: list):
+    for path in paths:
+        with open(path, "rb") as f:
+            data: bytes = f.read()
+        route: str = "/class/import/takeout?dry_run=true" if path.endswith(".zip") else "/class/import/archive"
+        start_time = time.time()
+        r = requests.put(f"{API_URL}{route}", data=data, headers=auth_headers())
+        print(f" >> PUT {route}: {time.time()-start_time} -> {r.text[:200]}")
+
+# // Test the archive and restore class endpoints
+def test_archive(class_id: str):
+    send("POST", f"/class/{class_id}/archive")
+
+    # // Archived classes are read-only
+    send("PUT", f"/class/{class_id}/units", {"unit_name": "Unit"})
+    send("POST", f"/class/{class_id}/restore")
+
+# // Test the delete class endpoint
+def test_delete(class_id: str):
+    r = send("GET", f"/class/{class_id}/deletion_token")
+    send("DELETE", f"/class/{class_id}", {"confirmation_token": r.json().get("response", "")})
+
+
+# // Run the test functions. Course archives (.imscc, .mbz)
+# // and takeout archives (.zip) can be passed as arguments
+if __name__ == "__main__":
+    cloned_id: str = test_clone()
+    imported_id: str = test_export_import()
+    test_archive_imports(sys.argv[1:])
+    test_archive(cloned_id)
+    test_delete(cloned_id)
+    test_delete(imported_id)
@@ -0,0 +1,88 @@
+import requests, time, hashlib
+
+# // Constant Variables
+# // BEARER: str -> The class owner's bearer
+BEARER: str = "822f3d5b9c91b570a4f1848c5d147b4709d2fb96"
+# // SUPER_SECRET_CODE: str -> Secret Code for Preventing Abuse
+SUPER_SECRET_CODE: str = "SUPER_SECRET_CODE"
+# // CLASS_ID: str -> A class owned by the bearer's user
+CLASS_ID: str = ""
+# // SUBMISSION_ID: str -> A submission within the class to grade (optional)
+SUBMISSION_ID: str = ""
+# // API_URL: str -> Where the api is running
+API_URL: str = "http://127.0.0.1:8080"
+
+# // Function used for SHA256 encryption
+def sha256_encode(v: str) -> str:
+    return hashlib.sha256(v.encode('utf-8')).hexdigest()
+
+# // Function used to send a request to the api. Access tokens can
+# // only be used once, so wait for a new one before each request
+def send(method: str, path: str, body: dict = None) -> requests.Response:
+    time.sleep(1)
+    access_token: str = sha256_encode(f"{BEARER}:{int(time.time())}:{SUPER_SECRET_CODE}")
+    start_time = time.time()
+    r = requests.request(method, f"{API_URL}{path}", json=body, headers={
+        "authorization": BEARER,
+        "access_token": access_token
+    })
+    print(f" >> {method} {path}: {time.time()-start_time} -> {r.text}")
+    return r
+
+# // Test the create grade category endpoint
+def test_create_category() -> str:
+    r = send("PUT", f"/class/{CLASS_ID}/grades/categories", {
+        "category_name": "Quizzes",
+        "weight": 40,
+        "drop_lowest": 1
+    })
+    # {
+        # "response": "Grade category created",
+        # "category_id": "..."
+    # }
+    return r.json().get("category_id", "")
+
+# // Test the update grade category endpoint
+def test_update_category(category_id: str):
+    send("POST", f"/class/{CLASS_ID}/grades/categories/{category_id}", {
+        "category_name": "Quizzes",
+        "weight": 60,
+        "drop_lowest": 0
+    })
+
+# // Test the grade submission endpoint with a category
+def test_grade_submission(category_id: str):
+    send("POST", f"/class/{CLASS_ID}/submissions/{SUBMISSION_ID}", {
+        "mark": 8,
+        "out_of": 10,
+        "category_id": category_id
+    })
+
+# // Test the class grades endpoint
+def test_get_grades():
+    send("GET", f"/class/{CLASS_ID}/grades")
+    # {
+        # "response": {
+            # "class_average": 80.0,
+            # "students": [...]
+        # }
+    # }
+
+# // Test the student grades endpoint
+def test_get_student_grades():
+    send("GET", f"/class/{CLASS_ID}/student/grades")
+
+# // Test the delete grade category endpoint
+def test_delete_category(category_id: str):
+    send("DELETE", f"/class/{CLASS_ID}/grades/categories/{category_id}")
+
+
+# // Run the test functions
+if __name__ == "__main__":
+    category_id: str = test_create_category()
+    test_update_category(category_id)
+    if SUBMISSION_ID:
+        test_grade_submission(category_id)
+    test_get_grades()
+    test_get_student_grades()
+    test_delete_category(category_id)
@@ -0,0 +1,109 @@
+import requests, time, hashlib
+
+# // Constant Variables
+# // BEARER: str -> The class owner's bearer
+BEARER: str = "822f3d5b9c91b570a4f1848c5d147b4709d2fb96"
+# // SUPER_SECRET_CODE: str -> Secret Code for Preventing Abuse
+SUPER_SECRET_CODE: str = "SUPER_SECRET_CODE"
+# // CLASS_ID: str -> A class owned by the bearer's user
+CLASS_ID: str = ""
+# // STUDENT_ID: str -> A whitelisted student to give an extension to (optional)
+STUDENT_ID: str = ""
+# // API_URL: str -> Where the api is running
+API_URL: str = "http://127.0.0.1:8080"
+
+# // Function used for SHA256 encryption
+def sha256_encode(v: str) -> str:
+    return hashlib.sha256(v.encode('utf-8')).hexdigest()
+
+# // Function used to send a request to the api. Access tokens can
+# // only be used once, so wait for a new one before each request
+def send(method: str, path: str, body: dict = None) -> requests.Response:
+    time.sleep(1)
+    access_token: str = sha256_encode(f"{BEARER}:{int(time.time())}:{SUPER_SECRET_CODE}")
+    start_time = time.time()
+    r = requests.request(method, f"{API_URL}{path}", json=body, headers={
+        "authorization": BEARER,
+        "access_token": access_token
+    })
+    print(f" >> {method} {path}: {time.time()-start_time} -> {r.text}")
+    return r
+
+# // Test the create unit and reorder units endpoints
+def test_units() -> list:
+    first: str = send("PUT", f"/class/{CLASS_ID}/units", {"unit_name": "Unit 1"}).json().get("unit_id", "")
+    second: str = send("PUT", f"/class/{CLASS_ID}/units", {"unit_name": "Unit 2"}).json().get("unit_id", "")
+
+    # // Lock the second unit until tomorrow
+    send("POST", f"/class/{CLASS_ID}/units/{second}", {
+        "unit_name": "Unit 2",
+        "locked": False,
+        "unlock_at": int(time.time()) + 86400,
+        "lock_at": 0
+    })
+    send("POST", f"/class/{CLASS_ID}/units/order", {"unit_ids": [second, first]})
+    return [first, second]
+
+# // Test the create lesson endpoint with a prerequisite
+def test_create_lessons(unit_id: str) -> list:
+    path: str = f"/class/{CLASS_ID}/units/{unit_id}/lessons"
+    first: str = send("PUT", path, {
+        "title": "Reading",
+        "description": "Read the first chapter",
+        "work": "Summarize the chapter",
+        "due_date": int(time.time()) + 86400
+    }).json().get("lesson_id", "")
+    second: str = send("PUT", path, {
+        "title": "Timed Essay",
+        "work": "Write an essay on the chapter",
+        "time_limit": 1800,
+        "prerequisites": [{"lesson_id": first}]
+    }).json().get("lesson_id", "")
+    return [first, second]
+
+# // Test the update, reorder and move lesson endpoints
+def test_update_lessons(units: list, lessons: list):
+    path: str = f"/class/{CLASS_ID}/units/{units[0]}/lessons"
+    send("POST", f"{path}/{lessons[0]}", {"title": "Reading", "solutions_release": "manual"})
+
+    # // Prerequisites can't form a cycle
+    send("POST", f"{path}/{lessons[0]}", {"prerequisites": [{"lesson_id": lessons[1]}]})
+    send("POST", f"{path}/order", {"lesson_ids": [lessons[1], lessons[0]]})
+    send("POST", f"{path}/{lessons[1]}/move", {"unit_id": units[1], "position": 0})
+
+# // Test the lesson progress endpoints
+def test_progress(unit_id: str, lesson_id: str):
+    send("POST", f"/class/{CLASS_ID}/units/{unit_id}/lessons/{lesson_id}/progress", {
+        "completed": True,
+        "video_progress": 100
+    })
+    send("GET", f"/class/{CLASS_ID}/progress")
+
+# // Test the timed lesson and extension endpoints
+def test_extensions(unit_id: str, lesson_id: str):
+    path: str = f"/class/{CLASS_ID}/units/{unit_id}/lessons/{lesson_id}"
+    send("POST", f"{path}/start")
+    if STUDENT_ID:
+        send("POST", f"{path}/extensions/{STUDENT_ID}", {
+            "extra_time": 600,
+            "due_date": int(time.time()) + 172800
+        })
+        send("GET", f"{path}/extensions")
+        send("DELETE", f"{path}/extensions/{STUDENT_ID}")
+
+# // Test the delete lesson and delete unit endpoints
+def test_delete(units: list, lessons: list):
+    send("DELETE", f"/class/{CLASS_ID}/units/{units[0]}/lessons/{lessons[0]}")
+    send("DELETE", f"/class/{CLASS_ID}/units/{units[1]}/lessons/{lessons[1]}")
+    for unit_id in units:
+        send("DELETE", f"/class/{CLASS_ID}/units/{unit_id}")
+
+
+# // Run the test functions
+if __name__ == "__main__":
+    units: list = test_units()
+    lessons: list = test_create_lessons(units[0])
+    test_update_lessons(units, lessons)
+    test_progress(units[0], lessons[0])
+    test_extensions(units[1], lessons[1])
+    test_delete(units, lessons)
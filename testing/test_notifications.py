@@ -0,0 +1,69 @@
+import requests, time, hashlib
+
+# // Constant Variables
+# // BEARER: str -> The user's bearer
+BEARER: str = "822f3d5b9c91b570a4f1848c5d147b4709d2fb96"
+# // USER_ID: str -> The bearer's user id
+USER_ID: str = ""
+# // SUPER_SECRET_CODE: str -> Secret Code for Preventing Abuse
+SUPER_SECRET_CODE: str = "SUPER_SECRET_CODE"
+# // API_URL: str -> Where the api is running
+API_URL: str = "http://127.0.0.1:8080"
+
+# // Function used for SHA256 encryption
+def sha256_encode(v: str) -> str:
+    return hashlib.sha256(v.encode('utf-8')).hexdigest()
+
+# // Function used to send a request to the api. Access tokens can
+# // only be used once, so wait for a new one before each request
+def send(method: str, path: str, body: dict = None) -> requests.Response:
+    time.sleep(1)
+    access_token: str = sha256_encode(f"{BEARER}:{int(time.time())}:{SUPER_SECRET_CODE}")
+    start_time = time.time()
+    r = requests.request(method, f"{API_URL}{path}", json=body, headers={
+        "authorization": BEARER,
+        "access_token": access_token
+    })
+    print(f" >> {method} {path}: {time.time()-start_time} -> {r.text}")
+    return r
+
+# // Test the update notification preferences endpoint
+def test_update_preferences():
+    send("POST", f"/users/{USER_ID}/preferences", {
+        "email_grades": True,
+        "email_announcements": False,
+        "email_deadlines": True
+    })
+
+# // Test the get notification preferences endpoint
+def test_get_preferences():
+    send("GET", f"/users/{USER_ID}/preferences")
+    # {
+        # "response": {
+            # "email_grades": true,
+            # "email_announcements": false,
+            # "email_deadlines": true
+        # }
+    # }
+
+# // Test the get notifications endpoint
+def test_get_notifications() -> list:
+    r = send("GET", f"/users/{USER_ID}/notifications")
+    return [n["notification_id"] for n in r.json().get("response", [])]
+
+# // Test the mark notifications as read endpoint
+def test_mark_read(notification_ids: list):
+    # // Mark the first notification as read, then the rest
+    send("POST", f"/users/{USER_ID}/notifications/read", {
+        "notification_ids": notification_ids[:1]
+    })
+    send("POST", f"/users/{USER_ID}/notifications/read", {})
+
+
+# // Run the test functions
+if __name__ == "__main__":
+    test_update_preferences()
+    test_get_preferences()
+    notification_ids: list = test_get_notifications()
+    test_mark_read(notification_ids)
+    test_get_notifications()
@@ -0,0 +1,91 @@
+import requests, time, hashlib, json
+
+# // Constant Variables
+# // BEARER: str -> The class owner's bearer
+BEARER: str = "822f3d5b9c91b570a4f1848c5d147b4709d2fb96"
+# // STUDENT_BEARER: str -> The bearer of a student that can see the class
+STUDENT_BEARER: str = ""
+# // SUPER_SECRET_CODE: str -> Secret Code for Preventing Abuse
+SUPER_SECRET_CODE: str = "SUPER_SECRET_CODE"
+# // CLASS_ID: str -> A class owned by the bearer's user
+CLASS_ID: str = ""
+# // API_URL: str -> Where the api is running
+API_URL: str = "http://127.0.0.1:8080"
+
+# // Function used for SHA256 encryption
+def sha256_encode(v: str) -> str:
+    return hashlib.sha256(v.encode('utf-8')).hexdigest()
+
+# // Function used to send a request to the api. Access tokens can
+# // only be used once, so wait for a new one before each request
+def send(method: str, path: str, body: dict = None, bearer: str = BEARER) -> requests.Response:
+    time.sleep(1)
+    access_token: str = sha256_encode(f"{bearer}:{int(time.time())}:{SUPER_SECRET_CODE}")
+    start_time = time.time()
+    r = requests.request(method, f"{API_URL}{path}", json=body, headers={
+        "authorization": bearer,
+        "access_token": access_token
+    })
+    print(f" >> {method} {path}: {time.time()-start_time} -> {r.text}")
+    return r
+
+# // Test the question bank endpoints
+def test_question_bank(unit_id: str) -> str:
+    r = send("PUT", f"/class/{CLASS_ID}/questions", {
+        "unit_id": unit_id,
+        "question": {"type": "numeric", "prompt": "What is 6 x 7?"},
+        "answer": {"value": 42, "tolerance": 0}
+    })
+    question_id: str = r.json().get("question_id", "")
+    send("POST", f"/class/{CLASS_ID}/questions/{question_id}", {
+        "unit_id": unit_id,
+        "question": {"type": "multiple_choice", "prompt": "What is 6 x 7?", "options": ["36", "42"]},
+        "answer": 1
+    })
+    send("GET", f"/class/{CLASS_ID}/questions")
+    return question_id
+
+# // Test creating and submitting a quiz with two attempts
+def test_quiz(unit_id: str):
+    r = send("PUT", f"/class/{CLASS_ID}/units/{unit_id}/lessons", {
+        "title": "Quiz 1",
+        "work_type": "quiz",
+        "work": json.dumps({"questions": [
+            {"question_id": "q1", "type": "multiple_choice", "prompt": "2 + 2", "options": ["3", "4"]},
+            {"question_id": "q2", "type": "short_answer", "prompt": "The capital of Canada"}
+        ]}),
+        "work_solutions": json.dumps({"q1": 1, "q2": ["Ottawa"]}),
+        "solutions_release": "submitted",
+        "max_attempts": 2
+    })
+    path: str = f"/class/{CLASS_ID}/units/{unit_id}/lessons/{r.json().get('lesson_id', '')}/quiz"
+
+    # // The student's results are hidden until they've used
+    # // their last attempt, and a third attempt is rejected
+    send("POST", path, {"answers": {"q1": 0, "q2": "Toronto"}}, STUDENT_BEARER)
+    send("POST", path, {"answers": {"q1": 1, "q2": "ottawa"}}, STUDENT_BEARER)
+    send("POST", path, {"answers": {"q1": 1, "q2": "Ottawa"}}, STUDENT_BEARER)
+
+# // Test creating, drawing and submitting a bank quiz
+def test_bank_quiz(unit_id: str, question_id: str):
+    r = send("PUT", f"/class/{CLASS_ID}/units/{unit_id}/lessons", {
+        "title": "Bank Quiz",
+        "work_type": "bank",
+        "work": json.dumps({"draws": [{"unit_id": unit_id, "count": 1}]})
+    })
+    path: str = f"/class/{CLASS_ID}/units/{unit_id}/lessons/{r.json().get('lesson_id', '')}/quiz"
+
+    # // The options are shuffled for each student, so
+    # // find the correct answer within the student's draw
+    r = send("GET", path, None, STUDENT_BEARER)
+    options: list = r.json()["response"]["questions"][0]["options"]
+    send("POST", path, {"answers": {question_id: options.index("42")}}, STUDENT_BEARER)
+
+
+# // Run the test functions
+if __name__ == "__main__":
+    unit_id: str = send("PUT", f"/class/{CLASS_ID}/units", {"unit_name": "Quizzes"}).json().get("unit_id", "")
+    question_id: str = test_question_bank(unit_id)
+    test_quiz(unit_id)
+    test_bank_quiz(unit_id, question_id)
+    send("DELETE", f"/class/{CLASS_ID}/questions/{question_id}")